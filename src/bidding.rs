@@ -0,0 +1,258 @@
+//! Estimates how many tricks a hand of cards will take, for bidding.
+//!
+//! The estimate is the sum of the chance that each card wins a trick:
+//! - A card wins its suit when no higher card of the suit was dealt to an
+//!   opponent. The chance of that falls with the number of higher cards still
+//!   unseen, and with how many of the unseen cards the opponents hold, so it
+//!   depends on the hand size and the number of players.
+//! - A card below unseen higher cards may still win once they fall, if the hand
+//!   holds enough lower cards of the suit to give up in the meantime.
+//! - A card outside trump wins only if its suit is led while it is held, which
+//!   is likelier the more tricks there are and the fewer players there are to
+//!   lead. It may also be ruffed by an opponent out of the suit, which is likelier
+//!   in small hands and with each round of the suit played before it.
+//! - Low trumps win by ruffing suits the hand is short in, or by outlasting the
+//!   trumps the opponents are expected to hold.
+//!
+//! [hand_strength] returns the estimate and [estimate_bid] rounds it to a bid.
+//!
+//! # Examples
+//! ```
+//! use contract_whist::bidding::estimate_bid;
+//! use contract_whist::{Card, GameView, PlayerId};
+//!
+//! let cards: Vec<Card> = ["AS", "AD", "AC", "7S"].iter().map(|e| e.parse().unwrap()).collect();
+//! let view = GameView::new(PlayerId::new(0), 3, "2H".parse().unwrap(), cards.len());
+//!
+//! // In so short a hand, the opponents are often out of a suit and may ruff an ace
+//! assert_eq!(estimate_bid(&view, &cards), 2);
+//! ```
+
+use crate::card::Card;
+use crate::card_set::CardSet;
+use crate::suit::Suit;
+use crate::view::GameView;
+
+/// The chance a card below unseen higher cards wins once they fall.
+const GUARDED: f64 = 0.4;
+/// The chance an opponent out of a suit ruffs it rather than throwing a card away.
+const WILL_RUFF: f64 = 0.5;
+/// The chance a low trump wins by ruffing a short suit.
+const RUFF_WIN: f64 = 0.6;
+/// The chance a low trump wins by outlasting the opponents' trumps.
+const LONG_TRUMP: f64 = 0.5;
+
+/// Returns the number of tricks `cards` are expected to take in the hand seen by
+/// `view`, between 0 and the number of cards.
+pub fn hand_strength(view: &GameView, cards: &[Card]) -> f64 {
+    let held: CardSet = cards.iter().collect();
+    let trump = view.get_trump_suit();
+    let num_opponents = view.get_num_players().saturating_sub(1);
+
+    // The turned up trump is known, so every other card is unseen. Each unseen card
+    // is equally likely to be held by an opponent or left in the deck.
+    let mut unseen = !held;
+    unseen.remove(view.get_trump());
+    let dealt = match unseen.is_empty() {
+        true => 0.0,
+        false => (num_opponents * held.len()) as f64 / unseen.len() as f64,
+    };
+    let dealt = dealt.min(1.0);
+
+    let odds = Odds {
+        num_cards: held.len(),
+        num_players: view.get_num_players(),
+        dealt,
+        has_trump: 1.0 - (1.0 - share(unseen.in_suit(trump), unseen)).powi(held.len() as i32),
+    };
+
+    let mut strength = 0.0;
+    for suit in Suit::VALUES.into_iter().filter(|e| *e != trump) {
+        let suit_share = share(unseen.in_suit(suit), unseen);
+        strength += suit_strength(held.in_suit(suit), unseen.in_suit(suit), suit_share, &odds);
+    }
+    strength += trump_strength(held, unseen.in_suit(trump), trump, &odds);
+
+    strength.clamp(0.0, held.len() as f64)
+}
+
+/// Returns the bid for `cards` in the hand seen by `view`, which is their
+/// [hand_strength] rounded to the nearest trick.
+pub fn estimate_bid(view: &GameView, cards: &[Card]) -> isize {
+    let bid = hand_strength(view, cards).round() as isize;
+    bid.clamp(0, view.get_num_tricks() as isize)
+}
+
+/// The chances shared by every card in a hand.
+struct Odds {
+    num_cards: usize,
+    num_players: usize,
+    /// The chance each unseen card was dealt to an opponent.
+    dealt: f64,
+    /// The chance an opponent holds a trump.
+    has_trump: f64,
+}
+
+/// Returns the share of the unseen cards that are in `cards`.
+fn share(cards: CardSet, unseen: CardSet) -> f64 {
+    match unseen.is_empty() {
+        true => 0.0,
+        false => cards.len() as f64 / unseen.len() as f64,
+    }
+}
+
+/// Returns the chance of at most `most` successes in `trials` trials, each with
+/// the chance `p`.
+fn at_most(trials: usize, p: f64, most: usize) -> f64 {
+    if p >= 1.0 {
+        return f64::from(u8::from(most >= trials));
+    }
+    let mut term = (1.0 - p).powi(trials as i32);
+    let mut total = term;
+    for k in 0..most.min(trials) {
+        term *= (trials - k) as f64 / (k + 1) as f64 * p / (1.0 - p);
+        total += term;
+    }
+    total.min(1.0)
+}
+
+/// Returns the chance that no unseen card above `card` was dealt to an opponent.
+fn chance_of_top(card: Card, unseen: CardSet, dealt: f64) -> f64 {
+    let higher = unseen.iter().filter(|e| e.rank() > card.rank()).count();
+    (1.0 - dealt).powi(higher as i32)
+}
+
+/// Returns the tricks expected from the cards held in a suit other than trump,
+/// which make up `suit_share` of the unseen cards.
+fn suit_strength(held: CardSet, unseen: CardSet, suit_share: f64, odds: &Odds) -> f64 {
+    let cards: Vec<Card> = held.iter().rev().collect();
+    let num_opponents = odds.num_players.saturating_sub(1);
+
+    // The player leads some tricks, and the suit is led to the others at random
+    let leads = 1.0 / odds.num_players.max(1) as f64;
+    let led = leads + (1.0 - leads) * (1.0 - 0.75f64.powi(odds.num_cards as i32));
+
+    cards
+        .iter()
+        .enumerate()
+        .map(|(round, card)| {
+            let top = chance_of_top(*card, unseen, odds.dealt);
+            let higher = unseen.iter().filter(|e| e.rank() > card.rank()).count();
+            let lower_held = cards.len() - round - 1;
+            let chance = match higher > 0 && lower_held >= higher {
+                true => top + (1.0 - top) * GUARDED,
+                false => top,
+            };
+
+            // An opponent ruffs if they are out of the suit by this round and hold a trump
+            let ruff = at_most(odds.num_cards, suit_share, round) * odds.has_trump * WILL_RUFF;
+            let safe = (1.0 - ruff).powi(num_opponents as i32);
+            chance * led * safe
+        })
+        .sum()
+}
+
+/// Returns the tricks expected from the trumps held, including ruffs of the short
+/// suits in `held`.
+fn trump_strength(held: CardSet, unseen: CardSet, trump: Suit, odds: &Odds) -> f64 {
+    let trumps: Vec<Card> = held.in_suit(trump).iter().rev().collect();
+    if trumps.is_empty() {
+        return 0.0;
+    }
+
+    // Suits the hand is short in may be ruffed, more so in larger hands where each
+    // suit is led more often
+    let num_cards = held.len() as f64;
+    let mut ruffs: f64 = Suit::VALUES
+        .into_iter()
+        .filter(|e| *e != trump)
+        .map(|e| match held.count_suit(e) {
+            0 => 1.0,
+            1 => 0.5,
+            2 => 0.25,
+            _ => 0.0,
+        })
+        .sum::<f64>()
+        * (num_cards / 4.0).min(1.0);
+
+    // The most trumps an opponent is expected to hold
+    let per_opponent = match odds.num_players.saturating_sub(1) {
+        0 => 0.0,
+        num => unseen.len() as f64 * odds.dealt / num as f64,
+    };
+    let longest = (per_opponent * 1.5 + 0.5).ceil() as usize;
+
+    let mut strength = 0.0;
+    for (index, card) in trumps.iter().enumerate().rev() {
+        let top = chance_of_top(*card, unseen, odds.dealt);
+        if top >= RUFF_WIN {
+            strength += top;
+            continue;
+        }
+
+        let ruff = RUFF_WIN * ruffs.min(1.0);
+        ruffs = (ruffs - 1.0).max(0.0);
+        let long = match index >= longest {
+            true => LONG_TRUMP,
+            false => 0.0,
+        };
+        strength += top.max(ruff).max(long);
+    }
+    strength
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::PlayerId;
+
+    fn setup(num_players: usize, trump: &str, cards: &[&str]) -> (GameView, Vec<Card>) {
+        let cards: Vec<Card> = cards.iter().map(|e| e.parse().unwrap()).collect();
+        let view = GameView::new(
+            PlayerId::new(0),
+            num_players,
+            trump.parse().unwrap(),
+            cards.len(),
+        );
+        (view, cards)
+    }
+
+    #[test]
+    fn long_low_trumps_do_not_bid_every_trick() {
+        let (view, cards) = setup(4, "9H", &["2H", "3H", "4H", "5H", "6H", "7H", "8H"]);
+        let bid = estimate_bid(&view, &cards);
+
+        assert!((2..=4).contains(&bid), "bid {}", bid);
+    }
+
+    #[test]
+    fn high_cards_and_trumps_bid_tricks() {
+        let (view, cards) = setup(4, "2C", &["AS", "AD", "KD", "AC", "KC", "QC"]);
+
+        assert!(estimate_bid(&view, &cards) >= 4);
+    }
+
+    #[test]
+    fn weak_hand_bids_nothing() {
+        let (view, cards) = setup(5, "2H", &["3S", "5S", "4D", "7D", "2C"]);
+
+        assert_eq!(estimate_bid(&view, &cards), 0);
+    }
+
+    #[test]
+    fn more_opponents_lower_the_estimate() {
+        let (view, cards) = setup(2, "2H", &["QS", "JD", "10C"]);
+        let (crowded, _) = setup(7, "2H", &["QS", "JD", "10C"]);
+
+        assert!(hand_strength(&crowded, &cards) < hand_strength(&view, &cards));
+    }
+
+    #[test]
+    fn single_card_bids_on_its_chance_of_winning() {
+        let (view, cards) = setup(4, "2H", &["AH"]);
+        assert_eq!(estimate_bid(&view, &cards), 1);
+
+        let (view, cards) = setup(4, "2H", &["3S"]);
+        assert_eq!(estimate_bid(&view, &cards), 0);
+    }
+}
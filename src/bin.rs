@@ -1,5 +1,24 @@
+use std::path::PathBuf;
+use std::{env, process};
+
 use contract_whist::ContractWhistGame;
 
+const USAGE: &str = "Usage: contract_whist_game [--seed <number>] [--record <file>]";
+
 fn main() {
-    ContractWhistGame::play(true);
+    // A game may be replayed by passing the seed shown at the start of the game
+    let seed = env::args().skip_while(|e| e != "--seed").nth(1).map(|e| {
+        e.parse().unwrap_or_else(|_| {
+            println!("--seed must be a number, not {}", e);
+            println!("{}", USAGE);
+            process::exit(2);
+        })
+    });
+    // The record of the game is written to the given file as it is played
+    let record = env::args()
+        .skip_while(|e| e != "--record")
+        .nth(1)
+        .map(PathBuf::from);
+
+    ContractWhistGame::play(true, seed, record.as_deref());
 }
@@ -3,14 +3,39 @@
 //! The [Rank] attribute holds the value that determines where the Card sorts
 //! among other Cards in its [Suit]. The [Suit] holds the value that
 //! determines which grouping of Cards the Card belongs to.
+//!
+//! Cards display as "Ace of Hearts", and may also be written in the compact
+//! [Notation]s used by tools, logs, and test fixtures. Cards are read from any of
+//! the common notations.
+//!
+//! # Examples
+//! ```
+//! use contract_whist::{Card, Notation, Rank, Suit};
+//!
+//! let card: Card = "10s".parse().unwrap();
+//! assert_eq!(card, Card::new(Rank::Ten, Suit::Spades));
+//! assert_eq!("Td".parse::<Card>().unwrap(), Card::new(Rank::Ten, Suit::Diamonds));
+//! assert_eq!("Q\u{2663}".parse::<Card>().unwrap(), Card::new(Rank::Queen, Suit::Clubs));
+//!
+//! let queen = Card::new(Rank::Queen, Suit::Spades);
+//! assert_eq!(queen.format(Notation::Short), "QS");
+//! assert_eq!(queen.format(Notation::Unicode), "Q\u{2660}");
+//! assert_eq!(queen.format(Notation::Verbose), "Queen of Spades");
+//! ```
 
+use std::cmp::Ordering;
 use std::fmt;
+use std::str::FromStr;
 
 use crate::rank::Rank;
 use crate::suit::Suit;
 
 /// A Card, representing a traditional Card from a French deck of playing cards.
-#[derive(Debug, Clone, Copy, PartialEq)]
+///
+/// Cards are ordered by [Suit], then by [Rank] with aces high, so a sorted hand is
+/// grouped by suit. Which card wins a trick depends on the trump and led suits, and
+/// is found with a [crate::trick::TrickComparator].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Card {
     rank: Rank,
     suit: Suit,
@@ -26,9 +51,7 @@ impl Card {
     /// # Examples
     ///
     /// ```
-    /// # use mormon_bridge::rank::Rank;
-    /// # use mormon_bridge::suit::Suit;
-    /// # use mormon_bridge::card::Card;
+    /// use contract_whist::{Card, Rank, Suit};
     ///
     /// let card = Card::new(Rank::Ace, Suit::Hearts);
     /// assert_eq!((&Rank::Ace, &Suit::Hearts), card.get_value());
@@ -42,10 +65,22 @@ impl Card {
         self.rank
     }
 
-    // Access the [Suit] of the Card.
+    /// Access the [Suit] of the Card.
     pub fn suit(&self) -> Suit {
         self.suit
     }
+
+    /// Returns the Card written in the given [Notation].
+    pub fn format(&self, notation: Notation) -> String {
+        match notation {
+            Notation::Verbose => self.to_string(),
+            notation => format!(
+                "{}{}",
+                self.rank.format(notation),
+                self.suit.format(notation)
+            ),
+        }
+    }
 }
 
 impl fmt::Display for Card {
@@ -54,10 +89,86 @@ impl fmt::Display for Card {
     }
 }
 
+impl Ord for Card {
+    fn cmp(&self, other: &Card) -> Ordering {
+        (self.suit, self.rank).cmp(&(other.suit, other.rank))
+    }
+}
+
+impl PartialOrd for Card {
+    fn partial_cmp(&self, other: &Card) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Reads a Card from its rank followed by its suit, such as "AH", "10s", "Td", or
+/// "Q\u{2663}", or from its verbose notation, such as "Queen of Clubs".
+///
+/// See [Rank] and [Suit] for the ways each may be written.
+impl FromStr for Card {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Card, ParseCardError> {
+        let s = s.trim();
+        let words: Vec<&str> = s.split_whitespace().collect();
+        if let [rank, of, suit] = words[..] {
+            if of.eq_ignore_ascii_case("of") {
+                return Ok(Card::new(rank.parse()?, suit.parse()?));
+            }
+        }
+
+        let split = match s.char_indices().last() {
+            Some((split, _)) if split > 0 => split,
+            _ => return Err(ParseCardError::Card(s.to_string())),
+        };
+        Ok(Card::new(s[..split].parse()?, s[split..].parse()?))
+    }
+}
+
+/// The ways a [Card] may be written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Notation {
+    /// The rank and the first letter of the suit, such as "QS" or "10H".
+    Short,
+    /// The rank and the suit symbol, such as "Q\u{2660}" or "10\u{2665}".
+    Unicode,
+    /// The rank and suit in words, such as "Queen of Spades".
+    Verbose,
+}
+
+/// Errors raised when reading a [Card], [Rank], or [Suit] from text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseCardError {
+    /// The text is too short to hold a rank and a suit.
+    Card(String),
+    /// The rank is not recognised.
+    Rank(String),
+    /// The suit is not recognised.
+    Suit(String),
+}
+
+impl fmt::Display for ParseCardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseCardError::Card(card) => write!(f, "\"{}\" is not a card", card),
+            ParseCardError::Rank(rank) => write!(f, "\"{}\" is not a rank", rank),
+            ParseCardError::Suit(suit) => write!(f, "\"{}\" is not a suit", suit),
+        }
+    }
+}
+
+impl std::error::Error for ParseCardError {}
+
+/// Reads a card from text such as "10C", for tests.
+#[cfg(test)]
+pub(crate) fn card(text: &str) -> Card {
+    text.parse().unwrap()
+}
+
+#[cfg(test)]
 mod tests {
     use super::*;
 
-    #[allow(dead_code)]
     fn setup() -> Card {
         Card::new(Rank::Ace, Suit::Hearts)
     }
@@ -79,4 +190,77 @@ mod tests {
         let card = setup();
         assert_eq!(card.rank(), Rank::Ace);
     }
+
+    #[test]
+    fn cards_are_sorted_by_suit_then_rank() {
+        let mut cards = vec![
+            Card::new(Rank::Two, Suit::Clubs),
+            Card::new(Rank::Ace, Suit::Clubs),
+            Card::new(Rank::King, Suit::Hearts),
+            Card::new(Rank::Three, Suit::Clubs),
+        ];
+        cards.sort();
+
+        assert_eq!(
+            cards,
+            vec![
+                Card::new(Rank::King, Suit::Hearts),
+                Card::new(Rank::Two, Suit::Clubs),
+                Card::new(Rank::Three, Suit::Clubs),
+                Card::new(Rank::Ace, Suit::Clubs),
+            ]
+        );
+    }
+
+    #[test]
+    fn cards_may_be_used_as_keys() {
+        let mut seen = std::collections::HashSet::new();
+
+        assert!(seen.insert(setup()));
+        assert!(!seen.insert(Card::new(Rank::Ace, Suit::Hearts)));
+    }
+
+    #[test]
+    fn cards_are_read_from_common_notations() {
+        let ten = Card::new(Rank::Ten, Suit::Diamonds);
+
+        assert_eq!("AH".parse(), Ok(setup()));
+        assert_eq!("10d".parse(), Ok(ten));
+        assert_eq!("Td".parse(), Ok(ten));
+        assert_eq!("10\u{2666}".parse(), Ok(ten));
+        assert_eq!("ace of hearts".parse(), Ok(setup()));
+        assert_eq!(
+            "H".parse::<Card>(),
+            Err(ParseCardError::Card("H".to_string()))
+        );
+        assert_eq!(
+            "1H".parse::<Card>(),
+            Err(ParseCardError::Rank("1".to_string()))
+        );
+        assert_eq!(
+            "14H".parse::<Card>(),
+            Err(ParseCardError::Rank("14".to_string()))
+        );
+        assert_eq!(
+            "AX".parse::<Card>(),
+            Err(ParseCardError::Suit("X".to_string()))
+        );
+    }
+
+    #[test]
+    fn every_card_is_read_back_from_every_notation() {
+        for rank in Rank::VALUES {
+            for suit in Suit::VALUES {
+                let card = Card::new(rank, suit);
+                for notation in [Notation::Short, Notation::Unicode, Notation::Verbose] {
+                    assert_eq!(card.format(notation).parse(), Ok(card));
+                }
+            }
+        }
+        assert_eq!(setup().format(Notation::Short), "AH");
+        assert_eq!(
+            Card::new(Rank::Ten, Suit::Clubs).format(Notation::Unicode),
+            "10\u{2663}"
+        );
+    }
 }
@@ -0,0 +1,319 @@
+//! A compact set of [Card]s, held as one bit per card of the deck.
+//!
+//! A [CardSet] is `Copy` and fits in a single word, so adding, removing, and
+//! checking cards, counting a suit, or combining sets takes a few instructions
+//! and never allocates. Hands and the cards played in a hand are held as
+//! CardSets, which makes them cheap to copy for simulation and search.
+//!
+//! Cards are iterated in their sorted order: grouped by [Suit], lowest rank first
+//! with aces high.
+//!
+//! # Examples
+//! ```
+//! use contract_whist::{Card, CardSet, Rank, Suit};
+//!
+//! let mut hand: CardSet = ["AH", "10H", "2C"].iter().map(|e| e.parse::<Card>().unwrap()).collect();
+//!
+//! assert_eq!(hand.len(), 3);
+//! assert_eq!(hand.count_suit(Suit::Hearts), 2);
+//! assert_eq!(hand.in_suit(Suit::Hearts).highest(), Some(Card::new(Rank::Ace, Suit::Hearts)));
+//!
+//! hand.remove(Card::new(Rank::Ace, Suit::Hearts));
+//! assert_eq!(hand.to_string(), "10H 2C");
+//! ```
+
+use std::fmt;
+use std::iter::FromIterator;
+use std::ops::{BitAnd, BitOr, Not, Sub};
+
+use crate::card::{Card, Notation};
+use crate::rank::Rank;
+use crate::suit::Suit;
+
+/// The Suits in the order their cards are held in a [CardSet].
+const SUITS: [Suit; 4] = [Suit::Hearts, Suit::Spades, Suit::Diamonds, Suit::Clubs];
+/// The number of Ranks in each Suit.
+const RANKS: u32 = 13;
+/// The bits of the cards in the lowest Suit.
+const SUIT_MASK: u64 = (1 << RANKS) - 1;
+/// The bits of every card in the deck.
+const DECK_MASK: u64 = (1 << (RANKS * 4)) - 1;
+
+/// A set of [Card]s from a single deck.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct CardSet(u64);
+
+/// Returns the position of the Suit in a [CardSet].
+fn suit_index(suit: Suit) -> u32 {
+    SUITS.iter().position(|e| *e == suit).unwrap() as u32
+}
+
+/// Returns the bit of a Card in a [CardSet].
+fn bit(card: Card) -> u64 {
+    let rank = card.rank().get_numerical_rank(true) as u32 - 2;
+    1 << (suit_index(card.suit()) * RANKS + rank)
+}
+
+/// Returns the Card held by a bit in a [CardSet].
+fn card_at(index: u32) -> Card {
+    let rank = Rank::VALUES[(RANKS - 1 - index % RANKS) as usize];
+    Card::new(rank, SUITS[(index / RANKS) as usize])
+}
+
+impl CardSet {
+    /// Creates an empty CardSet.
+    pub fn new() -> CardSet {
+        CardSet(0)
+    }
+
+    /// Returns the bits of the set, one for each card, which are the same in every
+    /// build of the crate.
+    pub fn bits(&self) -> u64 {
+        self.0
+    }
+
+    /// Creates a CardSet holding every card in the deck.
+    pub fn full() -> CardSet {
+        CardSet(DECK_MASK)
+    }
+
+    /// Creates a CardSet holding every card in a Suit.
+    pub fn suit(suit: Suit) -> CardSet {
+        CardSet(SUIT_MASK << (suit_index(suit) * RANKS))
+    }
+
+    /// Adds a card, returning true if it was not already held.
+    pub fn insert(&mut self, card: Card) -> bool {
+        let added = !self.contains(card);
+        self.0 |= bit(card);
+        added
+    }
+
+    /// Removes a card, returning true if it was held.
+    pub fn remove(&mut self, card: Card) -> bool {
+        let removed = self.contains(card);
+        self.0 &= !bit(card);
+        removed
+    }
+
+    /// Returns true if the card is held.
+    pub fn contains(&self, card: Card) -> bool {
+        self.0 & bit(card) != 0
+    }
+
+    /// Returns the number of cards held.
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Returns true if no cards are held.
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns the cards held in a Suit.
+    pub fn in_suit(&self, suit: Suit) -> CardSet {
+        *self & CardSet::suit(suit)
+    }
+
+    /// Returns the number of cards held in a Suit.
+    pub fn count_suit(&self, suit: Suit) -> usize {
+        self.in_suit(suit).len()
+    }
+
+    /// Returns the lowest card held, with suits in the order of a sorted hand.
+    ///
+    /// Within a single suit, as given by [CardSet::in_suit], this is the lowest
+    /// ranking card.
+    pub fn lowest(&self) -> Option<Card> {
+        match self.0 {
+            0 => None,
+            bits => Some(card_at(bits.trailing_zeros())),
+        }
+    }
+
+    /// Returns the highest card held, with suits in the order of a sorted hand.
+    ///
+    /// Within a single suit, as given by [CardSet::in_suit], this is the highest
+    /// ranking card.
+    pub fn highest(&self) -> Option<Card> {
+        match self.0 {
+            0 => None,
+            bits => Some(card_at(63 - bits.leading_zeros())),
+        }
+    }
+
+    /// Returns an iterator over the cards held, in sorted order.
+    pub fn iter(&self) -> Iter {
+        Iter(self.0)
+    }
+
+    /// Returns the cards held, in sorted order.
+    pub fn to_vec(&self) -> Vec<Card> {
+        self.iter().collect()
+    }
+}
+
+impl BitOr for CardSet {
+    type Output = CardSet;
+
+    /// Returns the cards held in either set.
+    fn bitor(self, other: CardSet) -> CardSet {
+        CardSet(self.0 | other.0)
+    }
+}
+
+impl BitAnd for CardSet {
+    type Output = CardSet;
+
+    /// Returns the cards held in both sets.
+    fn bitand(self, other: CardSet) -> CardSet {
+        CardSet(self.0 & other.0)
+    }
+}
+
+impl Sub for CardSet {
+    type Output = CardSet;
+
+    /// Returns the cards held in this set but not the other.
+    fn sub(self, other: CardSet) -> CardSet {
+        CardSet(self.0 & !other.0)
+    }
+}
+
+impl Not for CardSet {
+    type Output = CardSet;
+
+    /// Returns every card in the deck not held in this set.
+    fn not(self) -> CardSet {
+        CardSet(!self.0 & DECK_MASK)
+    }
+}
+
+/// Iterator over the cards in a [CardSet], in sorted order.
+#[derive(Debug, Clone)]
+pub struct Iter(u64);
+
+impl Iterator for Iter {
+    type Item = Card;
+
+    fn next(&mut self) -> Option<Card> {
+        if self.0 == 0 {
+            return None;
+        }
+        let index = self.0.trailing_zeros();
+        self.0 &= self.0 - 1;
+        Some(card_at(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+impl DoubleEndedIterator for Iter {
+    fn next_back(&mut self) -> Option<Card> {
+        if self.0 == 0 {
+            return None;
+        }
+        let index = 63 - self.0.leading_zeros();
+        self.0 &= !(1 << index);
+        Some(card_at(index))
+    }
+}
+
+impl ExactSizeIterator for Iter {}
+
+impl IntoIterator for CardSet {
+    type Item = Card;
+    type IntoIter = Iter;
+
+    fn into_iter(self) -> Iter {
+        self.iter()
+    }
+}
+
+impl FromIterator<Card> for CardSet {
+    fn from_iter<I: IntoIterator<Item = Card>>(cards: I) -> CardSet {
+        let mut set = CardSet::new();
+        set.extend(cards);
+        set
+    }
+}
+
+impl<'a> FromIterator<&'a Card> for CardSet {
+    fn from_iter<I: IntoIterator<Item = &'a Card>>(cards: I) -> CardSet {
+        cards.into_iter().copied().collect()
+    }
+}
+
+impl Extend<Card> for CardSet {
+    fn extend<I: IntoIterator<Item = Card>>(&mut self, cards: I) {
+        for card in cards {
+            self.insert(card);
+        }
+    }
+}
+
+/// Writes the cards in short notation, separated by spaces.
+impl fmt::Display for CardSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cards: Vec<String> = self.iter().map(|e| e.format(Notation::Short)).collect();
+        write!(f, "{}", cards.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_card_has_its_own_bit() {
+        let mut set = CardSet::new();
+        for suit in Suit::VALUES {
+            for rank in Rank::VALUES {
+                assert!(set.insert(Card::new(rank, suit)));
+            }
+        }
+
+        assert_eq!(set, CardSet::full());
+        assert_eq!(set.len(), 52);
+        assert!(!set.insert(Card::new(Rank::Ace, Suit::Clubs)));
+    }
+
+    #[test]
+    fn cards_are_iterated_in_sorted_order() {
+        let mut cards: Vec<Card> = CardSet::full().iter().collect();
+        let sorted = {
+            let mut sorted = cards.clone();
+            sorted.sort();
+            sorted
+        };
+
+        assert_eq!(cards, sorted);
+        cards.reverse();
+        assert_eq!(cards.iter().collect::<CardSet>(), CardSet::full());
+    }
+
+    #[test]
+    fn suits_are_counted_and_masked() {
+        let mut set = CardSet::suit(Suit::Diamonds);
+        set.insert(Card::new(Rank::Two, Suit::Hearts));
+        set.remove(Card::new(Rank::Ace, Suit::Diamonds));
+
+        assert_eq!(set.count_suit(Suit::Diamonds), 12);
+        assert_eq!(set.count_suit(Suit::Hearts), 1);
+        assert_eq!(set.count_suit(Suit::Clubs), 0);
+        assert_eq!(
+            set.in_suit(Suit::Diamonds).highest(),
+            Some(Card::new(Rank::King, Suit::Diamonds))
+        );
+        assert_eq!(
+            set.in_suit(Suit::Diamonds).lowest(),
+            Some(Card::new(Rank::Two, Suit::Diamonds))
+        );
+        assert_eq!((!set).len(), 52 - 13);
+        assert_eq!((set - CardSet::suit(Suit::Diamonds)).len(), 1);
+    }
+}
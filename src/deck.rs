@@ -3,20 +3,29 @@
 //! At its core, a wrapper around a Vec of [Card]s. Dealing a [Card] removes one
 //! from the top of the Deck and returns it. Shuffling the Deck randomizes the
 //! order. Building a [Deck] is done by calling configuration methods after a
-//! `Deck::new()` call.
+//! `Deck::new()` call. Seeding the builder makes the shuffles reproducible, so
+//! the same seed always gives the same order of cards.
 //!
 //! # Examples
 //! ```
+//! use contract_whist::{Deck, DeckType};
+//!
 //! // Create a new full 52 card deck and shuffle it 7 times
-//! let deck = Deck::new().deck_type(DeckType::Full).shuffle(7);
-//! assert_eq!(deck.len(), 52);
+//! let deck = Deck::new().deck_type(DeckType::Full).shuffle(7).end();
+//! assert_eq!(deck.total_cards(), 52);
+//!
+//! // Decks shuffled with the same seed deal the same cards
+//! let mut first = Deck::new().deck_type(DeckType::Full).seed(42).shuffle(7).end();
+//! let mut second = Deck::new().deck_type(DeckType::Full).seed(42).shuffle(7).end();
+//! assert_eq!(first.deal(), second.deal());
 //! ```
 //!
 //! # Todo
 //! - [ ] Update documentation
 
 use rand::prelude::SliceRandom;
-use rand::thread_rng;
+use rand::rngs::StdRng;
+use rand::{FromEntropy, SeedableRng};
 
 use crate::card::Card;
 use crate::rank::Rank;
@@ -26,7 +35,7 @@ use crate::suit::Suit;
 ///
 /// Provides dealing and shuffling funtionality to randomize Card order and
 /// return a [Card].
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Deck {
     cards: Vec<Card>,
 }
@@ -35,7 +44,10 @@ impl Deck {
     /// Provides a [DeckBuilder] for Deck configuration.
     #[allow(clippy::new_ret_no_self)]
     pub fn new() -> DeckBuilder {
-        DeckBuilder { cards: Vec::new() }
+        DeckBuilder {
+            cards: Vec::new(),
+            rng: StdRng::from_entropy(),
+        }
     }
 
     /// Prints all the Cards in the Deck in order for debugging purposes.
@@ -85,6 +97,7 @@ impl Deck {
 }
 
 /// Types of Deck that may be created.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DeckType {
     Full,
 }
@@ -93,14 +106,17 @@ pub enum DeckType {
 ///
 /// # Examples
 /// ```
+/// use contract_whist::{Deck, DeckType};
+///
 /// // create the DeckBuilder
 /// let deck_builder = Deck::new();
-/// // COnfigure the Deck
+/// // Configure the Deck
 /// let deck = deck_builder.deck_type(DeckType::Full).end();
 /// assert_eq!(deck.total_cards(), 52);
 /// ```
 pub struct DeckBuilder {
     cards: Vec<Card>,
+    rng: StdRng,
 }
 
 impl DeckBuilder {
@@ -118,27 +134,39 @@ impl DeckBuilder {
             }
         }
 
-        DeckBuilder { cards }
+        DeckBuilder { cards, ..self }
+    }
+
+    /// Seeds the random number generator used to shuffle the [Deck].
+    ///
+    /// Without a seed, the shuffles are seeded from the operating system.
+    pub fn seed(self, seed: u64) -> DeckBuilder {
+        DeckBuilder {
+            rng: StdRng::seed_from_u64(seed),
+            ..self
+        }
     }
 
     /// Shuffles the [Deck] 7 times.
     #[allow(dead_code)]
     pub fn default_shuffle(self) -> DeckBuilder {
         let mut cards = self.cards;
-        let mut shuffling = || cards.shuffle(&mut thread_rng());
+        let mut rng = self.rng;
+        let mut shuffling = || cards.shuffle(&mut rng);
         {
             for _ in 0..7 {
                 shuffling();
             }
         }
 
-        DeckBuilder { cards }
+        DeckBuilder { cards, rng }
     }
 
     /// Shuffles the [Deck] anywhere from 1 to 10 times.
     pub fn shuffle(self, shuffles: usize) -> DeckBuilder {
         let mut cards = self.cards;
-        let mut shuffling = || cards.shuffle(&mut thread_rng());
+        let mut rng = self.rng;
+        let mut shuffling = || cards.shuffle(&mut rng);
 
         match shuffles {
             1..=10 => {
@@ -149,7 +177,7 @@ impl DeckBuilder {
             _ => shuffling(),
         }
 
-        DeckBuilder { cards }
+        DeckBuilder { cards, rng }
     }
 
     /// Used by `end` to ensure the [Deck] is always cut before use.
@@ -165,6 +193,7 @@ impl DeckBuilder {
 
         DeckBuilder {
             cards: [second_split, first_split].concat(),
+            ..self
         }
     }
 
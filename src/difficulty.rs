@@ -0,0 +1,138 @@
+//! Difficulty levels for the AI players of a game.
+//!
+//! Each [Difficulty] is played by its own type of [Player]:
+//! - [Difficulty::Beginner] by a [BeginnerPlayer], which bids one trick for each
+//!   of its highest cards and plays any card it may.
+//! - [Difficulty::Intermediate] by an [AIPlayer], which bids the tricks its hand is
+//!   expected to take, remembers the cards played, and plays towards its bid in
+//!   the style of its [Personality].
+//! - [Difficulty::Expert] by a [MonteCarloPlayer], which plays each card by
+//!   simulating the deals of the cards it cannot see that fit what it has seen.
+//!
+//! A difficulty may be chosen for all the AI players of a game at once, or for each
+//! of them, and is read from text such as "expert".
+//!
+//! # Examples
+//! ```
+//! use contract_whist::difficulty::Difficulty;
+//! use contract_whist::personality::Personality;
+//! use contract_whist::{ContractWhistGame, Player, Rules};
+//!
+//! let players: Vec<Box<dyn Player>> = vec![
+//!     Difficulty::Beginner.create_player("Huey Duck".to_string(), Personality::default()),
+//!     Difficulty::Intermediate.create_player("Dewey Duck".to_string(), Personality::GAMBLER),
+//! ];
+//!
+//! assert_eq!("expert".parse::<Difficulty>().unwrap(), Difficulty::Expert);
+//!
+//! let result = ContractWhistGame::with_seed(players, Rules::default().hand_sizes(vec![2, 1]), 3)
+//!     .unwrap()
+//!     .run();
+//! assert_eq!(result.get_hands().len(), 2);
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::monte_carlo::MonteCarloPlayer;
+use crate::personality::Personality;
+use crate::player::{AIPlayer, BeginnerPlayer, Player};
+
+/// How well an AI player plays.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Difficulty {
+    /// Bids roughly and plays any card it may.
+    Beginner,
+    /// Bids its expected tricks and plays towards its bid by fixed rules.
+    #[default]
+    Intermediate,
+    /// Remembers the cards played and searches the deals they leave.
+    Expert,
+}
+
+/// Error returned when text does not name a [Difficulty].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDifficultyError(String);
+
+impl Difficulty {
+    /// Every difficulty, from the easiest to the hardest.
+    pub const VALUES: [Difficulty; 3] = [
+        Difficulty::Beginner,
+        Difficulty::Intermediate,
+        Difficulty::Expert,
+    ];
+
+    /// Creates a Player with the given name that plays at this difficulty. An
+    /// [AIPlayer] plays with `personality`, which the other difficulties ignore.
+    pub fn create_player(&self, name: String, personality: Personality) -> Box<dyn Player> {
+        match self {
+            Difficulty::Beginner => Box::new(BeginnerPlayer::new(name)),
+            Difficulty::Intermediate => Box::new(AIPlayer::new(name).personality(personality)),
+            Difficulty::Expert => Box::new(MonteCarloPlayer::new(name)),
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Difficulty::Beginner => "Beginner",
+            Difficulty::Intermediate => "Intermediate",
+            Difficulty::Expert => "Expert",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = ParseDifficultyError;
+
+    /// Reads a difficulty from its name, in any case.
+    fn from_str(s: &str) -> Result<Difficulty, ParseDifficultyError> {
+        Difficulty::VALUES
+            .into_iter()
+            .find(|e| e.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| ParseDifficultyError(s.trim().to_string()))
+    }
+}
+
+impl fmt::Display for ParseDifficultyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "\"{}\" is not a difficulty", self.0)
+    }
+}
+
+impl std::error::Error for ParseDifficultyError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::ContractWhistGame;
+    use crate::rules::Rules;
+
+    #[test]
+    fn difficulties_are_read_from_their_names() {
+        for difficulty in Difficulty::VALUES {
+            let name = difficulty.to_string().to_uppercase();
+            assert_eq!(name.parse(), Ok(difficulty));
+        }
+        assert_eq!(
+            "hard".parse::<Difficulty>(),
+            Err(ParseDifficultyError("hard".to_string()))
+        );
+    }
+
+    #[test]
+    fn every_difficulty_plays_a_game() {
+        let players = Difficulty::VALUES
+            .iter()
+            .map(|e| e.create_player(format!("{} Player", e), Personality::default()))
+            .collect();
+        let rules = Rules::default().hand_sizes(vec![3, 2]);
+
+        let result = ContractWhistGame::with_seed(players, rules, 21)
+            .unwrap()
+            .run();
+        assert_eq!(result.get_hands().len(), 2);
+    }
+}
@@ -0,0 +1,537 @@
+//! A double dummy solver, which works out the tricks each player can take when
+//! every card is known.
+//!
+//! Given every player's cards, the trump suit, and the player to lead, a
+//! [DoubleDummy] finds the most tricks a player can make sure of taking however the
+//! other players play, and the fewest tricks they can hold themselves to however
+//! the other players try to give them tricks. Both assume perfect play by every
+//! player, with the other players working together against the one being solved
+//! for.
+//!
+//! The search is an alpha-beta search over the cards each player may play, as
+//! given by [Rules::legal_set], with each trick won as given by
+//! [TrickComparator]. The results of positions at the start of each trick are kept
+//! in a transposition table, so a position reached by playing the same tricks in
+//! another order is solved once. Cards that are equal, with no card left between
+//! them in the suit, are only tried once.
+//!
+//! The time taken grows quickly with the number of cards left, so a full hand of
+//! seven cards between five or more players may take seconds to solve.
+//!
+//! The solver is used to analyse hands after the game, to find the par result of a
+//! deal, and to check the play of the AI players.
+//!
+//! # Examples
+//! ```
+//! use contract_whist::double_dummy::DoubleDummy;
+//! use contract_whist::{Card, CardSet, PlayerId, Suit};
+//!
+//! let hand = |cards: &[&str]| -> CardSet { cards.iter().map(|e| e.parse::<Card>().unwrap()).collect() };
+//!
+//! // Seat 1 may ruff the ace of spades, but the king of diamonds takes a trick
+//! // whatever is led
+//! let hands = vec![hand(&["AS", "KD"]), hand(&["2H", "3D"])];
+//! let mut solver = DoubleDummy::new(Suit::Hearts, hands, PlayerId::new(0));
+//!
+//! assert_eq!(solver.max_tricks(PlayerId::new(0)), 1);
+//! assert_eq!(solver.min_tricks(PlayerId::new(0)), 1);
+//! ```
+
+use std::collections::HashMap;
+
+use crate::card::Card;
+use crate::card_set::CardSet;
+use crate::hand::{Hand, HandError, InProgressHand, Playing};
+use crate::player::PlayerId;
+use crate::rules::Rules;
+use crate::suit::Suit;
+use crate::trick::TrickComparator;
+
+/// Solves a deal with every card known.
+#[derive(Debug, Clone)]
+pub struct DoubleDummy {
+    rules: Rules,
+    trump: Suit,
+    hands: Vec<CardSet>,
+    leader: PlayerId,
+    trick: Vec<(PlayerId, Card)>,
+    table: HashMap<Position, Bounds>,
+    outcomes: HashMap<Position, bool>,
+}
+
+/// Whether the player being solved for is trying to take the most or the fewest
+/// tricks, or exactly a number of the tricks left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Goal {
+    Most,
+    Fewest,
+    Exactly(usize),
+}
+
+/// A position at the start of a trick, for the player being solved for.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Position {
+    hands: [CardSet; Rules::MAX_PLAYERS],
+    leader: PlayerId,
+    player: PlayerId,
+    goal: Goal,
+}
+
+/// The least and most tricks a position is known to be worth.
+#[derive(Debug, Clone, Copy)]
+struct Bounds {
+    lower: usize,
+    upper: usize,
+}
+
+impl DoubleDummy {
+    /// Creates a solver for the cards in `hands`, indexed by seat, with `leader` to
+    /// lead the first trick.
+    ///
+    /// Every player must hold the same number of cards, which is the number of
+    /// tricks left to play.
+    pub fn new(trump: Suit, hands: Vec<CardSet>, leader: PlayerId) -> DoubleDummy {
+        DoubleDummy {
+            rules: Rules::default(),
+            trump,
+            hands,
+            leader,
+            trick: Vec::new(),
+            table: HashMap::new(),
+            outcomes: HashMap::new(),
+        }
+    }
+
+    /// Creates a solver for the rest of a hand being played.
+    pub fn from_hand(hand: &InProgressHand<Playing>) -> DoubleDummy {
+        let hands = (0..hand.get_num_players())
+            .map(|seat| hand.get_cards(PlayerId::new(seat)))
+            .collect();
+        let trick = hand.get_current_trick();
+        let leader = trick.get_player_order()[0];
+
+        DoubleDummy::new(hand.get_trump().suit(), hands, leader)
+            .rules(hand.get_rules().clone())
+            .current_trick(trick.get_cards_played().to_vec())
+    }
+
+    /// Creates a solver for a finished hand as it was dealt, with the dealer to lead.
+    pub fn from_deal(hand: &Hand) -> DoubleDummy {
+        let hands = (0..hand.get_bids().len())
+            .map(|seat| hand.get_deal(PlayerId::new(seat)).iter().collect())
+            .collect();
+
+        DoubleDummy::new(hand.get_trump().suit(), hands, hand.get_dealer())
+    }
+
+    /// Sets the rules deciding which cards may be played.
+    pub fn rules(self, rules: Rules) -> DoubleDummy {
+        DoubleDummy {
+            rules,
+            table: HashMap::new(),
+            outcomes: HashMap::new(),
+            ..self
+        }
+    }
+
+    /// Sets the cards already played to the current trick, which are no longer held.
+    /// The trick is led by the first player in it.
+    pub fn current_trick(self, trick: Vec<(PlayerId, Card)>) -> DoubleDummy {
+        let leader = trick.first().map_or(self.leader, |e| e.0);
+        DoubleDummy {
+            leader,
+            trick,
+            table: HashMap::new(),
+            outcomes: HashMap::new(),
+            ..self
+        }
+    }
+
+    /// Returns the cards held by a player.
+    pub fn get_cards(&self, player: PlayerId) -> CardSet {
+        self.hands[player.seat()]
+    }
+
+    /// Returns the cards played to the current trick.
+    pub fn get_current_trick(&self) -> &[(PlayerId, Card)] {
+        &self.trick
+    }
+
+    /// Returns the player whose turn it is to play, or None once every card is played.
+    pub fn next_player(&self) -> Option<PlayerId> {
+        let next = match self.trick.last() {
+            Some((last, _)) => PlayerId::new((last.seat() + 1) % self.hands.len()),
+            None => self.leader,
+        };
+        match self.hands[next.seat()].is_empty() {
+            true => None,
+            false => Some(next),
+        }
+    }
+
+    /// Returns the cards the next player may play.
+    pub fn legal_cards(&self) -> CardSet {
+        match self.next_player() {
+            Some(player) => self.rules.legal_set(self.hands[player.seat()], &self.trick),
+            None => CardSet::new(),
+        }
+    }
+
+    /// Plays a card for the next player, and returns the winner of the trick if the
+    /// card finishes it.
+    ///
+    /// Tricks are counted from the position the solver is in, so the tricks won
+    /// before a card is played are not counted by the solver after it.
+    pub fn play_card(&mut self, card: Card) -> Result<Option<PlayerId>, HandError> {
+        let player = self.next_player().ok_or(HandError::NoDecisionPending)?;
+        self.rules
+            .check_card(self.hands[player.seat()], &self.trick, card)?;
+
+        self.hands[player.seat()].remove(card);
+        self.trick.push((player, card));
+        if self.trick.len() < self.hands.len() {
+            return Ok(None);
+        }
+
+        let winner = self.trick_winner();
+        self.trick.clear();
+        self.leader = winner;
+        Ok(Some(winner))
+    }
+
+    /// Returns the most tricks left that `player` can make sure of taking.
+    pub fn max_tricks(&mut self, player: PlayerId) -> usize {
+        self.solve(player, Goal::Most)
+    }
+
+    /// Returns the fewest tricks left that `player` can hold themselves to.
+    pub fn min_tricks(&mut self, player: PlayerId) -> usize {
+        self.solve(player, Goal::Fewest)
+    }
+
+    /// Returns true if `player` can make sure of taking exactly `tricks` of the
+    /// tricks left, however the other players play.
+    pub fn can_take(&mut self, player: PlayerId, tricks: usize) -> bool {
+        self.search_exactly(player, tricks)
+    }
+
+    /// Returns the tricks left taken by `player` with perfect play towards `goal`.
+    fn solve(&mut self, player: PlayerId, goal: Goal) -> usize {
+        let left = self.hands.iter().map(|e| e.len()).max().unwrap_or(0);
+        let mut tricks = 0;
+        while tricks < left && self.search(player, goal, tricks, tricks + 1) > tricks {
+            tricks += 1;
+        }
+        tricks
+    }
+
+    /// Returns the winner of the current trick, once every player has played to it.
+    fn trick_winner(&self) -> PlayerId {
+        let led = self.trick[0].1.suit();
+        TrickComparator::new(self.trump, led)
+            .winner(&self.trick)
+            .unwrap()
+    }
+
+    /// Returns the tricks left taken by `player` with perfect play towards `goal`,
+    /// if between `alpha` and `beta`. Otherwise returns a value at or below `alpha`,
+    /// or at or above `beta`, which bounds it.
+    fn search(&mut self, player: PlayerId, goal: Goal, alpha: usize, beta: usize) -> usize {
+        if !self.trick.is_empty() {
+            return self.search_cards(player, goal, alpha, beta);
+        }
+        let left = self.hands[self.leader.seat()].len();
+        if left == 0 {
+            return 0;
+        }
+
+        let position = self.position(player, goal);
+        let mut bounds = self.table.get(&position).copied().unwrap_or(Bounds {
+            lower: 0,
+            upper: left,
+        });
+        if bounds.lower == bounds.upper || bounds.lower >= beta {
+            return bounds.lower;
+        }
+        if bounds.upper <= alpha {
+            return bounds.upper;
+        }
+
+        let (alpha, beta) = (alpha.max(bounds.lower), beta.min(bounds.upper));
+        let value = self.search_cards(player, goal, alpha, beta);
+        if value <= alpha {
+            bounds.upper = value;
+        } else if value >= beta {
+            bounds.lower = value;
+        } else {
+            bounds = Bounds {
+                lower: value,
+                upper: value,
+            };
+        }
+        self.table.insert(position, bounds);
+        value
+    }
+
+    /// Returns true if `player` can make sure of taking exactly `needed` of the
+    /// tricks left.
+    fn search_exactly(&mut self, player: PlayerId, needed: usize) -> bool {
+        if !self.trick.is_empty() {
+            return self.search_cards_exactly(player, needed);
+        }
+        let left = self.hands[self.leader.seat()].len();
+        if needed > left || left == 0 {
+            return needed == 0;
+        }
+
+        let position = self.position(player, Goal::Exactly(needed));
+        if let Some(outcome) = self.outcomes.get(&position) {
+            return *outcome;
+        }
+        let outcome = self.search_cards_exactly(player, needed);
+        self.outcomes.insert(position, outcome);
+        outcome
+    }
+
+    /// Searches each card the next player may play, for
+    /// [DoubleDummy::search_exactly]. The player needs one card to succeed, and the
+    /// other players need one card to make them fail.
+    fn search_cards_exactly(&mut self, player: PlayerId, needed: usize) -> bool {
+        let next = self.next_player().unwrap();
+
+        for card in self.distinct_cards() {
+            self.hands[next.seat()].remove(card);
+            self.trick.push((next, card));
+
+            let outcome = match self.trick.len() < self.hands.len() {
+                true => self.search_exactly(player, needed),
+                false => {
+                    let winner = self.trick_winner();
+                    let trick = std::mem::take(&mut self.trick);
+                    let leader = std::mem::replace(&mut self.leader, winner);
+
+                    let outcome = match (winner == player, needed) {
+                        (true, 0) => false,
+                        (true, needed) => self.search_exactly(player, needed - 1),
+                        (false, needed) => self.search_exactly(player, needed),
+                    };
+                    self.trick = trick;
+                    self.leader = leader;
+                    outcome
+                }
+            };
+            self.trick.pop();
+            self.hands[next.seat()].insert(card);
+
+            if outcome == (next == player) {
+                return outcome;
+            }
+        }
+        next != player
+    }
+
+    /// Returns the position at the start of a trick, for `player` playing towards
+    /// `goal`.
+    fn position(&self, player: PlayerId, goal: Goal) -> Position {
+        let mut hands = [CardSet::new(); Rules::MAX_PLAYERS];
+        hands[..self.hands.len()].copy_from_slice(&self.hands);
+        Position {
+            hands,
+            leader: self.leader,
+            player,
+            goal,
+        }
+    }
+
+    /// Searches each card the next player may play, for [DoubleDummy::search].
+    fn search_cards(
+        &mut self,
+        player: PlayerId,
+        goal: Goal,
+        mut alpha: usize,
+        mut beta: usize,
+    ) -> usize {
+        let next = self.next_player().unwrap();
+        let maximising = (next == player) == (goal == Goal::Most);
+        let mut best = match maximising {
+            true => 0,
+            false => usize::MAX,
+        };
+
+        for card in self.distinct_cards() {
+            self.hands[next.seat()].remove(card);
+            self.trick.push((next, card));
+
+            let value = match self.trick.len() < self.hands.len() {
+                true => self.search(player, goal, alpha, beta),
+                false => {
+                    let winner = self.trick_winner();
+                    let won = usize::from(winner == player);
+                    let trick = std::mem::take(&mut self.trick);
+                    let leader = std::mem::replace(&mut self.leader, winner);
+
+                    let value = self.search(player, goal, alpha.saturating_sub(won), beta - won);
+                    self.trick = trick;
+                    self.leader = leader;
+                    won + value
+                }
+            };
+            self.trick.pop();
+            self.hands[next.seat()].insert(card);
+
+            if maximising {
+                best = best.max(value);
+                alpha = alpha.max(value);
+            } else {
+                best = best.min(value);
+                beta = beta.min(value);
+            }
+            if alpha >= beta {
+                break;
+            }
+        }
+        best
+    }
+
+    /// Returns the cards the next player may play, leaving out any card equal to a
+    /// lower one held in the same suit, with no card left between them.
+    fn distinct_cards(&self) -> CardSet {
+        let mut left = self.hands.iter().fold(CardSet::new(), |all, e| all | *e);
+        left.extend(self.trick.iter().map(|e| e.1));
+
+        let mut cards = CardSet::new();
+        let mut previous: Option<Card> = None;
+        for card in self.legal_cards() {
+            let equal = previous.is_some_and(|e| {
+                e.suit() == card.suit()
+                    && !left
+                        .in_suit(card.suit())
+                        .iter()
+                        .any(|other| other.rank() > e.rank() && other.rank() < card.rank())
+            });
+            if !equal {
+                cards.insert(card);
+            }
+            previous = Some(card);
+        }
+        cards
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use rand::SeedableRng;
+
+    fn hand(cards: &[&str]) -> CardSet {
+        cards.iter().map(|e| e.parse::<Card>().unwrap()).collect()
+    }
+
+    /// Returns the tricks left taken by `player` towards `goal`, by trying every
+    /// card without any pruning.
+    fn minimax(solver: &DoubleDummy, player: PlayerId, goal: Goal) -> usize {
+        let next = match solver.next_player() {
+            Some(next) => next,
+            None => return 0,
+        };
+        let values = solver.legal_cards().iter().map(|card| {
+            let mut solver = solver.clone();
+            let won = solver.play_card(card).unwrap() == Some(player);
+            usize::from(won) + minimax(&solver, player, goal)
+        });
+        match (next == player) == (goal == Goal::Most) {
+            true => values.max().unwrap(),
+            false => values.min().unwrap(),
+        }
+    }
+
+    /// Returns true if `player` can take exactly `needed` tricks, by trying every card
+    /// without any pruning.
+    fn takes_exactly(solver: &DoubleDummy, player: PlayerId, needed: isize) -> bool {
+        let next = match solver.next_player() {
+            Some(next) => next,
+            None => return needed == 0,
+        };
+        let mut outcomes = solver.legal_cards().into_iter().map(|card| {
+            let mut solver = solver.clone();
+            let won = solver.play_card(card).unwrap() == Some(player);
+            takes_exactly(&solver, player, needed - isize::from(won))
+        });
+        match next == player {
+            true => outcomes.any(|e| e),
+            false => outcomes.all(|e| e),
+        }
+    }
+
+    #[test]
+    fn solves_random_deals_as_a_full_search_does() {
+        let mut rng = StdRng::seed_from_u64(4);
+        for _ in 0..20 {
+            let mut deck = CardSet::full().to_vec();
+            deck.shuffle(&mut rng);
+            let hands: Vec<CardSet> = deck.chunks(3).take(3).map(|e| e.iter().collect()).collect();
+            let mut solver = DoubleDummy::new(Suit::Spades, hands, PlayerId::new(1));
+
+            for seat in 0..3 {
+                let player = PlayerId::new(seat);
+                assert_eq!(
+                    solver.max_tricks(player),
+                    minimax(&solver, player, Goal::Most)
+                );
+                assert_eq!(
+                    solver.min_tricks(player),
+                    minimax(&solver, player, Goal::Fewest)
+                );
+                for tricks in 0..=3 {
+                    let expected = takes_exactly(&solver, player, tricks as isize);
+                    assert_eq!(solver.can_take(player, tricks), expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn most_and_fewest_tricks_can_differ() {
+        // Seat 0 wins with the ace of clubs whether it leads the ace or seat 1 leads a
+        // club back
+        let hands = vec![hand(&["2D", "AC"]), hand(&["AD", "3C"])];
+        let mut solver = DoubleDummy::new(Suit::Hearts, hands, PlayerId::new(0));
+
+        assert_eq!(solver.min_tricks(PlayerId::new(0)), 1);
+        assert_eq!(solver.max_tricks(PlayerId::new(0)), 1);
+
+        // Seat 1 can not lead a club back, so seat 0 takes no trick by leading low
+        let hands = vec![hand(&["2D", "AC"]), hand(&["AD", "3S"])];
+        let mut solver = DoubleDummy::new(Suit::Hearts, hands, PlayerId::new(0));
+
+        assert_eq!(solver.min_tricks(PlayerId::new(0)), 0);
+        assert_eq!(solver.max_tricks(PlayerId::new(0)), 1);
+    }
+
+    #[test]
+    fn solves_a_hand_part_way_through_a_trick() {
+        let mut hand = Hand::new(3, &Rules::default(), 4, PlayerId::new(2), 5).deal_players_in();
+        for seat in [2, 0, 1] {
+            hand.place_bid(PlayerId::new(seat), 1).unwrap();
+        }
+        let mut hand = hand.finish_bidding().ok().unwrap();
+        let id = hand.next_player().unwrap();
+        let card = hand.get_cards(id).lowest().unwrap();
+        hand.play_card(id, card).unwrap();
+
+        let mut solver = DoubleDummy::from_hand(&hand);
+        assert_eq!(solver.get_current_trick(), &[(id, card)]);
+
+        let total: usize = (0..3).map(|e| solver.max_tricks(PlayerId::new(e))).sum();
+        assert!(total >= 4, "total {}", total);
+        for seat in 0..3 {
+            let player = PlayerId::new(seat);
+            assert_eq!(
+                solver.max_tricks(player),
+                minimax(&solver, player, Goal::Most)
+            );
+        }
+    }
+}
@@ -0,0 +1,268 @@
+//! Exact play of the last few tricks of a hand.
+//!
+//! With few cards left, the rest of the hand can be solved exactly for every deal
+//! of the cards a player cannot see. [best_cards] finds these deals with
+//! [deals], solves each with a [DoubleDummy] for the player making exactly their
+//! bid, and returns the cards that make the bid in the most of them. Each deal
+//! counts for as much as it is likely, given the other players' habits in an
+//! [OpponentModel].
+//!
+//! Every deal that fits what the player has seen is solved when there are at most
+//! [MAX_DEALS] of them. Otherwise, such as when many cards were never dealt,
+//! [MAX_DEALS] deals are sampled at random.
+//!
+//! # Examples
+//! ```
+//! use contract_whist::endgame::{best_cards, deals};
+//! use contract_whist::opponents::OpponentModel;
+//! use contract_whist::{Card, GameView, PlayerId, Suit};
+//! use rand::rngs::StdRng;
+//! use rand::SeedableRng;
+//!
+//! let card = |text: &str| text.parse::<Card>().unwrap();
+//! let mut rng = StdRng::seed_from_u64(1);
+//!
+//! // Seat 2 needs the last trick, and seat 1 could not follow the ace of trumps
+//! let view = GameView::new(PlayerId::new(2), 3, card("2H"), 2)
+//!     .dealer(PlayerId::new(0))
+//!     .bids(vec![(PlayerId::new(0), 1), (PlayerId::new(1), 0), (PlayerId::new(2), 1)])
+//!     .current_trick(vec![(PlayerId::new(0), card("AH")), (PlayerId::new(1), card("5C"))])
+//!     .played_cards([card("AH"), card("5C")].iter().collect());
+//! let cards = [card("KD"), card("3S")];
+//!
+//! let deals = deals(&view, &cards, &mut rng);
+//! assert!(!deals.is_empty());
+//! assert!(deals.iter().all(|e| e[&PlayerId::new(1)].count_suit(Suit::Hearts) == 0));
+//!
+//! // The ace takes this trick whatever is played, so the king is kept for the last
+//! let best = best_cards(&view, &cards, &OpponentModel::new(), &mut rng);
+//! assert_eq!(best.to_vec(), vec![card("3S")]);
+//! ```
+
+use std::collections::HashMap;
+
+use rand::Rng;
+
+use crate::card::Card;
+use crate::card_set::CardSet;
+use crate::double_dummy::DoubleDummy;
+use crate::memory::CardMemory;
+use crate::opponents::OpponentModel;
+use crate::player::PlayerId;
+use crate::simulation::{cards_left, sample_hands};
+use crate::view::GameView;
+
+/// The most deals solved for each decision.
+pub const MAX_DEALS: usize = 200;
+
+/// Returns the deals of the cards still held by every player that could be true
+/// for the player seeing `view` and holding `cards`.
+///
+/// Every such deal is returned if there are at most [MAX_DEALS] of them, and
+/// otherwise [MAX_DEALS] deals are sampled with [sample_hands]. No deals are
+/// returned if none keeps to the voids shown, as when the view is not of a real
+/// hand.
+pub fn deals<R: Rng>(
+    view: &GameView,
+    cards: &[Card],
+    rng: &mut R,
+) -> Vec<HashMap<PlayerId, CardSet>> {
+    let memory = CardMemory::from_view(view);
+    let held: CardSet = cards.iter().collect();
+    let unseen = memory.unseen(held);
+    let others: Vec<PlayerId> = (0..view.get_num_players())
+        .map(PlayerId::new)
+        .filter(|e| *e != view.get_player())
+        .collect();
+
+    // The number of ways to deal the unseen cards, before taking out the deals that
+    // give a player a suit they are out of
+    let mut remaining = unseen.len();
+    let mut ways: f64 = 1.0;
+    for player in &others {
+        let count = cards_left(view, *player);
+        ways *= choose(remaining, count);
+        remaining = remaining.saturating_sub(count);
+    }
+    if ways > MAX_DEALS as f64 {
+        return (0..MAX_DEALS)
+            .filter_map(|_| sample_hands(view, cards, rng))
+            .collect();
+    }
+
+    let mut hands = HashMap::with_capacity(view.get_num_players());
+    hands.insert(view.get_player(), held);
+    let mut found = Vec::new();
+    deal_each(view, &memory, &others, unseen, &mut hands, &mut found);
+    found
+}
+
+/// Returns the legal cards for the player seeing `view` and holding `cards` that
+/// make their bid in the most [deals], with the rest of the hand played perfectly.
+///
+/// Each deal is weighted by [OpponentModel::deal_weight] from `model`, and the
+/// other players are taken to play against the bid. Returns an empty set if no
+/// card makes the bid in any deal, or if `cards` are not as many as the player
+/// has left in the hand.
+pub fn best_cards<R: Rng>(
+    view: &GameView,
+    cards: &[Card],
+    model: &OpponentModel,
+    rng: &mut R,
+) -> CardSet {
+    let player = view.get_player();
+    let legal: CardSet = view.legal_cards(cards).into_iter().collect();
+    let needed = view.get_bid(player).unwrap_or(0) - view.get_tricks_won(player);
+    if needed < 0 || cards.len() != cards_left(view, player) {
+        return CardSet::new();
+    }
+
+    let leader = match view.get_current_trick().first() {
+        Some((leader, _)) => *leader,
+        None => player,
+    };
+    let mut made: HashMap<Card, f64> = HashMap::with_capacity(legal.len());
+    for hands in deals(view, cards, rng) {
+        let weight = model.deal_weight(view, &hands);
+        let hands = (0..view.get_num_players())
+            .map(|seat| hands[&PlayerId::new(seat)])
+            .collect();
+        let solver = DoubleDummy::new(view.get_trump_suit(), hands, leader)
+            .rules(view.get_rules().clone())
+            .current_trick(view.get_current_trick().to_vec());
+
+        for card in legal {
+            let mut solver = solver.clone();
+            let won = solver.play_card(card).expect("the card may be played") == Some(player);
+            let needed = needed - isize::from(won);
+            if needed >= 0 && solver.can_take(player, needed as usize) {
+                *made.entry(card).or_default() += weight;
+            }
+        }
+    }
+
+    let most = made.values().copied().fold(0.0, f64::max);
+    legal
+        .into_iter()
+        .filter(|e| most > 0.0 && made.get(e) == Some(&most))
+        .collect()
+}
+
+/// Returns the number of ways to choose `k` of `n` things.
+fn choose(n: usize, k: usize) -> f64 {
+    match k > n {
+        true => 0.0,
+        false => (0..k).fold(1.0, |ways, i| ways * (n - i) as f64 / (i + 1) as f64),
+    }
+}
+
+/// Deals the cards in `unseen` to each player in `others` in every way that fits
+/// the suits they have shown they are out of, adding each deal to `found`.
+fn deal_each(
+    view: &GameView,
+    memory: &CardMemory,
+    others: &[PlayerId],
+    unseen: CardSet,
+    hands: &mut HashMap<PlayerId, CardSet>,
+    found: &mut Vec<HashMap<PlayerId, CardSet>>,
+) {
+    let (player, rest) = match others.split_first() {
+        Some(split) => split,
+        None => return found.push(hands.clone()),
+    };
+
+    let allowed = (unseen - memory.get_voids(*player)).to_vec();
+    let mut chosen = Vec::with_capacity(allowed.len());
+    choose_each(
+        &allowed,
+        cards_left(view, *player),
+        &mut chosen,
+        &mut |hand| {
+            hands.insert(*player, hand);
+            deal_each(view, memory, rest, unseen - hand, hands, found);
+        },
+    );
+}
+
+/// Calls `deal` with every set of `count` cards from `cards`.
+fn choose_each(
+    cards: &[Card],
+    count: usize,
+    chosen: &mut Vec<Card>,
+    deal: &mut dyn FnMut(CardSet),
+) {
+    if chosen.len() == count {
+        return deal(chosen.iter().collect());
+    }
+    for (index, card) in cards.iter().enumerate() {
+        chosen.push(*card);
+        choose_each(&cards[index + 1..], count, chosen, deal);
+        chosen.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::card;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    /// Returns a view of a two card hand where seat 1 has led the ten of clubs and
+    /// every card has been seen but `cards` and `unseen`.
+    fn setup(cards: &[Card], unseen: &[&str], bid: isize) -> GameView {
+        let mut played =
+            !(cards.iter().collect::<CardSet>() | unseen.iter().map(|e| card(e)).collect());
+        played.remove(card("2H"));
+        played.insert(card("10C"));
+
+        GameView::new(PlayerId::new(0), 2, card("2H"), 2)
+            .dealer(PlayerId::new(1))
+            .bids(vec![(PlayerId::new(1), 0), (PlayerId::new(0), bid)])
+            .current_trick(vec![(PlayerId::new(1), card("10C"))])
+            .played_cards(played)
+    }
+
+    #[test]
+    fn every_deal_is_found_when_there_are_few() {
+        let cards = [card("AC"), card("3S")];
+        let view = setup(&cards, &["4D", "5D", "QH"], 1);
+        let found = deals(&view, &cards, &mut StdRng::seed_from_u64(2));
+
+        assert_eq!(found.len(), 3);
+        for hands in &found {
+            assert_eq!(hands[&PlayerId::new(0)], cards.iter().collect());
+            assert_eq!(hands[&PlayerId::new(1)].len(), 1);
+        }
+    }
+
+    #[test]
+    fn plays_the_line_that_makes_the_bid() {
+        // The opponent's last card is a trump, which takes the trick after the ten, so
+        // the ace must win the ten for the bid of one
+        let cards = [card("AC"), card("2C")];
+        let view = setup(&cards, &["3H", "4H"], 1);
+        let best = best_cards(
+            &view,
+            &cards,
+            &OpponentModel::new(),
+            &mut StdRng::seed_from_u64(2),
+        );
+
+        assert_eq!(best.to_vec(), vec![card("AC")]);
+    }
+
+    #[test]
+    fn no_card_is_best_once_the_bid_is_broken() {
+        let cards = [card("AC"), card("3S")];
+        let view = setup(&cards, &["QH", "JS"], 0);
+
+        assert!(best_cards(
+            &view,
+            &cards,
+            &OpponentModel::new(),
+            &mut StdRng::seed_from_u64(2)
+        )
+        .is_empty());
+    }
+}
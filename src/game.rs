@@ -1,29 +1,868 @@
 //! Entrypoint for playing a game of Contract Whist.
 //!
+//! A [ContractWhistGame] is created from a list of [Player]s and the [Rules] to
+//! play with. Running the game plays every [Hand] in the rules, rotating the
+//! dealer each hand, and returns a [GameResult] with the results of each hand
+//! and the total points for each player.
+//!
+//! The state of the game is held in a [GameState], which owns all of its data
+//! and references players by their [PlayerId]. A GameState may be stored in
+//! long-lived server state and advanced one decision at a time, with
+//! [GameState::pending_decision] saying which player is to bid or play next.
+//!
+//! Every game has a seed. The deck for each hand, and any randomness used by the
+//! players, is seeded from the game seed, so two games with the same seed, rules,
+//! and players are played identically.
+//!
+//! A game may be saved between any two decisions with [ContractWhistGame::save] and
+//! resumed with [ContractWhistGame::from_state] once the save is read back with
+//! [SavedGame::read_from].
+//!
+//! # Examples
+//! ```
+//! use contract_whist::{AIPlayer, ContractWhistGame, Player, PlayerId, Rules};
+//!
+//! let players: Vec<Box<dyn Player>> = vec![
+//!     Box::new(AIPlayer::new("Mickey Mouse".to_string())),
+//!     Box::new(AIPlayer::new("Donald Duck".to_string())),
+//!     Box::new(AIPlayer::new("Goofy Dog".to_string())),
+//! ];
+//! let rules = Rules::default().hand_sizes(vec![1, 2, 3]);
+//!
+//! let result = ContractWhistGame::new(players, rules).unwrap().run();
+//!
+//! assert_eq!(result.get_hands().len(), 3);
+//! let total: isize = result.get_hands().iter().map(|e| e.get_scores()[&PlayerId::new(0)]).sum();
+//! assert_eq!(result.get_total(PlayerId::new(0)), total);
+//! ```
+//!
 //! # Todo
 //! - [ ] Update documentation
 //! - [ x ] Call a series of hands automatically
-//! - [ ] Add typestate pattern
-//! - [ x ] Prevent user from using an AI player name
+//! - [ x ] Add typestate pattern
+//! - [ x ] Key players on their seat so names need not be unique
+//! - [ x ] Save and resume a game in progress
+//! - [ x ] Take back moves in casual games
 
 use std::collections::HashMap;
-use std::io;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+
+use rand::rngs::StdRng;
+use rand::{random, Rng, SeedableRng};
 
-use crate::hand::Hand;
-use crate::player::{AIPlayer, HumanPlayer, Player};
-use crate::MAX_DISPLAY_WIDTH;
+use crate::card::Card;
+use crate::card_set::CardSet;
+use crate::difficulty::Difficulty;
+use crate::hand::{dealer_of, Bidding, Hand, HandError, InProgressHand, Playing};
+use crate::personality::Personality;
+use crate::player::{HumanPlayer, Player, PlayerId};
+use crate::record::{GameRecord, RecordWriter};
+use crate::rules::{Rules, RulesError};
+use crate::save::{SaveError, SavedGame};
+use crate::trick::Trick;
+use crate::view::GameView;
+use crate::{PlayerHands, MAX_DISPLAY_WIDTH};
 
 /// Struct of the Game.
-pub struct ContractWhistGame {}
+pub struct ContractWhistGame {
+    players: Vec<Box<dyn Player>>,
+    state: GameState,
+    recorder: Option<RecordWriter<Box<dyn Write + Send>>>,
+    recorded: usize,
+    casual: bool,
+    undo_points: Vec<(PlayerId, GameState)>,
+}
+
+/// The phase of the [Hand] currently being played in a [GameState].
+#[derive(Debug, Clone)]
+pub enum HandPhase {
+    /// Players are bidding on the current hand.
+    Bidding(InProgressHand<Bidding>),
+    /// Players are playing the tricks of the current hand.
+    Playing(InProgressHand<Playing>),
+    /// Every hand has been played.
+    Finished,
+}
+
+/// A decision waiting to be made by a player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    /// The player must place a bid.
+    Bid(PlayerId),
+    /// The player must play a card.
+    Play(PlayerId),
+}
+
+impl Decision {
+    /// Returns the player who must make the decision.
+    pub fn get_player(&self) -> PlayerId {
+        match self {
+            Decision::Bid(player) | Decision::Play(player) => *player,
+        }
+    }
+}
+
+/// Something that happened in a game, recorded by the [GameState] in the order it
+/// happened.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GameEvent {
+    /// A hand was dealt, holding the cards dealt to every player.
+    HandDealt {
+        dealer: PlayerId,
+        trump: Card,
+        deal: PlayerHands,
+    },
+    /// A player placed a bid.
+    BidPlaced { player: PlayerId, bid: isize },
+    /// A player played a card.
+    CardPlayed { player: PlayerId, card: Card },
+    /// A trick was won.
+    TrickWon { winner: PlayerId },
+    /// A hand was scored, holding the points for every player.
+    HandScored { scores: HashMap<PlayerId, isize> },
+    /// A player took back their last decision in a casual game, along with the
+    /// decisions made since. `moves` is the number of bids and cards taken back.
+    Undo { player: PlayerId, moves: usize },
+    /// A player ran out of time to make their decision. The bid or card that
+    /// follows was made in their place.
+    TimedOut { player: PlayerId },
+}
+
+impl GameEvent {
+    /// Returns true if the event is a bid or a card played.
+    pub fn is_move(&self) -> bool {
+        matches!(
+            self,
+            GameEvent::BidPlaced { .. } | GameEvent::CardPlayed { .. }
+        )
+    }
+}
+
+/// Removes the last `moves` bids and cards from `events`, along with every event
+/// that followed them and any timeout that led to them.
+pub(crate) fn take_back(events: &mut Vec<GameEvent>, moves: usize) {
+    let mut taken = 0;
+    while taken < moves {
+        match events.pop() {
+            Some(event) if event.is_move() => taken += 1,
+            Some(_) => (),
+            None => return,
+        }
+    }
+    while let Some(GameEvent::TimedOut { .. }) = events.last() {
+        events.pop();
+    }
+}
+
+/// Returns the events of a game with every [GameEvent::Undo], and the events it
+/// took back, removed.
+pub fn effective_events(events: &[GameEvent]) -> Vec<GameEvent> {
+    let mut effective = Vec::with_capacity(events.len());
+    for event in events.iter() {
+        match event {
+            GameEvent::Undo { moves, .. } => take_back(&mut effective, *moves),
+            event => effective.push(event.clone()),
+        }
+    }
+    effective
+}
+
+/// The owned state of a game of Contract Whist between two decisions.
+///
+/// The GameState holds the rules, the finished hands, the points so far, and the
+/// current [Hand] in its [HandPhase]. Each new hand is dealt as soon as the previous
+/// one is scored, with the dealer moving one seat to the left. Every deal, decision,
+/// and result is logged as a [GameEvent].
+#[derive(Debug, Clone)]
+pub struct GameState {
+    num_players: usize,
+    rules: Rules,
+    seed: u64,
+    hands: Vec<Hand>,
+    totals: HashMap<PlayerId, isize>,
+    phase: HandPhase,
+    events: Vec<GameEvent>,
+}
+
+/// Stream of seeds used to shuffle the deck for each hand.
+const HAND_SEEDS: u64 = 0;
+/// Stream of seeds used for each player's decisions.
+pub(crate) const PLAYER_SEEDS: u64 = 1;
+/// Stream of seeds used for the decisions made for players who run out of time.
+pub(crate) const TIMEOUT_SEEDS: u64 = 2;
+
+/// Derives the `index`th seed in a stream of seeds from the game seed.
+pub(crate) fn derive_seed(seed: u64, stream: u64, index: u64) -> u64 {
+    let mut bytes = [0u8; 32];
+    bytes[..8].copy_from_slice(&seed.to_le_bytes());
+    bytes[8..16].copy_from_slice(&stream.to_le_bytes());
+    bytes[16..24].copy_from_slice(&index.to_le_bytes());
+
+    StdRng::from_seed(bytes).gen()
+}
+
+impl GameState {
+    /// Creates a new game for `num_players` players and deals the first hand.
+    ///
+    /// The deck for each hand is shuffled with a seed derived from `seed`.
+    pub fn new(num_players: usize, rules: Rules, seed: u64) -> Result<GameState, RulesError> {
+        rules.validate(num_players)?;
+
+        let mut state = GameState {
+            num_players,
+            rules,
+            seed,
+            hands: Vec::new(),
+            totals: (0..num_players)
+                .map(|seat| (PlayerId::new(seat), 0))
+                .collect(),
+            phase: HandPhase::Finished,
+            events: Vec::new(),
+        };
+        state.deal_next_hand();
+
+        Ok(state)
+    }
+
+    /// Deals the next hand in the rules, or finishes the game if all hands are played.
+    fn deal_next_hand(&mut self) {
+        let index = self.hands.len();
+        match self.rules.get_hand_sizes().get(index).copied() {
+            Some(num_tricks) => {
+                let dealer = dealer_of(self.num_players, index);
+                let seed = derive_seed(self.seed, HAND_SEEDS, index as u64);
+                let hand = Hand::new(self.num_players, &self.rules, num_tricks, dealer, seed);
+                self.start_hand(hand.deal_players_in());
+            }
+            None => self.phase = HandPhase::Finished,
+        }
+    }
+
+    /// Replaces the current hand with one that has already been dealt.
+    ///
+    /// Used when restoring a saved game, whose deals are read from the save.
+    pub(crate) fn start_hand(&mut self, hand: InProgressHand<Bidding>) {
+        if let Some(GameEvent::HandDealt { .. }) = self.events.last() {
+            self.events.pop();
+        }
+        self.events.push(GameEvent::HandDealt {
+            dealer: hand.get_dealer(),
+            trump: hand.get_trump(),
+            deal: (0..self.num_players)
+                .map(|seat| {
+                    let id = PlayerId::new(seat);
+                    (id, hand.get_deal(id).to_vec())
+                })
+                .collect(),
+        });
+        self.phase = HandPhase::Bidding(hand);
+    }
+
+    /// Returns the number of players in the game.
+    pub fn get_num_players(&self) -> usize {
+        self.num_players
+    }
+
+    /// Returns the rules in use for the game.
+    pub fn get_rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    /// Returns the seed the game was created with.
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns the phase of the current hand.
+    pub fn get_phase(&self) -> &HandPhase {
+        &self.phase
+    }
+
+    /// Returns the finished hands, in the order they were played.
+    pub fn get_hands(&self) -> &[Hand] {
+        &self.hands
+    }
+
+    /// Returns the total points for every player so far.
+    pub fn get_totals(&self) -> &HashMap<PlayerId, isize> {
+        &self.totals
+    }
+
+    /// Returns every event of the game so far, in the order they happened.
+    ///
+    /// Decisions taken back in a casual game stay in the log, followed by a
+    /// [GameEvent::Undo]. Use [effective_events] to get the events of the game as
+    /// it stands.
+    pub fn get_events(&self) -> &[GameEvent] {
+        &self.events
+    }
+
+    /// Rewinds the game to an earlier state of the same game, logging that `player`
+    /// took back the decisions made since.
+    pub(crate) fn rewind(&mut self, earlier: GameState, player: PlayerId) {
+        let count_moves = |events: &[GameEvent]| {
+            effective_events(events)
+                .iter()
+                .filter(|e| e.is_move())
+                .count()
+        };
+        let moves = count_moves(&self.events) - count_moves(&earlier.events);
+
+        let mut events = std::mem::take(&mut self.events);
+        events.push(GameEvent::Undo { player, moves });
+        *self = GameState { events, ..earlier };
+    }
+
+    /// Takes back the last `moves` bids and cards for `player`, as [GameState::rewind]
+    /// does, by playing the game as it stands again from the first hand.
+    ///
+    /// Used when restoring a saved game, which holds the decisions taken back but not
+    /// the states they returned to. Returns an error if fewer moves were made.
+    pub(crate) fn take_back_moves(
+        &mut self,
+        player: PlayerId,
+        moves: usize,
+    ) -> Result<(), &'static str> {
+        let mut events = effective_events(&self.events);
+        if events.iter().filter(|e| e.is_move()).count() < moves {
+            return Err("more moves taken back than were made");
+        }
+        take_back(&mut events, moves);
+
+        let mut earlier = GameState::new(self.num_players, self.rules.clone(), self.seed)
+            .expect("the rules were checked when the game was created");
+        for event in events {
+            let result = match event {
+                GameEvent::HandDealt {
+                    dealer,
+                    trump,
+                    deal,
+                } => {
+                    let hand = Hand::from_deal(self.num_players, &self.rules, dealer, trump, deal);
+                    earlier.start_hand(hand);
+                    Ok(())
+                }
+                GameEvent::BidPlaced { player, bid } => earlier.place_bid(player, bid),
+                GameEvent::CardPlayed { player, card } => {
+                    earlier.play_card(player, card).map(|_| ())
+                }
+                _ => Ok(()),
+            };
+            result.expect("the moves were made in this game");
+        }
+        self.rewind(earlier, player);
+
+        Ok(())
+    }
+
+    /// Returns the [GameResult] of the hands played so far, with the players' names.
+    pub(crate) fn into_result(self, names: Vec<String>) -> GameResult {
+        GameResult {
+            names,
+            hands: self.hands,
+            totals: self.totals,
+        }
+    }
+
+    /// Returns true once every hand has been played.
+    pub fn is_finished(&self) -> bool {
+        matches!(self.phase, HandPhase::Finished)
+    }
+
+    /// Returns the cards held by a player in the current hand, or None if the game
+    /// is finished.
+    pub fn get_cards(&self, player: PlayerId) -> Option<CardSet> {
+        match &self.phase {
+            HandPhase::Bidding(hand) => Some(hand.get_cards(player)),
+            HandPhase::Playing(hand) => Some(hand.get_cards(player)),
+            HandPhase::Finished => None,
+        }
+    }
+
+    /// Returns the public information in the game as seen by `player`, or None if
+    /// the game is finished.
+    pub fn view(&self, player: PlayerId) -> Option<GameView> {
+        let view = match &self.phase {
+            HandPhase::Bidding(hand) => hand.view(player),
+            HandPhase::Playing(hand) => hand.view(player),
+            HandPhase::Finished => return None,
+        };
+
+        Some(view.history(self.hands.clone(), self.totals.clone()))
+    }
+
+    /// Returns the decision waiting to be made, or None if the game is finished.
+    pub fn pending_decision(&self) -> Option<Decision> {
+        match &self.phase {
+            HandPhase::Bidding(hand) => hand.next_bidder().map(Decision::Bid),
+            HandPhase::Playing(hand) => hand.next_player().map(Decision::Play),
+            HandPhase::Finished => None,
+        }
+    }
+
+    /// Logs that the player whose turn it is ran out of time to make their decision.
+    ///
+    /// The bid or card made in their place is then placed or played as usual.
+    pub fn time_out(&mut self, player: PlayerId) -> Result<(), HandError> {
+        match self.pending_decision() {
+            Some(decision) if decision.get_player() == player => (),
+            Some(_) => return Err(HandError::NotPlayersTurn(player)),
+            None => return Err(HandError::NoDecisionPending),
+        }
+        self.events.push(GameEvent::TimedOut { player });
+
+        Ok(())
+    }
+
+    /// Places a bid for the player whose turn it is to bid.
+    ///
+    /// Play of the hand begins once the last player has bid.
+    pub fn place_bid(&mut self, player: PlayerId, bid: isize) -> Result<(), HandError> {
+        match &mut self.phase {
+            HandPhase::Bidding(hand) => hand.place_bid(player, bid)?,
+            _ => return Err(HandError::NoDecisionPending),
+        };
+        self.events.push(GameEvent::BidPlaced { player, bid });
+
+        if let HandPhase::Bidding(hand) = std::mem::replace(&mut self.phase, HandPhase::Finished) {
+            self.phase = match hand.finish_bidding() {
+                Ok(hand) => HandPhase::Playing(hand),
+                Err(hand) => HandPhase::Bidding(hand),
+            };
+        }
+
+        Ok(())
+    }
+
+    /// Plays a card for the player whose turn it is to play.
+    ///
+    /// Returns the finished [Trick] if the card was the last one in the trick. Once the
+    /// last trick of a hand is played, the hand is scored and the next hand is dealt.
+    pub fn play_card(&mut self, player: PlayerId, card: Card) -> Result<Option<Trick>, HandError> {
+        let trick = match &mut self.phase {
+            HandPhase::Playing(hand) => hand.play_card(player, card)?,
+            _ => return Err(HandError::NoDecisionPending),
+        };
+        self.events.push(GameEvent::CardPlayed { player, card });
+        if let Some(trick) = &trick {
+            self.events.push(GameEvent::TrickWon {
+                winner: trick.get_winner(),
+            });
+        }
+
+        if let HandPhase::Playing(hand) = std::mem::replace(&mut self.phase, HandPhase::Finished) {
+            match hand.finish_playing() {
+                Ok(hand) => {
+                    let hand = hand.score_hand();
+                    for (id, points) in hand.get_scores().iter() {
+                        *self.totals.entry(*id).or_insert(0) += points;
+                    }
+                    self.events.push(GameEvent::HandScored {
+                        scores: hand.get_scores().clone(),
+                    });
+                    self.hands.push(hand);
+                    self.deal_next_hand();
+                }
+                Err(hand) => self.phase = HandPhase::Playing(hand),
+            }
+        }
+
+        Ok(trick)
+    }
+}
+
+/// The results of a finished [ContractWhistGame].
+#[derive(Debug, Clone)]
+pub struct GameResult {
+    names: Vec<String>,
+    hands: Vec<Hand>,
+    totals: HashMap<PlayerId, isize>,
+}
+
+impl GameResult {
+    /// Returns the names of the players, indexed by seat.
+    pub fn get_names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Returns the finished [Hand]s in the order they were played.
+    pub fn get_hands(&self) -> &[Hand] {
+        &self.hands
+    }
+
+    /// Returns the total points for every player.
+    pub fn get_totals(&self) -> &HashMap<PlayerId, isize> {
+        &self.totals
+    }
+
+    /// Returns the total points for a player.
+    pub fn get_total(&self, player: PlayerId) -> isize {
+        *self.totals.get(&player).unwrap_or(&0)
+    }
+
+    /// Returns the players with the highest total points.
+    pub fn winners(&self) -> Vec<PlayerId> {
+        let best = self.totals.values().max().copied().unwrap_or(0);
+        let mut winners: Vec<PlayerId> = self
+            .totals
+            .iter()
+            .filter(|(_, points)| **points == best)
+            .map(|(id, _)| *id)
+            .collect();
+        winners.sort();
+        winners
+    }
+}
 
 impl ContractWhistGame {
-    pub fn play(debug: bool) {
+    /// Creates a new game with a random seed, checking that the [Rules] can be played
+    /// by the players.
+    ///
+    /// Players are seated in the order given, so the first player has
+    /// `PlayerId::new(0)` and deals the first hand.
+    pub fn new(
+        players: Vec<Box<dyn Player>>,
+        rules: Rules,
+    ) -> Result<ContractWhistGame, RulesError> {
+        ContractWhistGame::with_seed(players, rules, random())
+    }
+
+    /// Creates a new game with the given seed, checking that the [Rules] can be played
+    /// by the players.
+    ///
+    /// Each player is seeded with a seed derived from `seed`, so the same seed gives
+    /// the same game.
+    pub fn with_seed(
+        players: Vec<Box<dyn Player>>,
+        rules: Rules,
+        seed: u64,
+    ) -> Result<ContractWhistGame, RulesError> {
+        let state = GameState::new(players.len(), rules, seed)?;
+
+        ContractWhistGame::from_state(players, state)
+    }
+
+    /// Creates a game that continues from a [GameState], such as one read from a
+    /// [SavedGame].
+    ///
+    /// Players are seeded from the game seed as in [ContractWhistGame::with_seed].
+    /// Returns an error if the number of players does not match the state.
+    pub fn from_state(
+        mut players: Vec<Box<dyn Player>>,
+        state: GameState,
+    ) -> Result<ContractWhistGame, RulesError> {
+        if players.len() != state.get_num_players() {
+            return Err(RulesError::PlayerCount(players.len()));
+        }
+
+        for (seat, player) in players.iter_mut().enumerate() {
+            player.set_seed(derive_seed(state.get_seed(), PLAYER_SEEDS, seat as u64));
+        }
+
+        Ok(ContractWhistGame {
+            players,
+            state,
+            recorder: None,
+            recorded: 0,
+            casual: false,
+            undo_points: Vec::new(),
+        })
+    }
+
+    /// Sets whether the game is casual.
+    ///
+    /// In a casual game, a [Player] may take back their last bid or card before
+    /// their next decision, rewinding the decisions of the other players made
+    /// since. Every take-back is logged as a [GameEvent::Undo].
+    pub fn casual(self, casual: bool) -> ContractWhistGame {
+        ContractWhistGame {
+            casual,
+            undo_points: Vec::new(),
+            ..self
+        }
+    }
+
+    /// Returns true if players may take back their decisions.
+    pub fn is_casual(&self) -> bool {
+        self.casual
+    }
+
+    /// Returns the seed the game was created with.
+    pub fn get_seed(&self) -> u64 {
+        self.state.get_seed()
+    }
+
+    /// Returns the players seated at the game.
+    pub fn get_players(&self) -> &[Box<dyn Player>] {
+        &self.players
+    }
+
+    /// Returns the current state of the game.
+    pub fn get_state(&self) -> &GameState {
+        &self.state
+    }
+
+    /// Returns the public information in the game as seen by `player`, including
+    /// the names of the players.
+    ///
+    /// Panics if the game is finished.
+    pub fn view(&self, player: PlayerId) -> GameView {
+        self.state
+            .view(player)
+            .expect("the game is not finished")
+            .names(self.get_names())
+    }
+
+    /// Asks the player for the pending decision and applies it to the game.
+    ///
+    /// Returns false once the game is finished. Panics if a [Player] makes a
+    /// decision the rules do not allow.
+    pub fn play_next(&mut self) -> bool {
+        let decision = match self.state.pending_decision() {
+            Some(decision) => decision,
+            None => return false,
+        };
+        let id = decision.get_player();
+        let view = self.view(id);
+
+        if self.casual {
+            if let Some(index) = self.undo_points.iter().rposition(|(e, _)| *e == id) {
+                if self.players[id.seat()].take_back(&view) {
+                    let (_, earlier) = self.undo_points.remove(index);
+                    self.undo_points.truncate(index);
+                    self.state.rewind(earlier, id);
+                    self.write_record();
+                    for seat in 0..self.players.len() {
+                        let view = self.view(PlayerId::new(seat));
+                        self.players[seat].decisions_taken_back(&view);
+                    }
+
+                    println!();
+                    println!("{} took back their last move", self.players[id.seat()]);
+                    return true;
+                }
+            }
+            self.undo_points.push((id, self.state.clone()));
+        }
+
+        // Every player is told of each card played, with their view from before it
+        let views: Vec<GameView> = match decision {
+            Decision::Bid(_) => Vec::new(),
+            Decision::Play(_) => (0..self.players.len())
+                .map(|seat| self.view(PlayerId::new(seat)))
+                .collect(),
+        };
+        let players = &self.players;
+        let player = &players[id.seat()];
+        let state = &mut self.state;
+        let hands_played = state.get_hands().len();
+        let mut played = None;
+
+        match decision {
+            Decision::Bid(_) => {
+                if view.get_bids().is_empty() {
+                    println!();
+                    println!("{} is dealing...", view.get_name(view.get_dealer()));
+                }
+                let bid = player.get_player_bid(&view, &state.get_cards(id).unwrap().to_vec());
+                state
+                    .place_bid(id, bid)
+                    .unwrap_or_else(|e| panic!("{} made an invalid bid: {}", player, e));
+
+                if let HandPhase::Playing(hand) = state.get_phase() {
+                    ContractWhistGame::display_bids(hand.view(id).get_bids(), players);
+                    println!();
+                    println!("Playing trick: 1");
+                }
+            }
+            Decision::Play(_) => {
+                let card = player.play_card(&view, &state.get_cards(id).unwrap().to_vec());
+                let trick = state
+                    .play_card(id, card)
+                    .unwrap_or_else(|e| panic!("{} made an invalid play: {}", player, e));
+                println!("{} played the {}", player, &card);
+                played = Some(card);
+
+                if let Some(trick) = trick {
+                    trick.display_trick(players);
+                    if let HandPhase::Playing(hand) = state.get_phase() {
+                        println!();
+                        println!("Playing trick: {}", hand.get_tricks().len() + 1);
+                    }
+                }
+            }
+        }
+
+        if let Some(hand) = state.get_hands().get(hands_played) {
+            println!();
+            println!("Points for Hand {}", hands_played + 1);
+            println!();
+            hand.display_points(players);
+
+            println!("Points through Hand {}", hands_played + 1);
+            ContractWhistGame::display_cumulative_points(state.get_totals(), players);
+        }
+
+        if let Some(card) = played {
+            for (player, view) in self.players.iter_mut().zip(&views) {
+                player.card_played(view, id, card);
+            }
+        }
+
+        let finished = self.state.is_finished();
+        self.write_record();
+
+        !finished
+    }
+
+    /// Returns the [GameRecord] of the game so far.
+    pub fn to_record(&self) -> GameRecord {
+        GameRecord::from_events(
+            self.get_names(),
+            self.state.get_rules().clone(),
+            self.state.get_seed(),
+            self.state.get_events(),
+        )
+    }
+
+    /// Returns the text record of the game so far.
+    pub fn record(&self) -> String {
+        self.to_record().to_text()
+    }
+
+    /// Writes the record of the game to `out` as it is played.
+    ///
+    /// Everything that has already happened in the game is written straight away.
+    pub fn record_to<W: Write + Send + 'static>(&mut self, out: W) -> io::Result<()> {
+        let out: Box<dyn Write + Send> = Box::new(out);
+        let writer = RecordWriter::new(
+            out,
+            &self.get_names(),
+            self.state.get_rules(),
+            self.state.get_seed(),
+        )?;
+
+        self.recorder = Some(writer);
+        self.recorded = 0;
+        self.write_record();
+
+        Ok(())
+    }
+
+    /// Feeds the events of the game that have not yet been recorded to the recorder.
+    fn write_record(&mut self) {
+        let writer = match self.recorder.as_mut() {
+            Some(writer) => writer,
+            None => return,
+        };
+
+        for event in self.state.get_events()[self.recorded..].iter() {
+            if let Err(e) = writer.write_event(event) {
+                println!("Could not write the game record: {}", e);
+                self.recorder = None;
+                return;
+            }
+        }
+        self.recorded = self.state.get_events().len();
+    }
+
+    /// Returns the names of the players, indexed by seat.
+    fn get_names(&self) -> Vec<String> {
+        self.players
+            .iter()
+            .map(|e| e.get_name().to_string())
+            .collect()
+    }
+
+    /// Returns a [SavedGame] of the game as it stands, with the [Difficulty] of each
+    /// AI player.
+    pub fn to_saved(&self) -> SavedGame {
+        let difficulties = self.players.iter().map(|e| e.get_difficulty()).collect();
+
+        SavedGame::new(self.get_names(), self.state.clone()).difficulties(difficulties)
+    }
+
+    /// Saves the game as it stands to a file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), SaveError> {
+        self.to_saved().write_to(path)
+    }
+
+    /// Plays every hand of the game and returns the results.
+    pub fn run(mut self) -> GameResult {
+        while self.play_next() {}
+
+        self.finish()
+    }
+
+    /// Prints the final scores and returns the results.
+    fn finish(self) -> GameResult {
+        println!();
+        println!("Final Scores");
+
+        ContractWhistGame::display_cumulative_points(self.state.get_totals(), &self.players);
+
+        let names = self.get_names();
+        self.state.into_result(names)
+    }
+
+    /// Sets up a game against AI opponents from stdinput and plays it.
+    ///
+    /// The game is played with `seed` if given, or a random seed otherwise. The game
+    /// is saved to [ContractWhistGame::SAVE_FILE] after every decision, and the player
+    /// is offered to resume it if it was left unfinished. A resumed game keeps the
+    /// seed it was saved with, and the player is told if `seed` is not used. If
+    /// `record` is given, the record of the game is written to it as the game is
+    /// played.
+    pub fn play(debug: bool, seed: Option<u64>, record: Option<&Path>) {
         println!();
         println!("{:^1$}", "Welcome to Contract Whist!", MAX_DISPLAY_WIDTH);
         println!();
 
+        let game = match ContractWhistGame::resume_saved_game() {
+            Some(game) => {
+                if seed.is_some_and(|e| e != game.get_seed()) {
+                    println!("The saved game keeps its own seed, so the seed given is not used.");
+                }
+                game
+            }
+            None => ContractWhistGame::setup_new_game(debug, seed),
+        };
+        let mut game = game.casual(ContractWhistGame::get_yes_or_no(
+            "Would you like a casual game, where you may take back your moves?",
+        ));
+
+        println!();
+        println!("Game seed: {}", game.get_seed());
+
+        if let Some(path) = record {
+            if let Err(e) = File::create(path).and_then(|file| game.record_to(file)) {
+                println!("Could not write the game record: {}", e);
+            }
+        }
+
+        game.autosave();
+        while game.play_next() {
+            game.autosave();
+        }
+        if Path::new(ContractWhistGame::SAVE_FILE).exists() {
+            if let Err(e) = std::fs::remove_file(ContractWhistGame::SAVE_FILE) {
+                println!("Could not remove the saved game: {}", e);
+            }
+        }
+
+        game.finish();
+    }
+
+    /// Asks the player for the number of opponents, their name, and the difficulty of
+    /// the opponents, and creates a game.
+    fn setup_new_game(debug: bool, seed: Option<u64>) -> ContractWhistGame {
         let num_players = ContractWhistGame::get_number_of_players();
         let player_name = ContractWhistGame::get_human_player_name();
+        let opponents = &ContractWhistGame::AI_PLAYER_NAMES[..num_players];
+        let names: Vec<&str> = opponents.iter().map(|(name, _)| *name).collect();
+        let difficulties = ContractWhistGame::get_difficulties(&names);
 
         println!();
         println!();
@@ -32,54 +871,77 @@ impl ContractWhistGame {
         let human_player = HumanPlayer::new(player_name);
         players.push(Box::new(human_player));
 
-        for i in 0..num_players {
-            let ai = AIPlayer::new(
-                ContractWhistGame::AI_PLAYER_NAMES
-                    .get(i)
-                    .unwrap()
-                    .to_string(),
-            );
-            players.push(Box::new(ai));
+        for ((name, personality), difficulty) in opponents.iter().zip(difficulties) {
+            players.push(difficulty.create_player(name.to_string(), *personality));
         }
 
         println!("Welcome, {}", &players[0].get_name());
         println!("You are playing with:");
         ContractWhistGame::display_players(&players);
 
-        let tricks = match debug {
-            false => ContractWhistGame::TRICKS_PER_HAND.to_vec(),
-            true => ContractWhistGame::DEBUG_TRICKS.to_vec(),
+        let rules = match debug {
+            false => Rules::default(),
+            true => Rules::debug(),
         };
 
-        let mut cumulative_points: HashMap<&Box<dyn Player>, isize> =
-            HashMap::with_capacity(players.len());
+        ContractWhistGame::with_seed(players, rules, seed.unwrap_or_else(random))
+            .expect("1 to 6 opponents can always play")
+    }
 
-        for (index, trick_num) in tricks.iter().enumerate() {
-            let hand = Hand::new(&players, *trick_num, &players[index % players.len()])
-                .deal_players_in()
-                .get_player_bids()
-                .play_tricks()
-                .score_hand();
-            println!();
-            println!("Points for Hand {}", index + 1);
-            println!();
-            hand.display_points();
+    /// Offers to resume the game in the save file, if there is one.
+    ///
+    /// The first seat is played by the human player and every other seat by an AI
+    /// player of the [Difficulty] saved for it, or [Difficulty::default] for a save
+    /// that does not hold one.
+    fn resume_saved_game() -> Option<ContractWhistGame> {
+        if !Path::new(ContractWhistGame::SAVE_FILE).exists() {
+            return None;
+        }
 
-            for (player, points) in hand.get_scores().iter() {
-                cumulative_points
-                    .entry(player)
-                    .and_modify(|e| *e += points)
-                    .or_insert(*points);
+        let saved = match SavedGame::read_from(ContractWhistGame::SAVE_FILE) {
+            Ok(saved) => saved,
+            Err(e) => {
+                println!("The saved game could not be loaded ({}).", e);
+                return None;
             }
+        };
 
-            println!("Points through Hand {}", index + 1);
-            ContractWhistGame::display_cumulative_points(&cumulative_points, &players);
+        if !ContractWhistGame::get_yes_or_no(
+            "An unfinished game was found. Would you like to resume it?",
+        ) {
+            return None;
+        }
+
+        let difficulties = saved.get_difficulties().to_vec();
+        let (names, state) = saved.into_parts();
+        let mut players: Vec<Box<dyn Player>> = Vec::with_capacity(names.len());
+        for (seat, (name, difficulty)) in names.into_iter().zip(difficulties).enumerate() {
+            match seat {
+                0 => players.push(Box::new(HumanPlayer::new(name))),
+                _ => {
+                    let personality = ContractWhistGame::get_personality(&name);
+                    players.push(
+                        difficulty
+                            .unwrap_or_default()
+                            .create_player(name, personality),
+                    )
+                }
+            }
         }
 
         println!();
-        println!("Final Scores");
+        println!("Welcome back, {}", &players[0].get_name());
+        println!("You are playing with:");
+        ContractWhistGame::display_players(&players);
 
-        ContractWhistGame::display_cumulative_points(&cumulative_points, &players);
+        ContractWhistGame::from_state(players, state).ok()
+    }
+
+    /// Saves the game to the save file, warning the player if it could not be saved.
+    fn autosave(&self) {
+        if let Err(e) = self.save(ContractWhistGame::SAVE_FILE) {
+            println!("Could not save the game: {}", e);
+        }
     }
 
     fn display_players(players: &[Box<dyn Player>]) {
@@ -91,15 +953,26 @@ impl ContractWhistGame {
         }
     }
 
+    fn display_bids(bids: &[(PlayerId, isize)], players: &[Box<dyn Player>]) {
+        println!();
+        println!("{:-^1$}", "Player Bids", MAX_DISPLAY_WIDTH);
+
+        for (id, bid) in bids.iter() {
+            println!("- {: <2$} {}", players[id.seat()], bid, 20);
+        }
+
+        println!();
+    }
+
     fn display_cumulative_points(
-        cumulative_points: &HashMap<&Box<dyn Player>, isize>,
+        cumulative_points: &HashMap<PlayerId, isize>,
         players: &[Box<dyn Player>],
     ) {
         println!();
         println!("     Player         Score");
         println!("{}", "-".repeat(26));
-        for player in players.iter() {
-            let points = cumulative_points.get(player).unwrap();
+        for (seat, player) in players.iter().enumerate() {
+            let points = cumulative_points.get(&PlayerId::new(seat)).unwrap();
             println!("{:<20} {:^5}", format!("{}", player), points);
         }
     }
@@ -124,6 +997,65 @@ impl ContractWhistGame {
         }
     }
 
+    /// Asks the player for the difficulty of the AI opponents named `names`, for all
+    /// of them at once or for each in turn.
+    fn get_difficulties(names: &[&str]) -> Vec<Difficulty> {
+        println!();
+        if names.len() > 1
+            && ContractWhistGame::get_yes_or_no(
+                "Would you like to choose the difficulty of each opponent?",
+            )
+        {
+            return names
+                .iter()
+                .map(|e| format!("How well should {} play?", e))
+                .map(|question| ContractWhistGame::get_difficulty(&question))
+                .collect();
+        }
+
+        let difficulty =
+            ContractWhistGame::get_difficulty("How well should the computer opponents play?");
+        vec![difficulty; names.len()]
+    }
+
+    fn get_difficulty(question: &str) -> Difficulty {
+        loop {
+            let mut input = String::new();
+            println!("{}", question);
+            for (index, difficulty) in Difficulty::VALUES.iter().enumerate() {
+                println!("{}. {}", index + 1, difficulty);
+            }
+            match io::stdin().read_line(&mut input) {
+                Ok(_) => {
+                    let chosen = match input.trim().parse::<usize>() {
+                        Ok(index) => Difficulty::VALUES.get(index.wrapping_sub(1)).copied(),
+                        Err(_) => input.parse().ok(),
+                    };
+                    match chosen {
+                        Some(difficulty) => return difficulty,
+                        None => println!("Please choose one of the difficulties listed!"),
+                    }
+                }
+                Err(_) => println!("There was an error attemping to read your input."),
+            }
+        }
+    }
+
+    fn get_yes_or_no(question: &str) -> bool {
+        loop {
+            let mut input = String::new();
+            println!("{} (y/n)", question);
+            match io::stdin().read_line(&mut input) {
+                Ok(_) => match input.trim().to_lowercase().as_str() {
+                    "y" | "yes" => return true,
+                    "n" | "no" => return false,
+                    _ => println!("Please answer y or n!"),
+                },
+                Err(_) => println!("There was an error attemping to read your input."),
+            }
+        }
+    }
+
     fn get_human_player_name() -> String {
         loop {
             let mut input = String::new();
@@ -132,10 +1064,7 @@ impl ContractWhistGame {
                 Ok(_) => {
                     let name = input.trim();
                     match name.is_empty() {
-                        false => match Self::AI_PLAYER_NAMES.contains(&name) {
-                            true => println!("That name is already in use by an opponent!"),
-                            false => return name.to_string(),
-                        },
+                        false => return name.to_string(),
                         true => println!("Please provide a name!"),
                     }
                 }
@@ -144,15 +1073,308 @@ impl ContractWhistGame {
         }
     }
 
-    const AI_PLAYER_NAMES: [&'static str; 6] = [
-        "Mickey Mouse",
-        "Minnie Mouse",
-        "Donald Duck",
-        "Daffy Duck",
-        "Goofy Dog",
-        "Pluto Dog",
+    /// File the game played from stdinput is saved to after every decision.
+    pub const SAVE_FILE: &'static str = "contract_whist_save.txt";
+
+    /// Names of the AI opponents, each with the [Personality] it plays with.
+    const AI_PLAYER_NAMES: [(&'static str, Personality); 6] = [
+        ("Mickey Mouse", Personality::CAUTIOUS),
+        ("Minnie Mouse", Personality::TRUMP_HAPPY),
+        ("Donald Duck", Personality::GAMBLER),
+        ("Daffy Duck", Personality::GAMBLER),
+        ("Goofy Dog", Personality::TRUMP_HAPPY),
+        ("Pluto Dog", Personality::CAUTIOUS),
     ];
 
-    const TRICKS_PER_HAND: [usize; 13] = [1, 2, 3, 4, 5, 6, 7, 6, 5, 4, 3, 2, 1];
-    const DEBUG_TRICKS: [usize; 5] = [1, 3, 5, 7, 1];
+    /// Returns the personality of the AI opponent called `name`, or the default
+    /// personality for a name outside [ContractWhistGame::AI_PLAYER_NAMES].
+    fn get_personality(name: &str) -> Personality {
+        ContractWhistGame::AI_PLAYER_NAMES
+            .into_iter()
+            .find(|(player, _)| *player == name)
+            .map(|(_, personality)| personality)
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::AIPlayer;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    fn setup_players() -> Vec<Box<dyn Player>> {
+        vec![
+            Box::new(AIPlayer::new("Mickey Mouse".to_string())),
+            Box::new(AIPlayer::new("Minnie Mouse".to_string())),
+        ]
+    }
+
+    /// An AI player that takes back its last move a set number of times.
+    ///
+    /// Checks that it has been told of every card played in the hand so far.
+    #[derive(Clone)]
+    struct TakeBackPlayer {
+        ai: AIPlayer,
+        take_backs: Arc<AtomicUsize>,
+        seen: CardSet,
+    }
+
+    impl Player for TakeBackPlayer {
+        fn get_name(&self) -> &String {
+            self.ai.get_name()
+        }
+
+        fn play_card(&self, view: &GameView, cards: &[Card]) -> Card {
+            assert_eq!(self.seen, view.get_played_cards());
+            self.ai.play_card(view, cards)
+        }
+
+        fn display_hand(&self, _: &[Card]) {}
+
+        fn get_player_bid(&self, view: &GameView, cards: &[Card]) -> isize {
+            self.ai.get_player_bid(view, cards)
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Player> {
+            Box::new(self.clone())
+        }
+
+        fn take_back(&self, _: &GameView) -> bool {
+            self.take_backs
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |e| e.checked_sub(1))
+                .is_ok()
+        }
+
+        fn card_played(&mut self, view: &GameView, player: PlayerId, card: Card) {
+            if view.get_played_cards().is_empty() {
+                self.seen = CardSet::new();
+            }
+            assert_eq!(self.seen, view.get_played_cards());
+            self.seen.insert(card);
+            self.ai.card_played(view, player, card);
+        }
+
+        fn decisions_taken_back(&mut self, view: &GameView) {
+            self.seen = view.get_played_cards();
+            self.ai.decisions_taken_back(view);
+        }
+    }
+
+    fn setup_take_back_game(take_backs: usize, casual: bool) -> ContractWhistGame {
+        let players: Vec<Box<dyn Player>> = vec![
+            Box::new(TakeBackPlayer {
+                ai: AIPlayer::new("Mickey Mouse".to_string()),
+                take_backs: Arc::new(AtomicUsize::new(take_backs)),
+                seen: CardSet::new(),
+            }),
+            Box::new(AIPlayer::new("Minnie Mouse".to_string())),
+        ];
+        let rules = Rules::default().hand_sizes(vec![3, 2]);
+
+        ContractWhistGame::with_seed(players, rules, 42)
+            .unwrap()
+            .casual(casual)
+    }
+
+    #[test]
+    fn new_game_rejects_invalid_rules() {
+        let rules = Rules::default().hand_sizes(vec![]);
+
+        assert_eq!(
+            ContractWhistGame::new(setup_players(), rules).err(),
+            Some(RulesError::NoHands)
+        );
+    }
+
+    #[test]
+    fn game_state_moves_through_every_decision() {
+        let mut state = GameState::new(2, Rules::default().hand_sizes(vec![1]), 7).unwrap();
+
+        assert_eq!(
+            state.pending_decision(),
+            Some(Decision::Bid(PlayerId::new(0)))
+        );
+        state.place_bid(PlayerId::new(0), 0).unwrap();
+        state.place_bid(PlayerId::new(1), 1).unwrap();
+
+        let view = state.view(PlayerId::new(1)).unwrap();
+        assert_eq!(
+            view.get_bids(),
+            &[(PlayerId::new(0), 0), (PlayerId::new(1), 1)]
+        );
+
+        while let Some(Decision::Play(id)) = state.pending_decision() {
+            let card = state.get_cards(id).unwrap().lowest().unwrap();
+            state.play_card(id, card).unwrap();
+        }
+
+        assert!(state.is_finished());
+        assert_eq!(state.get_hands().len(), 1);
+        assert!(matches!(state.get_events()[0], GameEvent::HandDealt { .. }));
+        assert_eq!(
+            state.get_events()[1],
+            GameEvent::BidPlaced {
+                player: PlayerId::new(0),
+                bid: 0
+            }
+        );
+        assert!(matches!(
+            state.get_events().last(),
+            Some(GameEvent::HandScored { .. })
+        ));
+        assert_eq!(
+            state.place_bid(PlayerId::new(0), 0),
+            Err(HandError::NoDecisionPending)
+        );
+    }
+
+    #[test]
+    fn same_seed_plays_the_same_game() {
+        let rules = Rules::default().hand_sizes(vec![3, 2, 1]);
+
+        let first = ContractWhistGame::with_seed(setup_players(), rules.clone(), 42)
+            .unwrap()
+            .run();
+        let second = ContractWhistGame::with_seed(setup_players(), rules, 42)
+            .unwrap()
+            .run();
+
+        assert_eq!(first.get_totals(), second.get_totals());
+        for (first, second) in first.get_hands().iter().zip(second.get_hands()) {
+            assert_eq!(first.get_trump(), second.get_trump());
+            assert_eq!(first.get_tricks(), second.get_tricks());
+        }
+    }
+
+    #[test]
+    fn hands_are_dealt_with_different_seeds() {
+        assert_ne!(
+            derive_seed(42, HAND_SEEDS, 0),
+            derive_seed(42, HAND_SEEDS, 1)
+        );
+        assert_ne!(
+            derive_seed(42, HAND_SEEDS, 0),
+            derive_seed(42, PLAYER_SEEDS, 0)
+        );
+    }
+
+    #[test]
+    fn every_ai_opponent_plays_with_a_preset() {
+        for (name, personality) in ContractWhistGame::AI_PLAYER_NAMES {
+            assert_ne!(personality, Personality::default());
+            assert!(Personality::PRESETS.iter().any(|(_, e)| *e == personality));
+            assert_eq!(ContractWhistGame::get_personality(name), personality);
+        }
+        assert_eq!(
+            ContractWhistGame::get_personality("Huey Duck"),
+            Personality::default()
+        );
+    }
+
+    #[test]
+    fn game_resumes_from_state() {
+        let mut state = GameState::new(2, Rules::default().hand_sizes(vec![2, 1]), 7).unwrap();
+        state.place_bid(PlayerId::new(0), 1).unwrap();
+
+        let game = ContractWhistGame::from_state(setup_players(), state.clone()).unwrap();
+        assert_eq!(
+            game.get_state().pending_decision(),
+            state.pending_decision()
+        );
+        assert_eq!(game.to_saved().get_names()[1], "Minnie Mouse");
+        assert_eq!(
+            game.to_saved().get_difficulties()[1],
+            Some(Difficulty::Intermediate)
+        );
+
+        assert_eq!(
+            ContractWhistGame::from_state(setup_players()[..1].to_vec(), state).err(),
+            Some(RulesError::PlayerCount(1))
+        );
+    }
+
+    #[test]
+    fn casual_take_back_rewinds_to_the_players_last_decision() {
+        let mut game = setup_take_back_game(1, true);
+        let earlier = game.get_state().clone();
+        // Mickey bids, then Minnie bids
+        game.play_next();
+        game.play_next();
+
+        // Mickey takes back the bid before leading, rewinding Minnie's bid
+        assert!(game.play_next());
+
+        let state = game.get_state();
+        assert_eq!(state.pending_decision(), earlier.pending_decision());
+        assert_eq!(
+            effective_events(state.get_events()),
+            effective_events(earlier.get_events())
+        );
+        assert_eq!(
+            state.get_events().last(),
+            Some(&GameEvent::Undo {
+                player: PlayerId::new(0),
+                moves: 2
+            })
+        );
+    }
+
+    #[test]
+    fn take_backs_are_only_offered_in_casual_games() {
+        let mut game = setup_take_back_game(1, false);
+        while game.play_next() {}
+
+        assert!(!game
+            .get_state()
+            .get_events()
+            .iter()
+            .any(|e| matches!(e, GameEvent::Undo { .. })));
+    }
+
+    #[test]
+    fn game_with_take_backs_records_the_game_as_played() {
+        let path = std::env::temp_dir().join("contract_whist_take_back_record.txt");
+        let mut game = setup_take_back_game(5, true);
+        game.record_to(File::create(&path).unwrap()).unwrap();
+        while game.play_next() {}
+        let streamed = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let undos = game
+            .get_state()
+            .get_events()
+            .iter()
+            .filter(|e| matches!(e, GameEvent::Undo { .. }))
+            .count();
+        let state = GameRecord::parse(&game.record()).unwrap().replay().unwrap();
+
+        assert_eq!(undos, 5);
+        assert_eq!(state.get_totals(), game.get_state().get_totals());
+        assert_eq!(
+            GameRecord::parse(&streamed).unwrap(),
+            GameRecord::parse(&game.record()).unwrap()
+        );
+    }
+
+    #[test]
+    fn game_is_send_and_static() {
+        fn assert_send<T: Send + 'static>() {}
+        assert_send::<GameState>();
+        assert_send::<ContractWhistGame>();
+    }
+
+    #[test]
+    fn running_game_plays_every_hand_in_the_rules() {
+        let rules = Rules::default().hand_sizes(vec![2, 1]);
+
+        let result = ContractWhistGame::new(setup_players(), rules)
+            .unwrap()
+            .run();
+
+        assert_eq!(result.get_hands().len(), 2);
+        assert_eq!(result.get_hands()[1].get_dealer(), PlayerId::new(1));
+        assert_eq!(result.get_names()[0], "Mickey Mouse");
+    }
 }
@@ -1,19 +1,25 @@
 //! A play of a [Hand] (or round) of Contract Whist, consisting of dealing players in,
 //! asking for bids, playing the tricks, and storing the resulting scores.
 //!
-//! The Hand progresses through 6 states. The first, Start, is only to create the Hand.
-//! The players and number of tricks in the hand are saved and the deck is created. In
-//! the Dealing state, cards are dealt to each player. The hand is then transitioned to
-//! the Bidding state. In this state, each player is asked for their bid for the hand.
-//! The hand then enters the Playing state. While Playing, the hand plays through a
+//! The Hand progresses through 5 states. The first, Dealing, is returned when creating
+//! the Hand. The number of players and tricks in the hand are saved and the deck is
+//! created. Dealing gives cards to each player and transitions the hand to the Bidding
+//! state. In this state, each player places their bid for the hand. The hand then
+//! enters the Playing state. While Playing, each player plays their cards through a
 //! number of tricks passed to the hand during instantiation. When finished playing all
 //! tricks, the hand begins Scoring. Scoring is used to compare the actual number of
 //! tricks won to the player's bid of tricks won and points tallied.
 //!
+//! The Hand owns all of its data and references players by their [PlayerId], so a
+//! Hand in any state may be kept in a struct, paused between decisions, or sent to
+//! another thread. Decisions are made one at a time with `place_bid` and `play_card`,
+//! which check that the decision is legal, or all at once by asking a list of
+//! [Player]s with `get_player_bids` and `play_tricks`.
+//!
 //! # Todo
 //! - [x] Implement scoring logic
 //! - [x] Change the order of the players passed to the trick based on the previous trick's winner
-//! - [ ] Update layout so that Trick::new returns a Builder and Trick<Finished> is just a Trick struct
+//! - [x] Update layout so that Trick::new returns a Builder and Trick<Finished> is just a Trick struct
 
 //! # States
 //! [Dealing]: the hand is dealing all players in and setting trump <br>
@@ -21,26 +27,40 @@
 //! [Playing]: playing the hand by playing a series of tricks <br>
 //! [Scoring]: players are being scored on the hand <br>
 
-use std::collections::hash_map::Entry;
 use std::collections::HashMap;
+use std::fmt;
 
 use crate::card::Card;
+use crate::card_set::CardSet;
 use crate::deck::{Deck, DeckType};
-use crate::player::Player;
-use crate::trick::Trick;
-use crate::{PlayerHands, MAX_DISPLAY_WIDTH};
-
-/// Base struct of the Hand, used with the HandState trait structs.
+use crate::player::{Player, PlayerId};
+use crate::rules::Rules;
+use crate::suit::Suit;
+use crate::trick::{self, InProgressTrick, Trick};
+use crate::view::GameView;
+use crate::PlayerHands;
+
+/// A finished Hand, holding the results of playing it.
 ///
-/// The Hand progresses through 5 states which determine the data stored in the hand.
-#[derive(Debug)]
-pub struct Hand<'a> {
-    players: &'a Vec<Box<dyn Player>>,
-    points: HashMap<&'a Box<dyn Player>, isize>,
+/// The Hand progresses through 5 states which determine the data stored in the hand,
+/// finishing with the trump, bids, tricks, and points for each player.
+#[derive(Debug, Clone)]
+pub struct Hand {
+    num_tricks: usize,
+    dealer: PlayerId,
+    trump: Card,
+    deal: PlayerHands,
+    bids: HashMap<PlayerId, isize>,
+    tricks: Vec<Trick>,
+    tricks_won: HashMap<PlayerId, isize>,
+    points: HashMap<PlayerId, isize>,
 }
 
-pub struct InProgressHand<'a, T: HandState> {
-    players: &'a Vec<Box<dyn Player>>,
+/// A Hand that is being played, used with the HandState trait structs.
+#[derive(Debug, Clone)]
+pub struct InProgressHand<T: HandState> {
+    num_players: usize,
+    rules: Rules,
     extra: T,
 }
 
@@ -48,72 +68,182 @@ pub struct InProgressHand<'a, T: HandState> {
 ///
 /// Provides a hand of cards for each player, sets the trump, and sets the hand to the
 /// [Bidding] state.
-#[derive(Debug)]
-pub struct Dealing<'a> {
+#[derive(Debug, Clone)]
+pub struct Dealing {
     deck: Deck,
     num_tricks: usize,
-    dealer: &'a Box<dyn Player>,
+    dealer: PlayerId,
 }
 
 /// State of the [Hand] while gathering bids.
 ///
-/// Asks [Player]s for their bids this [Hand] and stores them.
-#[derive(Debug)]
-pub struct Bidding<'a> {
+/// Collects the bid of each [Player] in turn, starting with the dealer.
+#[derive(Debug, Clone)]
+pub struct Bidding {
     trump: Card,
-    player_hands: HashMap<&'a Box<dyn Player>, Vec<Card>>,
+    deal: PlayerHands,
+    player_hands: HashMap<PlayerId, CardSet>,
     num_tricks: usize,
-    dealer: &'a Box<dyn Player>,
-    bid_order: Vec<&'a Box<dyn Player>>,
+    dealer: PlayerId,
+    bid_order: Vec<PlayerId>,
+    bids: HashMap<PlayerId, isize>,
 }
 
 /// State of the [Hand] while playing a series of [Trick]s.
 ///
 /// Plays a number of [Trick]s equal to the `num-trick` parameter passed when creating
 /// the [Hand].
-#[derive(Debug)]
-pub struct Playing<'a> {
-    bids: HashMap<&'a Box<dyn Player>, isize>,
+#[derive(Debug, Clone)]
+pub struct Playing {
+    bids: HashMap<PlayerId, isize>,
+    bid_order: Vec<PlayerId>,
     trump: Card,
+    deal: PlayerHands,
     num_tricks: usize,
-    player_hands: HashMap<&'a Box<dyn Player>, Vec<Card>>,
-    initial_player_order: Vec<&'a Box<dyn Player>>,
+    dealer: PlayerId,
+    player_hands: HashMap<PlayerId, CardSet>,
+    played: CardSet,
+    trick: InProgressTrick<trick::Playing>,
+    tricks: Vec<Trick>,
+    tricks_won: HashMap<PlayerId, isize>,
 }
 
 /// State of the [Hand] while scoring the players.
 ///
 /// Checks each player's bid vs actual tricks taken and determines points for the [Hand].
-#[derive(Debug)]
-pub struct Scoring<'a> {
-    bids: HashMap<&'a Box<dyn Player>, isize>,
-    tricks_won: HashMap<&'a Box<dyn Player>, isize>,
+#[derive(Debug, Clone)]
+pub struct Scoring {
+    trump: Card,
+    deal: PlayerHands,
+    num_tricks: usize,
+    dealer: PlayerId,
+    bids: HashMap<PlayerId, isize>,
+    tricks: Vec<Trick>,
+    tricks_won: HashMap<PlayerId, isize>,
 }
 
-// /// Final state of the [Hand] containing total points scored by player.
-// #[derive(Debug)]
-// pub struct Finished<'a> {
-//     points: HashMap<&'a Box<dyn Player>, isize>,
-// }
+/// Returns the cards dealt to each player as the [CardSet] they hold.
+fn held_cards(deal: &PlayerHands) -> HashMap<PlayerId, CardSet> {
+    deal.iter()
+        .map(|(id, cards)| (*id, cards.iter().collect()))
+        .collect()
+}
 
 /// Used to constraint the structs that may be used with [Hand].
 pub trait HandState {}
-impl<'a> HandState for Dealing<'a> {}
-impl<'a> HandState for Bidding<'a> {}
-impl<'a> HandState for Playing<'a> {}
-impl<'a> HandState for Scoring<'a> {}
+impl HandState for Dealing {}
+impl HandState for Bidding {}
+impl HandState for Playing {}
+impl HandState for Scoring {}
+
+/// Reasons a bid or play is refused by the [Hand].
+#[derive(Debug, Clone, PartialEq)]
+pub enum HandError {
+    /// It is not this player's turn to bid or play.
+    NotPlayersTurn(PlayerId),
+    /// The bid is not between 0 and the number of tricks in the hand.
+    InvalidBid(isize),
+    /// The player does not hold the card.
+    CardNotHeld(Card),
+    /// The player holds a card in the led suit, so must follow suit.
+    MustFollowSuit(Suit),
+    /// No bid or play is waiting to be made.
+    NoDecisionPending,
+}
+
+impl fmt::Display for HandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HandError::NotPlayersTurn(player) => write!(f, "It is not {}'s turn", player),
+            HandError::InvalidBid(bid) => write!(f, "{} is not a valid bid", bid),
+            HandError::CardNotHeld(card) => write!(f, "The {} is not in the hand", card),
+            HandError::MustFollowSuit(suit) => write!(f, "You must follow suit in {}", suit),
+            HandError::NoDecisionPending => write!(f, "No bid or play is waiting to be made"),
+        }
+    }
+}
+
+impl std::error::Error for HandError {}
+
+/// Returns the seats of all players in turn order, starting with `first`.
+pub(crate) fn player_order_from(total_players: usize, first: PlayerId) -> Vec<PlayerId> {
+    (first.seat()..total_players + first.seat())
+        .map(|index| PlayerId::new(index % total_players))
+        .collect()
+}
+
+/// Returns the player who deals the hand numbered `index`, counting from 0. The
+/// deal moves one seat to the left each hand.
+pub(crate) fn dealer_of(total_players: usize, index: usize) -> PlayerId {
+    PlayerId::new(index % total_players)
+}
 
-impl<'a> Hand<'a> {
+/// Checks that cards read from a save or record could have been dealt for the hand
+/// numbered `index`, counting from 0.
+///
+/// The hand must be dealt by the player whose turn it is to deal, and every player
+/// must hold `num_tricks` cards, with no card dealt twice or also turned up as trump.
+pub(crate) fn check_deal(
+    total_players: usize,
+    index: usize,
+    num_tricks: usize,
+    dealer: PlayerId,
+    trump: Card,
+    deal: &PlayerHands,
+) -> Result<(), &'static str> {
+    if dealer != dealer_of(total_players, index) {
+        return Err("the wrong player deals the hand");
+    }
+    if deal.len() != total_players {
+        return Err("every player must be dealt cards");
+    }
+
+    let mut dealt = CardSet::new();
+    dealt.insert(trump);
+    for seat in 0..total_players {
+        let cards = deal
+            .get(&PlayerId::new(seat))
+            .ok_or("every player must be dealt cards")?;
+        if cards.len() != num_tricks {
+            return Err("wrong number of cards dealt");
+        }
+        if !cards.iter().all(|card| dealt.insert(*card)) {
+            return Err("a card is dealt twice or is also the trump card");
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the bids placed so far, in bid order.
+fn ordered_bids(bid_order: &[PlayerId], bids: &HashMap<PlayerId, isize>) -> Vec<(PlayerId, isize)> {
+    bid_order
+        .iter()
+        .filter_map(|id| bids.get(id).map(|bid| (*id, *bid)))
+        .collect()
+}
+
+impl Hand {
     /// Creates the new [Hand] and returns the [Dealing] state.
+    ///
+    /// The deck is shuffled with `seed`, so the same seed always deals the same cards.
     #[allow(clippy::new_ret_no_self)]
     pub fn new(
-        players: &'a Vec<Box<dyn Player>>,
+        num_players: usize,
+        rules: &Rules,
         num_tricks: usize,
-        dealer: &'a Box<dyn Player>,
-    ) -> InProgressHand<'a, Dealing<'a>> {
-        let deck = Deck::new().deck_type(DeckType::Full).shuffle(7).end();
+        dealer: PlayerId,
+        seed: u64,
+    ) -> InProgressHand<Dealing> {
+        let deck = Deck::new()
+            .deck_type(DeckType::Full)
+            .seed(seed)
+            .shuffle(7)
+            .end();
 
         InProgressHand {
-            players,
+            num_players,
+            rules: rules.clone(),
             extra: Dealing {
                 deck,
                 num_tricks,
@@ -122,197 +252,650 @@ impl<'a> Hand<'a> {
         }
     }
 
+    /// Creates a [Hand] from cards that have already been dealt and returns the
+    /// [Bidding] state.
+    ///
+    /// Used to restore a saved hand or to set up a known deal. Every player must
+    /// hold the same number of cards, which is the number of tricks in the Hand.
+    pub fn from_deal(
+        num_players: usize,
+        rules: &Rules,
+        dealer: PlayerId,
+        trump: Card,
+        player_hands: PlayerHands,
+    ) -> InProgressHand<Bidding> {
+        let num_tricks = player_hands.get(&dealer).map_or(0, |e| e.len());
+
+        InProgressHand {
+            num_players,
+            rules: rules.clone(),
+            extra: Bidding {
+                trump,
+                player_hands: held_cards(&player_hands),
+                deal: player_hands,
+                num_tricks,
+                dealer,
+                bid_order: player_order_from(num_players, dealer),
+                bids: HashMap::with_capacity(num_players),
+            },
+        }
+    }
+
+    /// Get the number of tricks played in the Hand.
+    pub fn get_num_tricks(&self) -> usize {
+        self.num_tricks
+    }
+
+    /// Get the player who dealt the Hand.
+    pub fn get_dealer(&self) -> PlayerId {
+        self.dealer
+    }
+
+    /// Get the trump card turned over for the Hand.
+    pub fn get_trump(&self) -> Card {
+        self.trump
+    }
+
+    /// Get the cards dealt to a player at the start of the Hand.
+    pub fn get_deal(&self, player: PlayerId) -> &[Card] {
+        self.deal.get(&player).unwrap()
+    }
+
+    /// Get the player bids for the Hand.
+    pub fn get_bids(&self) -> &HashMap<PlayerId, isize> {
+        &self.bids
+    }
+
+    /// Get the tricks played in the Hand, in the order they were played.
+    pub fn get_tricks(&self) -> &[Trick] {
+        &self.tricks
+    }
+
+    /// Get the number of tricks each player won in the Hand.
+    pub fn get_tricks_won(&self) -> &HashMap<PlayerId, isize> {
+        &self.tricks_won
+    }
+
     /// Get the player scores for the Hand.
-    pub fn get_scores(&self) -> &HashMap<&'a Box<dyn Player>, isize> {
+    pub fn get_scores(&self) -> &HashMap<PlayerId, isize> {
         &self.points
     }
 
     /// Display the final points for the Hand.
-    pub fn display_points(&self) {
+    pub fn display_points(&self, players: &[Box<dyn Player>]) {
         let points = &self.points;
         println!();
         println!("     Player         Score");
         println!("{}", "-".repeat(26));
-        for player in self.players.iter() {
-            let points = points.get(player).unwrap();
+        for (seat, player) in players.iter().enumerate() {
+            let points = points.get(&PlayerId::new(seat)).unwrap();
             println!("{:<20} {:^5}", format!("{}", player), points);
         }
     }
 }
 
-impl<'a> InProgressHand<'a, Dealing<'a>> {
+impl<T: HandState> InProgressHand<T> {
+    /// Get the number of players in the Hand.
+    pub fn get_num_players(&self) -> usize {
+        self.num_players
+    }
+
+    /// Get the rules the Hand is played with.
+    pub fn get_rules(&self) -> &Rules {
+        &self.rules
+    }
+}
+
+impl InProgressHand<Dealing> {
+    /// Get the player dealing the Hand.
+    pub fn get_dealer(&self) -> PlayerId {
+        self.extra.dealer
+    }
+
     /// Generates a hand of cards for each player, set the trump, and returns the [Bidding] state.
-    pub fn deal_players_in(self) -> InProgressHand<'a, Bidding<'a>> {
-        let players = self.players;
+    pub fn deal_players_in(self) -> InProgressHand<Bidding> {
+        let num_players = self.num_players;
         let num_tricks = self.extra.num_tricks;
         let mut deck = self.extra.deck;
         let dealer = self.extra.dealer;
 
         let trump = deck.deal();
-        let mut player_hands: PlayerHands = HashMap::with_capacity(players.len());
-
-        println!();
-        println!("{} is dealing...", &dealer);
+        let mut player_hands: PlayerHands = (0..num_players)
+            .map(|seat| (PlayerId::new(seat), Vec::with_capacity(num_tricks)))
+            .collect();
 
         for _ in 0..num_tricks {
-            for player in players.iter() {
-                match player_hands.entry(player) {
-                    Entry::Vacant(e) => {
-                        let mut cards = Vec::with_capacity(num_tricks);
-                        cards.push(deck.deal());
-                        e.insert(cards);
-                    }
-                    Entry::Occupied(mut e) => {
-                        e.get_mut().push(deck.deal());
-                    }
-                }
+            for seat in 0..num_players {
+                let cards = player_hands.get_mut(&PlayerId::new(seat)).unwrap();
+                cards.push(deck.deal());
             }
         }
 
-        let total_players = players.len();
-        let dealer_position = players.iter().position(|e| e == dealer).unwrap();
-        let mut bid_order: Vec<&Box<dyn Player>> = Vec::with_capacity(total_players);
-
-        for index in dealer_position..total_players + dealer_position {
-            bid_order.push(&players[index % total_players]);
-        }
+        let bid_order = player_order_from(num_players, dealer);
 
         InProgressHand {
-            players,
+            num_players,
+            rules: self.rules,
             extra: Bidding {
-                player_hands,
+                player_hands: held_cards(&player_hands),
+                deal: player_hands,
                 trump,
                 num_tricks,
                 bid_order,
                 dealer,
+                bids: HashMap::with_capacity(num_players),
             },
         }
     }
 }
 
-impl<'a> InProgressHand<'a, Bidding<'a>> {
-    // Ask each player for their bid this Hand and return the Playing state.
-    pub fn get_player_bids(self) -> InProgressHand<'a, Playing<'a>> {
-        let player_hands: PlayerHands = self.extra.player_hands;
-        let trump = self.extra.trump;
-        let players = self.players;
-        let num_tricks = self.extra.num_tricks;
-        let bid_order = self.extra.bid_order;
-        let dealer = self.extra.dealer;
+impl InProgressHand<Bidding> {
+    /// Get the trump card for the Hand.
+    pub fn get_trump(&self) -> Card {
+        self.extra.trump
+    }
 
-        let mut bids: HashMap<&'a Box<dyn Player>, isize> = HashMap::with_capacity(players.len());
+    /// Get the number of tricks in the Hand.
+    pub fn get_num_tricks(&self) -> usize {
+        self.extra.num_tricks
+    }
 
-        for player in bid_order.iter() {
-            let cards = player_hands.get(player).unwrap();
-            let bid = player.get_player_bid(&trump, &num_tricks, cards);
-            bids.insert(player, bid);
-        }
+    /// Get the player who dealt the Hand.
+    pub fn get_dealer(&self) -> PlayerId {
+        self.extra.dealer
+    }
 
-        println!();
-        println!("{:-^1$}", "Player Bids", MAX_DISPLAY_WIDTH);
+    /// Get the cards held by a player.
+    pub fn get_cards(&self, player: PlayerId) -> CardSet {
+        self.extra.player_hands[&player]
+    }
 
-        for (player, bid) in bids.iter() {
-            println!("- {: <2$} {}", player, bid, 20);
-        }
+    /// Get the cards dealt to a player at the start of the Hand.
+    pub fn get_deal(&self, player: PlayerId) -> &[Card] {
+        self.extra.deal.get(&player).unwrap()
+    }
 
-        println!();
+    /// Get the bids placed so far.
+    pub fn get_bids(&self) -> &HashMap<PlayerId, isize> {
+        &self.extra.bids
+    }
+
+    /// Get the order the players bid in, starting with the dealer.
+    pub fn get_bid_order(&self) -> &[PlayerId] {
+        &self.extra.bid_order
+    }
 
-        let total_players = players.len();
-        let dealer_position = players.iter().position(|e| e == dealer).unwrap();
-        let mut initial_player_order: Vec<&Box<dyn Player>> = Vec::with_capacity(total_players);
+    /// Returns the public information in the Hand as seen by `player`.
+    pub fn view(&self, player: PlayerId) -> GameView {
+        GameView::new(
+            player,
+            self.num_players,
+            self.extra.trump,
+            self.extra.num_tricks,
+        )
+        .dealer(self.extra.dealer)
+        .bids(ordered_bids(&self.extra.bid_order, &self.extra.bids))
+        .rules(self.rules.clone())
+    }
+
+    /// Returns the player whose turn it is to bid, or None if all players have bid.
+    pub fn next_bidder(&self) -> Option<PlayerId> {
+        self.extra
+            .bid_order
+            .iter()
+            .find(|e| !self.extra.bids.contains_key(e))
+            .copied()
+    }
 
-        for index in dealer_position..total_players + dealer_position {
-            initial_player_order.push(&players[index % total_players]);
+    /// Places the bid for the player whose turn it is to bid.
+    pub fn place_bid(&mut self, player: PlayerId, bid: isize) -> Result<(), HandError> {
+        if self.next_bidder() != Some(player) {
+            return Err(HandError::NotPlayersTurn(player));
+        }
+        if bid < 0 || bid as usize > self.extra.num_tricks {
+            return Err(HandError::InvalidBid(bid));
         }
 
-        InProgressHand {
-            players,
+        self.extra.bids.insert(player, bid);
+        Ok(())
+    }
+
+    /// Returns the [Playing] state once every player has bid.
+    ///
+    /// Returns the hand unchanged if a player has yet to bid.
+    #[allow(clippy::result_large_err)]
+    pub fn finish_bidding(self) -> Result<InProgressHand<Playing>, Self> {
+        if self.next_bidder().is_some() {
+            return Err(self);
+        }
+
+        let initial_player_order = player_order_from(self.num_players, self.extra.dealer);
+
+        Ok(InProgressHand {
+            num_players: self.num_players,
+            rules: self.rules,
             extra: Playing {
-                bids,
-                trump,
-                player_hands,
-                num_tricks,
-                initial_player_order,
+                bids: self.extra.bids,
+                bid_order: self.extra.bid_order,
+                trump: self.extra.trump,
+                deal: self.extra.deal,
+                num_tricks: self.extra.num_tricks,
+                dealer: self.extra.dealer,
+                player_hands: self.extra.player_hands,
+                played: CardSet::new(),
+                trick: Trick::new(self.extra.trump, initial_player_order),
+                tricks: Vec::with_capacity(self.extra.num_tricks),
+                tricks_won: HashMap::with_capacity(self.num_players),
             },
+        })
+    }
+
+    /// Asks each player who has yet to bid for their bid and returns the [Playing] state.
+    ///
+    /// `players` is indexed by seat. Panics if a [Player] makes an invalid bid.
+    pub fn get_player_bids(mut self, players: &[Box<dyn Player>]) -> InProgressHand<Playing> {
+        while let Some(id) = self.next_bidder() {
+            let cards = self.get_cards(id).to_vec();
+            let bid = players[id.seat()].get_player_bid(&self.view(id), &cards);
+            self.place_bid(id, bid)
+                .unwrap_or_else(|e| panic!("{} made an invalid bid: {}", players[id.seat()], e));
+        }
+
+        match self.finish_bidding() {
+            Ok(hand) => hand,
+            Err(_) => unreachable!("every player has bid"),
         }
     }
 }
 
-impl<'a> InProgressHand<'a, Playing<'a>> {
-    // Plays through the number of tricks in this Hand and returns the Scoring state.
-    pub fn play_tricks(self) -> InProgressHand<'a, Scoring<'a>> {
-        let mut player_hands = self.extra.player_hands;
-        let trump = self.extra.trump;
-        let players = self.players;
-        let bids = self.extra.bids;
-        let num_tricks = self.extra.num_tricks;
-        let mut player_order: Vec<&Box<dyn Player>> = self.extra.initial_player_order;
+impl InProgressHand<Playing> {
+    /// Get the trump card for the Hand.
+    pub fn get_trump(&self) -> Card {
+        self.extra.trump
+    }
 
-        let set_new_player_order = |winner| {
-            let total_players = players.len();
-            let winner_position = players.iter().position(|e| e == winner).unwrap();
-            let mut new_player_order: Vec<&Box<dyn Player>> = Vec::with_capacity(total_players);
+    /// Get the number of tricks in the Hand.
+    pub fn get_num_tricks(&self) -> usize {
+        self.extra.num_tricks
+    }
 
-            for index in winner_position..total_players + winner_position {
-                new_player_order.push(&players[index % total_players]);
-            }
+    /// Get the player who dealt the Hand.
+    pub fn get_dealer(&self) -> PlayerId {
+        self.extra.dealer
+    }
+
+    /// Get the cards held by a player.
+    pub fn get_cards(&self, player: PlayerId) -> CardSet {
+        self.extra.player_hands[&player]
+    }
+
+    /// Get the cards dealt to a player at the start of the Hand.
+    pub fn get_deal(&self, player: PlayerId) -> &[Card] {
+        self.extra.deal.get(&player).unwrap()
+    }
 
-            new_player_order
+    /// Get the player bids for the Hand.
+    pub fn get_bids(&self) -> &HashMap<PlayerId, isize> {
+        &self.extra.bids
+    }
+
+    /// Get every card played so far in the Hand, including the current trick.
+    pub fn get_played_cards(&self) -> CardSet {
+        self.extra.played
+    }
+
+    /// Get the trick currently being played.
+    pub fn get_current_trick(&self) -> &InProgressTrick<trick::Playing> {
+        &self.extra.trick
+    }
+
+    /// Get the tricks finished so far, in the order they were played.
+    pub fn get_tricks(&self) -> &[Trick] {
+        &self.extra.tricks
+    }
+
+    /// Get the number of tricks each player has won so far.
+    pub fn get_tricks_won(&self) -> &HashMap<PlayerId, isize> {
+        &self.extra.tricks_won
+    }
+
+    /// Returns the public information in the Hand as seen by `player`.
+    pub fn view(&self, player: PlayerId) -> GameView {
+        GameView::new(
+            player,
+            self.num_players,
+            self.extra.trump,
+            self.extra.num_tricks,
+        )
+        .dealer(self.extra.dealer)
+        .bids(ordered_bids(&self.extra.bid_order, &self.extra.bids))
+        .rules(self.rules.clone())
+        .current_trick(self.extra.trick.get_cards_played().to_vec())
+        .tricks(self.extra.tricks.clone())
+        .played_cards(self.extra.played)
+    }
+
+    /// Returns the player whose turn it is to play, or None if all tricks are played.
+    pub fn next_player(&self) -> Option<PlayerId> {
+        self.extra.trick.next_player()
+    }
+
+    /// Plays a card for the player whose turn it is to play.
+    ///
+    /// The player must hold the card and must follow the led suit if they can.
+    /// Returns the finished [Trick] if the card was the last one in the trick.
+    pub fn play_card(&mut self, player: PlayerId, card: Card) -> Result<Option<Trick>, HandError> {
+        if self.next_player() != Some(player) {
+            return Err(HandError::NotPlayersTurn(player));
+        }
+
+        let cards = self.extra.player_hands.get_mut(&player).unwrap();
+        self.rules
+            .check_card(*cards, self.extra.trick.get_cards_played(), card)?;
+
+        cards.remove(card);
+        self.extra.played.insert(card);
+        self.extra.trick.play_card(player, card);
+
+        if self.extra.trick.next_player().is_some() {
+            return Ok(None);
+        }
+
+        // The trick is finished, so score it and start the next one with the winner leading
+        let finished = std::mem::replace(
+            &mut self.extra.trick,
+            Trick::new(self.extra.trump, Vec::new()),
+        );
+        let trick = match finished.finish() {
+            Ok(trick) => trick.determine_winner(),
+            Err(_) => unreachable!("every player has played"),
         };
+        let winner = trick.get_winner();
+
+        self.extra
+            .tricks_won
+            .entry(winner)
+            .and_modify(|e| *e += 1)
+            .or_insert(1);
+        self.extra.tricks.push(trick.clone());
+
+        if self.extra.tricks.len() < self.extra.num_tricks {
+            self.extra.trick = Trick::new(
+                self.extra.trump,
+                player_order_from(self.num_players, winner),
+            );
+        }
 
-        let mut tricks_won: HashMap<&Box<dyn Player>, isize> =
-            HashMap::with_capacity(players.len());
-
-        for index in 0..num_tricks {
-            println!();
-            println!("Playing trick: {}", index + 1);
-            let player_hands = &mut player_hands;
-            let trick = Trick::new(&trump, player_order, player_hands)
-                .play_trick()
-                .determine_winner();
-            let winner = trick.get_winner();
-            trick.display_trick();
-
-            tricks_won
-                .entry(winner)
-                .and_modify(|e| *e += 1)
-                .or_insert(1);
-
-            player_order = set_new_player_order(winner);
+        Ok(Some(trick))
+    }
+
+    /// Returns the [Scoring] state once every trick has been played.
+    ///
+    /// Returns the hand unchanged if a trick has yet to be played.
+    #[allow(clippy::result_large_err)]
+    pub fn finish_playing(self) -> Result<InProgressHand<Scoring>, Self> {
+        if self.next_player().is_some() {
+            return Err(self);
         }
 
-        InProgressHand {
-            players,
-            extra: Scoring { bids, tricks_won },
+        Ok(InProgressHand {
+            num_players: self.num_players,
+            rules: self.rules,
+            extra: Scoring {
+                trump: self.extra.trump,
+                deal: self.extra.deal,
+                num_tricks: self.extra.num_tricks,
+                dealer: self.extra.dealer,
+                bids: self.extra.bids,
+                tricks: self.extra.tricks,
+                tricks_won: self.extra.tricks_won,
+            },
+        })
+    }
+
+    /// Asks each player for their plays through the rest of the Hand and returns the
+    /// [Scoring] state.
+    ///
+    /// `players` is indexed by seat, and every Player is told of each card played.
+    /// Panics if a [Player] plays a card they may not play.
+    pub fn play_tricks(mut self, players: &mut [Box<dyn Player>]) -> InProgressHand<Scoring> {
+        while let Some(id) = self.next_player() {
+            let cards = self.get_cards(id).to_vec();
+            let card = players[id.seat()].play_card(&self.view(id), &cards);
+            let views: Vec<GameView> = (0..players.len())
+                .map(|seat| self.view(PlayerId::new(seat)))
+                .collect();
+            self.play_card(id, card)
+                .unwrap_or_else(|e| panic!("{} made an invalid play: {}", players[id.seat()], e));
+
+            for (player, view) in players.iter_mut().zip(&views) {
+                player.card_played(view, id, card);
+            }
+        }
+
+        match self.finish_playing() {
+            Ok(hand) => hand,
+            Err(_) => unreachable!("every trick has been played"),
         }
     }
 }
 
-impl<'a> InProgressHand<'a, Scoring<'a>> {
+impl InProgressHand<Scoring> {
     /// Score the Hand and return a Finished Hand.
-    pub fn score_hand(self) -> Hand<'a> {
-        let players = self.players;
+    pub fn score_hand(self) -> Hand {
+        let rules = self.rules;
         let tricks_won = self.extra.tricks_won;
         let bids = self.extra.bids;
 
-        let mut points: HashMap<&Box<dyn Player>, isize> = HashMap::with_capacity(players.len());
+        let mut points: HashMap<PlayerId, isize> = HashMap::with_capacity(self.num_players);
+
+        for seat in 0..self.num_players {
+            let id = PlayerId::new(seat);
+            let player_bid: isize = *bids.get(&id).unwrap();
+            let player_tricks_won: isize = *tricks_won.get(&id).unwrap_or(&0);
+
+            points.insert(id, rules.score(player_bid, player_tricks_won));
+        }
+
+        Hand {
+            num_tricks: self.extra.num_tricks,
+            dealer: self.extra.dealer,
+            trump: self.extra.trump,
+            deal: self.extra.deal,
+            bids,
+            tricks: self.extra.tricks,
+            tricks_won,
+            points,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::AIPlayer;
+    use crate::rank::Rank;
+
+    fn setup_players() -> Vec<Box<dyn Player>> {
+        vec![
+            Box::new(AIPlayer::new("Mickey".to_string())),
+            Box::new(AIPlayer::new("Mickey".to_string())),
+        ]
+    }
+
+    /// Creates a Hand in the Playing state with known cards, trump in Hearts.
+    fn setup_playing(cards: [Vec<Card>; 2]) -> InProgressHand<Playing> {
+        let [first, second] = cards;
+        let mut player_hands: PlayerHands = HashMap::new();
+        player_hands.insert(PlayerId::new(0), first);
+        player_hands.insert(PlayerId::new(1), second);
+        let trump = Card::new(Rank::Two, Suit::Hearts);
+
+        let mut hand = Hand::from_deal(2, &Rules::default(), PlayerId::new(0), trump, player_hands);
+        hand.place_bid(PlayerId::new(0), 1).unwrap();
+        hand.place_bid(PlayerId::new(1), 1).unwrap();
+
+        hand.finish_bidding().unwrap()
+    }
+
+    #[test]
+    fn players_with_the_same_name_are_dealt_separate_hands() {
+        let mut players = setup_players();
+
+        let hand = Hand::new(players.len(), &Rules::default(), 3, PlayerId::new(0), 7)
+            .deal_players_in()
+            .get_player_bids(&players)
+            .play_tricks(&mut players)
+            .score_hand();
 
-        for player in players.iter() {
-            let player_bid: isize = *bids.get(player).unwrap();
-            let player_tricks_won: isize = *tricks_won.get(player).unwrap_or(&0);
+        assert_eq!(hand.get_scores().len(), 2);
+        assert_eq!(hand.get_tricks().len(), 3);
+    }
+
+    #[test]
+    fn player_order_starts_with_given_player() {
+        let order = player_order_from(3, PlayerId::new(2));
 
-            let sandbag: isize = player_bid - player_tricks_won;
+        assert_eq!(
+            order,
+            vec![PlayerId::new(2), PlayerId::new(0), PlayerId::new(1)]
+        );
+    }
 
-            match sandbag {
-                0 => {
-                    points.insert(player, 10 + player_bid);
-                }
-                num => {
-                    points.insert(player, -(10 + num.abs()));
-                }
-            };
+    #[test]
+    fn bids_must_be_placed_in_turn_and_in_range() {
+        let mut hand = Hand::new(2, &Rules::default(), 2, PlayerId::new(1), 7).deal_players_in();
+
+        assert_eq!(
+            hand.place_bid(PlayerId::new(0), 1),
+            Err(HandError::NotPlayersTurn(PlayerId::new(0)))
+        );
+        assert_eq!(
+            hand.place_bid(PlayerId::new(1), 3),
+            Err(HandError::InvalidBid(3))
+        );
+        assert_eq!(hand.place_bid(PlayerId::new(1), 2), Ok(()));
+        assert!(hand.finish_bidding().is_err());
+    }
+
+    #[test]
+    fn player_must_follow_suit_when_able() {
+        let mut hand = setup_playing([
+            vec![
+                Card::new(Rank::Ace, Suit::Clubs),
+                Card::new(Rank::Two, Suit::Spades),
+            ],
+            vec![
+                Card::new(Rank::King, Suit::Clubs),
+                Card::new(Rank::Three, Suit::Hearts),
+            ],
+        ]);
+        hand.play_card(PlayerId::new(0), Card::new(Rank::Ace, Suit::Clubs))
+            .unwrap();
+
+        assert_eq!(
+            hand.play_card(PlayerId::new(1), Card::new(Rank::Three, Suit::Hearts)),
+            Err(HandError::MustFollowSuit(Suit::Clubs))
+        );
+        assert_eq!(
+            hand.play_card(PlayerId::new(1), Card::new(Rank::Ace, Suit::Hearts)),
+            Err(HandError::CardNotHeld(Card::new(Rank::Ace, Suit::Hearts)))
+        );
+    }
+
+    #[test]
+    fn trick_winner_leads_the_next_trick() {
+        let mut hand = setup_playing([
+            vec![
+                Card::new(Rank::Ten, Suit::Clubs),
+                Card::new(Rank::Two, Suit::Spades),
+            ],
+            vec![
+                Card::new(Rank::King, Suit::Clubs),
+                Card::new(Rank::Three, Suit::Spades),
+            ],
+        ]);
+        hand.play_card(PlayerId::new(0), Card::new(Rank::Ten, Suit::Clubs))
+            .unwrap();
+        let trick = hand
+            .play_card(PlayerId::new(1), Card::new(Rank::King, Suit::Clubs))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(trick.get_winner(), PlayerId::new(1));
+        assert_eq!(hand.next_player(), Some(PlayerId::new(1)));
+    }
+
+    #[test]
+    fn view_shows_bids_and_cards_played_so_far() {
+        let mut hand = setup_playing([
+            vec![
+                Card::new(Rank::Ten, Suit::Clubs),
+                Card::new(Rank::Two, Suit::Spades),
+            ],
+            vec![
+                Card::new(Rank::King, Suit::Clubs),
+                Card::new(Rank::Three, Suit::Spades),
+            ],
+        ]);
+        hand.play_card(PlayerId::new(0), Card::new(Rank::Ten, Suit::Clubs))
+            .unwrap();
+
+        let view = hand.view(PlayerId::new(1));
+
+        assert_eq!(view.get_bid(PlayerId::new(0)), Some(1));
+        assert_eq!(view.get_led_card(), Some(Card::new(Rank::Ten, Suit::Clubs)));
+        assert_eq!(view.get_trump(), Card::new(Rank::Two, Suit::Hearts));
+        assert_eq!(
+            view.get_played_cards().to_vec(),
+            vec![Card::new(Rank::Ten, Suit::Clubs)]
+        );
+        assert_eq!(hand.get_cards(PlayerId::new(0)).len(), 1);
+    }
+
+    #[test]
+    fn finished_hand_is_scored_from_bids() {
+        let mut hand = setup_playing([
+            vec![
+                Card::new(Rank::Ten, Suit::Clubs),
+                Card::new(Rank::Two, Suit::Spades),
+            ],
+            vec![
+                Card::new(Rank::King, Suit::Clubs),
+                Card::new(Rank::Three, Suit::Spades),
+            ],
+        ]);
+        for (seat, card) in [
+            (0, Card::new(Rank::Ten, Suit::Clubs)),
+            (1, Card::new(Rank::King, Suit::Clubs)),
+            (1, Card::new(Rank::Three, Suit::Spades)),
+            (0, Card::new(Rank::Two, Suit::Spades)),
+        ] {
+            hand.play_card(PlayerId::new(seat), card).unwrap();
+        }
+
+        let hand = hand.finish_playing().unwrap().score_hand();
+
+        assert_eq!(hand.get_scores()[&PlayerId::new(1)], -11);
+        assert_eq!(hand.get_scores()[&PlayerId::new(0)], -11);
+    }
+
+    #[test]
+    fn same_seed_deals_the_same_cards() {
+        let first = Hand::new(3, &Rules::default(), 5, PlayerId::new(0), 42).deal_players_in();
+        let second = Hand::new(3, &Rules::default(), 5, PlayerId::new(0), 42).deal_players_in();
+
+        assert_eq!(first.get_trump(), second.get_trump());
+        for seat in 0..3 {
+            let id = PlayerId::new(seat);
+            assert_eq!(first.get_cards(id), second.get_cards(id));
         }
+    }
 
-        Hand { players, points }
+    #[test]
+    fn in_progress_hand_is_send_and_static() {
+        fn assert_send<T: Send + 'static>() {}
+        assert_send::<InProgressHand<Bidding>>();
+        assert_send::<InProgressHand<Playing>>();
     }
 }
@@ -0,0 +1,515 @@
+//! An AI player that searches the rest of the hand with Information Set Monte Carlo
+//! Tree Search (ISMCTS).
+//!
+//! The [IsmctsPlayer] cannot see the other players' cards, so rather than searching
+//! a tree of the true positions it searches one tree of the positions as it sees
+//! them, its information sets. Each iteration of the search:
+//! - deals the unseen cards at random, as given by
+//!   [crate::simulation::sample_likely_hands] with the other players' habits from
+//!   the hands played so far, and sets up the hand in the engine with that deal,
+//! - walks down the tree choosing a bid or card for each player in turn, from the
+//!   moves the engine allows in that deal, until it reaches a move not yet tried,
+//! - plays the rest of the hand with [crate::player::AIPlayer]s in every seat, and
+//! - scores every move on the path for the player who made it.
+//!
+//! The tree covers both the bids still to be placed and the cards still to be
+//! played, so a bid is chosen by searching the hand that follows it. The moves of
+//! each player are scored by their own result, so the search expects the other
+//! players to play well for themselves.
+//!
+//! The number of iterations for each decision, and the time the search may take,
+//! are configurable.
+//!
+//! # Examples
+//! ```
+//! use std::time::Duration;
+//!
+//! use contract_whist::ismcts::IsmctsPlayer;
+//! use contract_whist::{AIPlayer, ContractWhistGame, Player, Rules};
+//!
+//! let players: Vec<Box<dyn Player>> = vec![
+//!     Box::new(
+//!         IsmctsPlayer::new("Gyro Gearloose".to_string())
+//!             .iterations(200)
+//!             .time_budget(Duration::from_millis(100)),
+//!     ),
+//!     Box::new(AIPlayer::new("Donald Duck".to_string())),
+//! ];
+//! let rules = Rules::default().hand_sizes(vec![3, 2, 1]);
+//!
+//! let result = ContractWhistGame::with_seed(players, rules, 7).unwrap().run();
+//! assert_eq!(result.get_hands().len(), 3);
+//! ```
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use rand::seq::SliceRandom;
+use rand::{random, Rng};
+
+use crate::bidding;
+use crate::card::Card;
+use crate::card_set::CardSet;
+use crate::hand::{Bidding, InProgressHand, Playing, Scoring};
+use crate::opponents::OpponentModel;
+use crate::player::{Player, PlayerId};
+use crate::simulation::{
+    decision_rng, rebuild_bidding, rebuild_hand, rollout_players, sample_likely_hands,
+};
+use crate::view::GameView;
+
+/// The weight given to trying moves that have been tried less often, over playing
+/// the moves that have scored best so far.
+const EXPLORATION: f64 = 0.7;
+
+/// An AI Player that bids and plays by searching a tree of the hand as it sees it.
+#[derive(Debug, Clone)]
+pub struct IsmctsPlayer {
+    name: String,
+    iterations: usize,
+    time_budget: Option<Duration>,
+    seed: u64,
+}
+
+impl IsmctsPlayer {
+    /// The number of iterations of the search for each decision by default.
+    pub const DEFAULT_ITERATIONS: usize = 1000;
+
+    /// Creates a new IsmctsPlayer, searching [IsmctsPlayer::DEFAULT_ITERATIONS]
+    /// iterations for each decision with no time budget.
+    pub fn new(name: String) -> IsmctsPlayer {
+        IsmctsPlayer {
+            name,
+            iterations: IsmctsPlayer::DEFAULT_ITERATIONS,
+            time_budget: None,
+            seed: random(),
+        }
+    }
+
+    /// Sets the number of iterations of the search for each decision, of at least one.
+    pub fn iterations(self, iterations: usize) -> IsmctsPlayer {
+        IsmctsPlayer {
+            iterations: iterations.max(1),
+            ..self
+        }
+    }
+
+    /// Sets the time each decision may take. The search stops once the time has run
+    /// out, after at least one iteration.
+    pub fn time_budget(self, time_budget: Duration) -> IsmctsPlayer {
+        IsmctsPlayer {
+            time_budget: Some(time_budget),
+            ..self
+        }
+    }
+
+    /// Returns the number of iterations of the search for each decision.
+    pub fn get_iterations(&self) -> usize {
+        self.iterations
+    }
+
+    /// Returns the time each decision may take, or None if there is no limit.
+    pub fn get_time_budget(&self) -> Option<Duration> {
+        self.time_budget
+    }
+
+    /// Searches the hand seen by `view` with `cards` held, and returns the move
+    /// tried most often from the root of the tree, or None if no deal of the unseen
+    /// cards could be set up.
+    fn search(&self, view: &GameView, cards: &[Card]) -> Option<Move> {
+        self.grow_tree(view, cards).best_move()
+    }
+
+    /// Returns the tree grown by searching the hand seen by `view` with `cards` held,
+    /// within the iterations and time allowed.
+    fn grow_tree(&self, view: &GameView, cards: &[Card]) -> Tree {
+        let mut rng = decision_rng(self.seed, view, cards.iter().collect());
+        let deadline = self.time_budget.map(|e| Instant::now() + e);
+        let mut tree = Tree::new();
+        let model = OpponentModel::from_view(view);
+
+        for iteration in 0..self.iterations {
+            if iteration > 0 && deadline.is_some_and(|e| Instant::now() >= e) {
+                break;
+            }
+
+            // An iteration whose deal cannot be played up to the view is skipped
+            let position = sample_likely_hands(view, cards, &model, &mut rng)
+                .and_then(|hands| Position::rebuild(view, &hands));
+            if let Some(position) = position {
+                tree.iterate(position, &mut rng);
+            }
+        }
+
+        tree
+    }
+}
+
+impl Player for IsmctsPlayer {
+    fn get_name(&self) -> &String {
+        &self.name
+    }
+
+    fn play_card(&self, view: &GameView, cards: &[Card]) -> Card {
+        let legal = view.legal_cards(cards);
+        if legal.len() == 1 {
+            return legal[0];
+        }
+
+        match self.search(view, cards) {
+            Some(Move::Play(card)) => card,
+            Some(Move::Bid(_)) => unreachable!("every player has bid"),
+            None => legal[0],
+        }
+    }
+
+    fn display_hand(&self, _cards: &[Card]) {}
+
+    fn get_player_bid(&self, view: &GameView, cards: &[Card]) -> isize {
+        match self.search(view, cards) {
+            Some(Move::Bid(bid)) => bid,
+            Some(Move::Play(_)) => unreachable!("the player has yet to bid"),
+            None => bidding::estimate_bid(view, cards),
+        }
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Player> {
+        Box::new(self.clone())
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+}
+
+/// A decision made by a player in the hand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Move {
+    Bid(isize),
+    Play(Card),
+}
+
+/// A hand being searched, with every card known.
+enum Position {
+    Bidding(InProgressHand<Bidding>),
+    Playing(InProgressHand<Playing>),
+    Scoring(InProgressHand<Scoring>),
+}
+
+impl Position {
+    /// Returns the hand seen by `view` set up in the engine with `hands` held, or
+    /// None if the hand cannot be played up to the view with them.
+    fn rebuild(view: &GameView, hands: &HashMap<PlayerId, CardSet>) -> Option<Position> {
+        let hand = match view.get_bids().len() < view.get_num_players() {
+            true => rebuild_bidding(view, hands).map(Position::Bidding),
+            false => rebuild_hand(view, hands).map(Position::Playing),
+        };
+        hand.ok()
+    }
+
+    /// Returns the player whose turn it is and the moves the rules allow them, or
+    /// None once every trick is played.
+    fn moves(&self) -> Option<(PlayerId, Vec<Move>)> {
+        match self {
+            Position::Bidding(hand) => {
+                let player = hand.next_bidder()?;
+                let bids = (0..=hand.get_num_tricks() as isize).map(Move::Bid);
+                Some((player, bids.collect()))
+            }
+            Position::Playing(hand) => {
+                let player = hand.next_player()?;
+                let trick = hand.get_current_trick().get_cards_played();
+                let legal = hand.get_rules().legal_set(hand.get_cards(player), trick);
+                Some((player, legal.iter().map(Move::Play).collect()))
+            }
+            Position::Scoring(_) => None,
+        }
+    }
+
+    /// Makes a move for `player`, which must be one of the moves they are allowed.
+    fn apply(self, player: PlayerId, next: Move) -> Position {
+        match (self, next) {
+            (Position::Bidding(mut hand), Move::Bid(bid)) => {
+                hand.place_bid(player, bid).expect("the bid is allowed");
+                match hand.finish_bidding() {
+                    Ok(hand) => Position::Playing(hand),
+                    Err(hand) => Position::Bidding(hand),
+                }
+            }
+            (Position::Playing(mut hand), Move::Play(card)) => {
+                hand.play_card(player, card).expect("the card is allowed");
+                match hand.finish_playing() {
+                    Ok(hand) => Position::Scoring(hand),
+                    Err(hand) => Position::Playing(hand),
+                }
+            }
+            _ => unreachable!("the move is made in its part of the hand"),
+        }
+    }
+
+    /// Plays out the rest of the hand, and returns the result for every player
+    /// between 0 for the worst score possible and 1 for the best.
+    fn play_out(self) -> HashMap<PlayerId, f64> {
+        let (num_players, rules) = match &self {
+            Position::Bidding(hand) => (hand.get_num_players(), hand.get_rules().clone()),
+            Position::Playing(hand) => (hand.get_num_players(), hand.get_rules().clone()),
+            Position::Scoring(hand) => (hand.get_num_players(), hand.get_rules().clone()),
+        };
+        let mut players = rollout_players(num_players);
+        let hand = match self {
+            Position::Bidding(hand) => hand
+                .get_player_bids(&players)
+                .play_tricks(&mut players)
+                .score_hand(),
+            Position::Playing(hand) => hand.play_tricks(&mut players).score_hand(),
+            Position::Scoring(hand) => hand.score_hand(),
+        };
+
+        // A player scores at best the bonus and every trick, and loses as much at worst
+        let best = (rules.get_made_bid_bonus() + hand.get_num_tricks() as isize) as f64;
+        hand.get_scores()
+            .iter()
+            .map(|(id, score)| (*id, (*score as f64 + best) / (2.0 * best)))
+            .collect()
+    }
+}
+
+/// A move in the search tree, and the results of the iterations that made it.
+#[derive(Debug, Clone)]
+struct Node {
+    /// The player who made the move, or None at the root.
+    player: Option<PlayerId>,
+    /// The move made, or None at the root.
+    next: Option<Move>,
+    children: Vec<usize>,
+    visits: usize,
+    /// The number of iterations in which the move could have been made.
+    available: usize,
+    /// The total result of the iterations that made the move, for the player who
+    /// made it.
+    reward: f64,
+}
+
+impl Node {
+    /// Returns the upper confidence bound of the move's result, which is used to
+    /// choose between moves that could be made.
+    fn upper_bound(&self) -> f64 {
+        let visits = self.visits as f64;
+        self.reward / visits + EXPLORATION * ((self.available as f64).ln() / visits).sqrt()
+    }
+}
+
+/// The tree of moves searched from the player's decision, held as a list of
+/// [Node]s with the root first.
+#[derive(Debug, Clone)]
+struct Tree {
+    nodes: Vec<Node>,
+}
+
+impl Tree {
+    fn new() -> Tree {
+        Tree {
+            nodes: vec![Node {
+                player: None,
+                next: None,
+                children: Vec::new(),
+                visits: 0,
+                available: 0,
+                reward: 0.0,
+            }],
+        }
+    }
+
+    /// Runs one iteration of the search with the deal in `position`.
+    ///
+    /// Only the moves allowed in this deal may be chosen, so the moves of the other
+    /// players depend on the cards they are dealt.
+    fn iterate<R: Rng>(&mut self, mut position: Position, rng: &mut R) {
+        let mut path = vec![0];
+        let mut node = 0;
+
+        while let Some((player, moves)) = position.moves() {
+            let children: Vec<usize> = self.nodes[node]
+                .children
+                .iter()
+                .copied()
+                .filter(|e| moves.contains(&self.nodes[*e].next.unwrap()))
+                .collect();
+            for child in &children {
+                self.nodes[*child].available += 1;
+            }
+
+            // A move not yet tried is tried before any move is tried again
+            let untried: Vec<Move> = moves
+                .into_iter()
+                .filter(|e| !children.iter().any(|c| self.nodes[*c].next == Some(*e)))
+                .collect();
+            if let Some(next) = untried.choose(rng) {
+                path.push(self.add_child(node, player, *next));
+                position = position.apply(player, *next);
+                break;
+            }
+
+            node = *children
+                .iter()
+                .max_by(|a, b| {
+                    let a = self.nodes[**a].upper_bound();
+                    a.total_cmp(&self.nodes[**b].upper_bound())
+                })
+                .unwrap();
+            path.push(node);
+            position = position.apply(player, self.nodes[node].next.unwrap());
+        }
+
+        let results = position.play_out();
+        for index in path {
+            let node = &mut self.nodes[index];
+            node.visits += 1;
+            if let Some(player) = node.player {
+                node.reward += results[&player];
+            }
+        }
+    }
+
+    /// Adds the move `next` by `player` below `parent`, and returns its index.
+    fn add_child(&mut self, parent: usize, player: PlayerId, next: Move) -> usize {
+        self.nodes.push(Node {
+            player: Some(player),
+            next: Some(next),
+            children: Vec::new(),
+            visits: 0,
+            available: 1,
+            reward: 0.0,
+        });
+        let index = self.nodes.len() - 1;
+        self.nodes[parent].children.push(index);
+        index
+    }
+
+    /// Returns the move from the root made in the most iterations, the earliest
+    /// tried of those if several are tied.
+    fn best_move(&self) -> Option<Move> {
+        let mut best: Option<&Node> = None;
+        for child in self.nodes[0].children.iter().map(|e| &self.nodes[*e]) {
+            if best.is_none_or(|e| child.visits > e.visits) {
+                best = Some(child);
+            }
+        }
+        best.and_then(|e| e.next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::card;
+    use crate::hand::Hand;
+    use crate::rules::Rules;
+    use crate::PlayerHands;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn setup_player(iterations: usize) -> IsmctsPlayer {
+        let mut player = IsmctsPlayer::new("Tester".to_string()).iterations(iterations);
+        player.set_seed(1);
+        player
+    }
+
+    /// The first bid of a hand of two tricks between two players.
+    fn setup_bid() -> (GameView, [Card; 2]) {
+        let view = GameView::new(PlayerId::new(0), 2, card("2H"), 2).dealer(PlayerId::new(0));
+        (view, [card("AH"), card("KC")])
+    }
+
+    /// A hand of one trick between two players, bid and ready for seat 0 to lead the
+    /// ace of clubs, with `card_held` dealt to seat 1.
+    fn setup_position(card_held: &str) -> Position {
+        let deal: PlayerHands = [
+            (PlayerId::new(0), vec![card("AC")]),
+            (PlayerId::new(1), vec![card(card_held)]),
+        ]
+        .into_iter()
+        .collect();
+        let rules = Rules::default().hand_sizes(vec![1]);
+        let mut hand = Hand::from_deal(2, &rules, PlayerId::new(0), card("2H"), deal);
+        hand.place_bid(PlayerId::new(0), 1).unwrap();
+        hand.place_bid(PlayerId::new(1), 0).unwrap();
+
+        match hand.finish_bidding() {
+            Ok(hand) => Position::Playing(hand),
+            Err(_) => panic!("every player has bid"),
+        }
+    }
+
+    #[test]
+    fn bids_the_tricks_a_sure_hand_takes() {
+        // Three of the top trumps take every trick in a hand of three
+        let player = IsmctsPlayer::new("Tester".to_string()).iterations(300);
+        let view = GameView::new(PlayerId::new(0), 3, card("2H"), 3).dealer(PlayerId::new(0));
+        let cards = [card("AH"), card("KH"), card("QH")];
+
+        assert_eq!(player.get_player_bid(&view, &cards), 3);
+    }
+
+    #[test]
+    fn bid_search_grows_the_card_play_below_the_bids() {
+        let (view, cards) = setup_bid();
+        let tree = setup_player(200).grow_tree(&view, &cards);
+
+        let root = &tree.nodes[0];
+        assert!(root
+            .children
+            .iter()
+            .all(|e| matches!(tree.nodes[*e].next, Some(Move::Bid(_)))));
+        assert!(tree.nodes.iter().any(|e| {
+            e.player == Some(PlayerId::new(0)) && matches!(e.next, Some(Move::Play(_)))
+        }));
+    }
+
+    #[test]
+    fn search_runs_the_iterations_set() {
+        let (view, cards) = setup_bid();
+
+        assert_eq!(
+            setup_player(25).grow_tree(&view, &cards).nodes[0].visits,
+            25
+        );
+        assert_eq!(setup_player(1).grow_tree(&view, &cards).nodes[0].visits, 1);
+    }
+
+    #[test]
+    fn search_stops_when_the_time_budget_runs_out() {
+        let (view, cards) = setup_bid();
+
+        let player = setup_player(usize::MAX).time_budget(Duration::ZERO);
+        assert_eq!(player.grow_tree(&view, &cards).nodes[0].visits, 1);
+
+        let player = setup_player(usize::MAX).time_budget(Duration::from_millis(20));
+        let start = Instant::now();
+        let visits = player.grow_tree(&view, &cards).nodes[0].visits;
+        assert!(visits > 1);
+        assert!(start.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn move_not_allowed_in_the_deal_is_never_chosen() {
+        let mut tree = Tree::new();
+        let mut rng = StdRng::seed_from_u64(1);
+
+        // Seat 0 leads the ace, then seat 1 follows with the king of clubs
+        tree.iterate(setup_position("KC"), &mut rng);
+        tree.iterate(setup_position("KC"), &mut rng);
+        // Deals without the king of clubs may only choose the queen of diamonds
+        for _ in 0..10 {
+            tree.iterate(setup_position("QD"), &mut rng);
+        }
+
+        let node = |text: &str| {
+            let next = Some(Move::Play(card(text)));
+            tree.nodes.iter().find(|e| e.next == next).unwrap()
+        };
+        assert_eq!(node("AC").visits, 12);
+        assert_eq!((node("KC").visits, node("KC").available), (1, 1));
+        assert_eq!((node("QD").visits, node("QD").available), (10, 10));
+    }
+}
@@ -1,22 +1,102 @@
+//! An implementation of the card game Contract Whist (also known as Oh Hell).
+//!
+//! The crate is organised around the pieces of the game:
+//! - [Card], [Rank], and [Suit] model a French deck of playing cards, and a
+//!   [Deck] is built, shuffled, and dealt with a [DeckBuilder]. Cards are read
+//!   from text such as "AH" or "10s" and written in any [Notation]. Hands are
+//!   held as a compact [CardSet].
+//! - [Rules] configure the number of cards in each hand and the scoring.
+//! - [Player] is implemented by anything that can bid and play cards. The
+//!   crate provides a [HumanPlayer] reading from stdinput and an [AIPlayer],
+//!   which bids the tricks its hand is expected to take. A [MonteCarloPlayer]
+//!   plays each card by simulating deals of the cards it cannot see, and an
+//!   [IsmctsPlayer] bids and plays by searching a tree of the hand as it sees it.
+//!   A deal with every card known is solved by a [double_dummy::DoubleDummy].
+//!   AI opponents are chosen by their [Difficulty], and the [AIPlayer] plays in
+//!   the style of its [personality::Personality], learning the other players'
+//!   habits over a game with an [opponents::OpponentModel].
+//!   Players are identified in the engine by their seat, a [PlayerId], and
+//!   are given a [GameView] of the public information with every decision.
+//! - [ContractWhistGame] runs a game, playing each [Hand] by way of a series
+//!   of [Trick]s, and returns a [GameResult]. An [AsyncGame] is played by
+//!   [AsyncPlayer]s whose decisions may take time to arrive, and several may be
+//!   run at once on one thread, with [TimeLimits] on every decision.
+//! - A game in progress is stored as a [GameState], which may be saved to a file
+//!   and resumed later as a [SavedGame]. A [GameRecord] of every hand may be
+//!   written as text while the game is played, and stepped through later with
+//!   a [Replay].
+//!
+//! # Examples
+//! ```
+//! use contract_whist::{AIPlayer, ContractWhistGame, Player, Rules};
+//!
+//! let players: Vec<Box<dyn Player>> = vec![
+//!     Box::new(AIPlayer::new("Mickey Mouse".to_string())),
+//!     Box::new(AIPlayer::new("Minnie Mouse".to_string())),
+//!     Box::new(AIPlayer::new("Donald Duck".to_string())),
+//! ];
+//!
+//! let result = ContractWhistGame::new(players, Rules::default())
+//!     .unwrap()
+//!     .run();
+//!
+//! assert_eq!(result.get_hands().len(), 13);
+//! println!("Winners: {:?}", result.winners());
+//! ```
+
 #![allow(clippy::borrowed_box)]
 
 use std::collections::HashMap;
 
-use crate::card::Card;
-use crate::player::Player;
-
-pub use crate::game::ContractWhistGame;
+pub use crate::card::{Card, Notation, ParseCardError};
+pub use crate::card_set::CardSet;
+pub use crate::deck::{Deck, DeckBuilder, DeckType};
+pub use crate::difficulty::{Difficulty, ParseDifficultyError};
+pub use crate::game::{ContractWhistGame, GameResult, GameState};
+pub use crate::hand::Hand;
+pub use crate::ismcts::IsmctsPlayer;
+pub use crate::monte_carlo::MonteCarloPlayer;
+pub use crate::player::{AIPlayer, BeginnerPlayer, HumanPlayer, Player, PlayerId};
+pub use crate::rank::Rank;
+pub use crate::record::{GameRecord, RecordError, RecordWriter};
+pub use crate::remote::{AsyncGame, AsyncPlayer, BlockingPlayer, ThreadedPlayer};
+pub use crate::replay::Replay;
+pub use crate::rules::{Rules, RulesError};
+pub use crate::save::{SaveError, SavedGame};
+pub use crate::suit::Suit;
+pub use crate::timeout::{CardFallback, TimeLimits};
+pub use crate::trick::Trick;
+pub use crate::view::GameView;
 
-pub(crate) mod card;
-pub(crate) mod deck;
-pub(crate) mod game;
-pub(crate) mod hand;
-pub(crate) mod player;
-pub(crate) mod rank;
-pub(crate) mod suit;
-pub(crate) mod trick;
+pub mod bidding;
+pub mod card;
+pub mod card_set;
+pub mod deck;
+pub mod difficulty;
+pub mod double_dummy;
+pub mod endgame;
+pub mod game;
+pub mod hand;
+pub mod ismcts;
+pub mod memory;
+pub mod monte_carlo;
+pub mod opponents;
+pub mod personality;
+pub mod player;
+pub mod rank;
+pub mod record;
+pub mod remote;
+pub mod replay;
+pub mod rules;
+pub mod save;
+pub mod simulation;
+pub mod suit;
+pub mod timeout;
+pub mod trick;
+pub mod view;
 
-/// Type alias for Player Hand.
-type PlayerHands<'a> = HashMap<&'a Box<dyn Player>, Vec<Card>>;
+/// Type alias for the cards held by each Player.
+pub type PlayerHands = HashMap<PlayerId, Vec<Card>>;
 
+/// Width used when printing the game to the terminal.
 pub const MAX_DISPLAY_WIDTH: usize = 35;
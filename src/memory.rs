@@ -0,0 +1,213 @@
+//! A player's memory of the cards played in a hand.
+//!
+//! A [CardMemory] is kept up to date with [CardMemory::record] as each card is
+//! played, and remembers:
+//! - every card played so far,
+//! - the suits each player has shown they are out of, by not following the led
+//!   suit, and so
+//! - the cards that are still unseen, such as the trumps still out.
+//!
+//! From these it can tell when a card is sure to win its trick. The memory is
+//! rebuilt from a [GameView] with [CardMemory::from_view], such as when a player
+//! joins a hand part way through or decisions are taken back.
+//!
+//! # Examples
+//! ```
+//! use contract_whist::memory::CardMemory;
+//! use contract_whist::{Card, CardSet, PlayerId, Suit};
+//!
+//! let card = |text: &str| text.parse::<Card>().unwrap();
+//! let mut memory = CardMemory::new(PlayerId::new(0), card("2H"));
+//!
+//! // Seat 1 leads the ace of spades, and seat 2 ruffs it
+//! memory.record(&[], PlayerId::new(1), card("AS"));
+//! memory.record(&[(PlayerId::new(1), card("AS"))], PlayerId::new(2), card("3H"));
+//!
+//! assert!(memory.is_void(PlayerId::new(2), Suit::Spades));
+//!
+//! // The king of spades is now the highest spade, but seat 2 may ruff it
+//! let held: CardSet = [card("KS"), card("AD")].iter().collect();
+//! assert!(!memory.is_sure_winner(card("KS"), held));
+//! ```
+
+use std::collections::HashMap;
+
+use crate::card::Card;
+use crate::card_set::CardSet;
+use crate::player::PlayerId;
+use crate::suit::Suit;
+use crate::view::GameView;
+
+/// The cards played in a hand, as remembered by one player.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CardMemory {
+    player: PlayerId,
+    trump: Card,
+    played: CardSet,
+    voids: HashMap<PlayerId, CardSet>,
+}
+
+impl CardMemory {
+    /// Creates the memory of `player` for a hand with the given trump card, before
+    /// any card is played.
+    pub fn new(player: PlayerId, trump: Card) -> CardMemory {
+        CardMemory {
+            player,
+            trump,
+            played: CardSet::new(),
+            voids: HashMap::new(),
+        }
+    }
+
+    /// Creates the memory of the player seeing `view`, from the tricks played so far
+    /// and the cards the view shows as played.
+    pub fn from_view(view: &GameView) -> CardMemory {
+        let mut memory = CardMemory::new(view.get_player(), view.get_trump());
+        let tricks = view.get_tricks().iter().map(|e| e.get_cards_played());
+
+        for cards in tricks.chain(std::iter::once(view.get_current_trick())) {
+            for (index, (player, card)) in cards.iter().enumerate() {
+                memory.record(&cards[..index], *player, *card);
+            }
+        }
+        memory.played = memory.played | view.get_played_cards();
+        memory
+    }
+
+    /// Records `card` played by `player` to a trick with `trick` played before it.
+    ///
+    /// A player who does not follow the led suit is out of it.
+    pub fn record(&mut self, trick: &[(PlayerId, Card)], player: PlayerId, card: Card) {
+        self.played.insert(card);
+
+        if let Some((_, led)) = trick.first() {
+            if card.suit() != led.suit() {
+                let voids = self.voids.entry(player).or_default();
+                *voids = *voids | CardSet::suit(led.suit());
+            }
+        }
+    }
+
+    /// Returns the player whose memory this is.
+    pub fn get_player(&self) -> PlayerId {
+        self.player
+    }
+
+    /// Returns the trump card of the hand.
+    pub fn get_trump(&self) -> Card {
+        self.trump
+    }
+
+    /// Returns every card played so far.
+    pub fn get_played(&self) -> CardSet {
+        self.played
+    }
+
+    /// Returns true if `player` has shown they are out of `suit`.
+    pub fn is_void(&self, player: PlayerId, suit: Suit) -> bool {
+        self.get_voids(player).count_suit(suit) > 0
+    }
+
+    /// Returns every card in the suits `player` has shown they are out of.
+    pub fn get_voids(&self, player: PlayerId) -> CardSet {
+        self.voids.get(&player).copied().unwrap_or_default()
+    }
+
+    /// Returns the cards not yet seen by a player holding `held`: those not held,
+    /// played, or turned up for trump.
+    pub fn unseen(&self, held: CardSet) -> CardSet {
+        let mut unseen = !(held | self.played);
+        unseen.remove(self.trump);
+        unseen
+    }
+
+    /// Returns the number of trumps still out, that are neither held nor seen.
+    pub fn trumps_out(&self, held: CardSet) -> usize {
+        self.unseen(held).count_suit(self.trump.suit())
+    }
+
+    /// Returns true if a card of `suit` led from `held` may be ruffed: an opponent is
+    /// out of the suit, as shown by them or because no other card of it is left,
+    /// and trumps are still out.
+    pub fn may_be_ruffed(&self, suit: Suit, held: CardSet) -> bool {
+        let shown_void = self
+            .voids
+            .iter()
+            .any(|(player, voids)| *player != self.player && voids.count_suit(suit) > 0);
+
+        suit != self.trump.suit()
+            && self.trumps_out(held) > 0
+            && (shown_void || self.unseen(held).count_suit(suit) == 0)
+    }
+
+    /// Returns true if `card`, led from `held`, is sure to win the trick.
+    ///
+    /// The card must be the highest left in its suit, and must not be open to a ruff,
+    /// as given by [CardMemory::may_be_ruffed].
+    pub fn is_sure_winner(&self, card: Card, held: CardSet) -> bool {
+        let highest = self
+            .unseen(held)
+            .in_suit(card.suit())
+            .iter()
+            .all(|e| e.rank() < card.rank());
+
+        highest && !self.may_be_ruffed(card.suit(), held)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::card;
+    use crate::trick::Trick;
+
+    fn cards(texts: &[&str]) -> CardSet {
+        texts.iter().map(|e| card(e)).collect()
+    }
+
+    #[test]
+    fn players_not_following_suit_are_void() {
+        let mut memory = CardMemory::new(PlayerId::new(0), card("2H"));
+        let trick = [(PlayerId::new(1), card("9C"))];
+        memory.record(&[], PlayerId::new(1), card("9C"));
+        memory.record(&trick, PlayerId::new(2), card("4D"));
+
+        assert!(memory.is_void(PlayerId::new(2), Suit::Clubs));
+        assert!(!memory.is_void(PlayerId::new(1), Suit::Clubs));
+        assert_eq!(memory.get_played(), cards(&["9C", "4D"]));
+    }
+
+    #[test]
+    fn highest_card_left_wins_unless_it_may_be_ruffed() {
+        let mut memory = CardMemory::new(PlayerId::new(0), card("2H"));
+        memory.record(&[], PlayerId::new(1), card("AC"));
+        let held = cards(&["KC", "QD", "3H"]);
+
+        assert!(memory.is_sure_winner(card("KC"), held));
+        assert!(!memory.is_sure_winner(card("QD"), held));
+        assert_eq!(memory.trumps_out(held), 11);
+
+        memory.record(
+            &[(PlayerId::new(1), card("AC"))],
+            PlayerId::new(2),
+            card("5D"),
+        );
+        assert!(!memory.is_sure_winner(card("KC"), held));
+    }
+
+    #[test]
+    fn memory_is_rebuilt_from_the_view() {
+        let mut trick = Trick::new(card("2H"), vec![PlayerId::new(1), PlayerId::new(0)]);
+        trick.play_card(PlayerId::new(1), card("9C"));
+        trick.play_card(PlayerId::new(0), card("JS"));
+        let trick = trick.finish().ok().unwrap().determine_winner();
+
+        let view = GameView::new(PlayerId::new(0), 2, card("2H"), 2)
+            .tricks(vec![trick])
+            .current_trick(vec![(PlayerId::new(1), card("10C"))]);
+        let memory = CardMemory::from_view(&view);
+
+        assert!(memory.is_void(PlayerId::new(0), Suit::Clubs));
+        assert_eq!(memory.get_played(), cards(&["9C", "JS", "10C"]));
+    }
+}
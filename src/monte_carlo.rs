@@ -0,0 +1,203 @@
+//! An AI player that chooses each card by simulating the rest of the hand.
+//!
+//! The [MonteCarloPlayer] cannot see the other players' cards, so for every card
+//! it may play it plays out many deals that could be true, as given by
+//! [crate::simulation::sample_likely_hands] with the other players' habits from
+//! the hands played so far, and plays the card that scores best on average
+//! against its bid. The rest of each simulated hand is played by
+//! [crate::player::AIPlayer]s in every seat.
+//!
+//! The number of deals sampled for each decision, and the time the search may
+//! take, are configurable. Bids are placed as the [crate::player::AIPlayer] bids.
+//!
+//! # Examples
+//! ```
+//! use std::time::Duration;
+//!
+//! use contract_whist::monte_carlo::MonteCarloPlayer;
+//! use contract_whist::{AIPlayer, ContractWhistGame, Player, Rules};
+//!
+//! let players: Vec<Box<dyn Player>> = vec![
+//!     Box::new(
+//!         MonteCarloPlayer::new("Ludwig Von Drake".to_string())
+//!             .samples(20)
+//!             .time_budget(Duration::from_millis(100)),
+//!     ),
+//!     Box::new(AIPlayer::new("Donald Duck".to_string())),
+//! ];
+//! let rules = Rules::default().hand_sizes(vec![3, 2, 1]);
+//!
+//! let result = ContractWhistGame::with_seed(players, rules, 7).unwrap().run();
+//! assert_eq!(result.get_hands().len(), 3);
+//! ```
+
+use std::time::{Duration, Instant};
+
+use rand::random;
+
+use crate::bidding;
+use crate::card::Card;
+use crate::difficulty::Difficulty;
+use crate::opponents::OpponentModel;
+use crate::player::Player;
+use crate::simulation::{
+    decision_rng, play_out, rebuild_hand, rollout_players, sample_likely_hands,
+};
+use crate::view::GameView;
+
+/// An AI Player that plays the card scoring best over many simulated deals.
+#[derive(Debug, Clone)]
+pub struct MonteCarloPlayer {
+    name: String,
+    samples: usize,
+    time_budget: Option<Duration>,
+    seed: u64,
+}
+
+impl MonteCarloPlayer {
+    /// The number of deals sampled for each decision by default.
+    pub const DEFAULT_SAMPLES: usize = 100;
+
+    /// Creates a new MonteCarloPlayer, sampling [MonteCarloPlayer::DEFAULT_SAMPLES]
+    /// deals for each decision with no time budget.
+    pub fn new(name: String) -> MonteCarloPlayer {
+        MonteCarloPlayer {
+            name,
+            samples: MonteCarloPlayer::DEFAULT_SAMPLES,
+            time_budget: None,
+            seed: random(),
+        }
+    }
+
+    /// Sets the number of deals sampled for each decision, of at least one.
+    pub fn samples(self, samples: usize) -> MonteCarloPlayer {
+        MonteCarloPlayer {
+            samples: samples.max(1),
+            ..self
+        }
+    }
+
+    /// Sets the time each decision may take. Sampling stops once the time has run
+    /// out, after at least one deal.
+    pub fn time_budget(self, time_budget: Duration) -> MonteCarloPlayer {
+        MonteCarloPlayer {
+            time_budget: Some(time_budget),
+            ..self
+        }
+    }
+
+    /// Returns the number of deals sampled for each decision.
+    pub fn get_samples(&self) -> usize {
+        self.samples
+    }
+
+    /// Returns the time each decision may take, or None if there is no limit.
+    pub fn get_time_budget(&self) -> Option<Duration> {
+        self.time_budget
+    }
+}
+
+impl Player for MonteCarloPlayer {
+    fn get_name(&self) -> &String {
+        &self.name
+    }
+
+    fn play_card(&self, view: &GameView, cards: &[Card]) -> Card {
+        let legal = view.legal_cards(cards);
+        if legal.len() == 1 {
+            return legal[0];
+        }
+
+        let player = view.get_player();
+        let mut rng = decision_rng(self.seed, view, cards.iter().collect());
+        let deadline = self.time_budget.map(|e| Instant::now() + e);
+        let mut totals = vec![0; legal.len()];
+        let model = OpponentModel::from_view(view);
+
+        for sample in 0..self.samples {
+            if sample > 0 && deadline.is_some_and(|e| Instant::now() >= e) {
+                break;
+            }
+
+            // A deal that cannot be played up to the view is skipped
+            let hands = sample_likely_hands(view, cards, &model, &mut rng);
+            let hand = match hands.map(|e| rebuild_hand(view, &e)) {
+                Some(Ok(hand)) => hand,
+                _ => continue,
+            };
+            for (total, card) in totals.iter_mut().zip(&legal) {
+                let mut hand = hand.clone();
+                hand.play_card(player, *card)
+                    .expect("the card may be played");
+                let scored = play_out(hand, &mut rollout_players(view.get_num_players()));
+                *total += scored.get_scores()[&player];
+            }
+        }
+
+        // The earliest of the best scoring cards is played
+        let best = totals.iter().max().unwrap();
+        let index = totals.iter().position(|e| e == best).unwrap();
+        legal[index]
+    }
+
+    fn display_hand(&self, _cards: &[Card]) {}
+
+    fn get_player_bid(&self, view: &GameView, cards: &[Card]) -> isize {
+        bidding::estimate_bid(view, cards)
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Player> {
+        Box::new(self.clone())
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    fn get_difficulty(&self) -> Option<Difficulty> {
+        Some(Difficulty::Expert)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::card;
+    use crate::game::ContractWhistGame;
+    use crate::player::{AIPlayer, PlayerId};
+    use crate::rules::Rules;
+
+    #[test]
+    fn plays_the_card_that_makes_its_bid() {
+        // Winning the first trick with the ace breaks the bid of none, while the two
+        // loses it and may yet lose the second
+        let player = MonteCarloPlayer::new("Tester".to_string()).samples(20);
+        let view = GameView::new(PlayerId::new(0), 2, card("2H"), 2)
+            .dealer(PlayerId::new(1))
+            .bids(vec![(PlayerId::new(1), 1), (PlayerId::new(0), 0)])
+            .current_trick(vec![(PlayerId::new(1), card("10C"))])
+            .played_cards([card("10C")].iter().collect());
+        let cards = [card("AC"), card("2C")];
+
+        assert_eq!(player.play_card(&view, &cards), card("2C"));
+    }
+
+    #[test]
+    fn same_seed_makes_the_same_decisions() {
+        let players = || -> Vec<Box<dyn Player>> {
+            vec![
+                Box::new(MonteCarloPlayer::new("Scrooge McDuck".to_string()).samples(5)),
+                Box::new(AIPlayer::new("Huey Duck".to_string())),
+                Box::new(AIPlayer::new("Dewey Duck".to_string())),
+            ]
+        };
+        let rules = Rules::default().hand_sizes(vec![4, 3]);
+        let play = || {
+            ContractWhistGame::with_seed(players(), rules.clone(), 9)
+                .unwrap()
+                .run()
+        };
+
+        assert_eq!(play().get_totals(), play().get_totals());
+    }
+}
@@ -0,0 +1,443 @@
+//! Models of the other players' habits, learned over the hands of a game.
+//!
+//! An [OpponentModel] is built from the events of a game with
+//! [OpponentModel::from_events], or from the finished hands a player is shown
+//! with [OpponentModel::from_view]. Only finished hands are learned from, as
+//! every card a player held is known once it has been played. For each player
+//! it keeps:
+//! - their bid accuracy by hand size: how often they made their bid exactly, and
+//!   by how many tricks they tend to miss it, and
+//! - their leads: how often they led a trump when they held one.
+//!
+//! A habit is trusted more the more hands it is seen in, as if each player had
+//! first shown no habit over a few hands. The model is used to judge how many
+//! tricks a player will really take for their bid with
+//! [OpponentModel::expected_tricks], and how likely a deal of the unseen cards
+//! is given the leads made so far with [OpponentModel::deal_weight]. The search
+//! players deal the unseen cards by that weight with
+//! [crate::simulation::sample_likely_hands].
+//!
+//! # Examples
+//! ```
+//! use contract_whist::game::{Decision, GameState};
+//! use contract_whist::opponents::OpponentModel;
+//! use contract_whist::{PlayerId, Rules};
+//!
+//! let mut state = GameState::new(3, Rules::default().hand_sizes(vec![1, 1]), 4).unwrap();
+//!
+//! // Every player bids to take the only trick of the first hand
+//! while state.get_hands().is_empty() {
+//!     match state.pending_decision().unwrap() {
+//!         Decision::Bid(player) => state.place_bid(player, 1).unwrap(),
+//!         Decision::Play(player) => {
+//!             let cards = state.get_cards(player).unwrap().to_vec();
+//!             state.play_card(player, cards[0]).unwrap();
+//!         }
+//!     }
+//! }
+//!
+//! let model = OpponentModel::from_events(state.get_events());
+//! assert_eq!(model.get_hands(), 1);
+//!
+//! // Two of the players took a trick fewer than they bid
+//! let missed: f64 = (0..3).map(|e| model.bid_error(PlayerId::new(e), 1)).sum();
+//! assert!(missed < 0.0);
+//! ```
+
+use std::collections::HashMap;
+
+use crate::card::Card;
+use crate::card_set::CardSet;
+use crate::game::{effective_events, GameEvent};
+use crate::hand::Hand;
+use crate::player::PlayerId;
+use crate::suit::Suit;
+use crate::view::GameView;
+
+/// The number of hands in which each player is taken to have missed their bid by
+/// nothing, before any are seen.
+const PRIOR_HANDS: f64 = 2.0;
+/// The number of leads with a trump held in which each player is taken to have led
+/// another suit, before any are seen.
+const PRIOR_LEADS: f64 = 2.0;
+/// The least weight a lead gives a deal, however unlike the player's habits it is.
+const MIN_WEIGHT: f64 = 0.1;
+
+/// The habits of every player in a game, learned from the hands they finished.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OpponentModel {
+    hands: usize,
+    bids: HashMap<PlayerId, HashMap<usize, BidRecord>>,
+    leads: HashMap<PlayerId, LeadRecord>,
+}
+
+/// The bids of a player in hands of one size.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct BidRecord {
+    hands: usize,
+    made: usize,
+    /// The tricks taken over the bid, summed over the hands.
+    error: isize,
+}
+
+/// The leads of a player made while holding a trump.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct LeadRecord {
+    leads: usize,
+    trumps: usize,
+}
+
+/// A hand being read from the events of a game.
+struct HandEvents {
+    trump: Suit,
+    num_tricks: usize,
+    bids: Vec<(PlayerId, isize)>,
+    plays: Vec<(PlayerId, Card)>,
+    leads: Vec<usize>,
+    tricks_won: HashMap<PlayerId, isize>,
+    trick_size: usize,
+}
+
+impl OpponentModel {
+    /// Creates a model that has seen no hands.
+    pub fn new() -> OpponentModel {
+        OpponentModel::default()
+    }
+
+    /// Returns the model learned from the hands finished in `events`, leaving out
+    /// any decisions taken back.
+    pub fn from_events(events: &[GameEvent]) -> OpponentModel {
+        let mut model = OpponentModel::new();
+        let mut hand: Option<HandEvents> = None;
+
+        for event in effective_events(events).iter() {
+            if let GameEvent::HandDealt { trump, deal, .. } = event {
+                hand = Some(HandEvents {
+                    trump: trump.suit(),
+                    num_tricks: deal.values().map(|e| e.len()).max().unwrap_or(0),
+                    bids: Vec::new(),
+                    plays: Vec::new(),
+                    leads: Vec::new(),
+                    tricks_won: HashMap::new(),
+                    trick_size: 0,
+                });
+                continue;
+            }
+
+            let current = match hand.as_mut() {
+                Some(current) => current,
+                None => continue,
+            };
+            match event {
+                GameEvent::BidPlaced { player, bid } => current.bids.push((*player, *bid)),
+                GameEvent::CardPlayed { player, card } => {
+                    if current.trick_size == 0 {
+                        current.leads.push(current.plays.len());
+                    }
+                    current.plays.push((*player, *card));
+                    current.trick_size += 1;
+                }
+                GameEvent::TrickWon { winner } => {
+                    *current.tricks_won.entry(*winner).or_default() += 1;
+                    current.trick_size = 0;
+                }
+                GameEvent::HandScored { .. } => {
+                    if let Some(finished) = hand.take() {
+                        model.learn(&finished);
+                    }
+                }
+                GameEvent::HandDealt { .. }
+                | GameEvent::Undo { .. }
+                | GameEvent::TimedOut { .. } => (),
+            }
+        }
+
+        model
+    }
+
+    /// Returns the model learned from the finished hands in `view`.
+    pub fn from_view(view: &GameView) -> OpponentModel {
+        let events: Vec<GameEvent> = view
+            .get_previous_hands()
+            .iter()
+            .flat_map(hand_events)
+            .collect();
+
+        OpponentModel::from_events(&events)
+    }
+
+    /// Returns the number of hands learned from.
+    pub fn get_hands(&self) -> usize {
+        self.hands
+    }
+
+    /// Returns the share of hands of `num_tricks` tricks in which `player` made
+    /// their bid exactly, or None if they have not played one.
+    pub fn bid_accuracy(&self, player: PlayerId, num_tricks: usize) -> Option<f64> {
+        self.bids
+            .get(&player)
+            .and_then(|e| e.get(&num_tricks))
+            .filter(|e| e.hands > 0)
+            .map(|e| e.made as f64 / e.hands as f64)
+    }
+
+    /// Returns the number of tricks `player` is expected to take over their bid in
+    /// a hand of `num_tricks` tricks, negative for a player who overbids.
+    ///
+    /// Hands of the same size are used if they have played one, and otherwise
+    /// every hand they have played.
+    pub fn bid_error(&self, player: PlayerId, num_tricks: usize) -> f64 {
+        let records = match self.bids.get(&player) {
+            Some(records) => records,
+            None => return 0.0,
+        };
+        let record = match records.get(&num_tricks) {
+            Some(record) => *record,
+            None => records
+                .values()
+                .fold(BidRecord::default(), |total, e| BidRecord {
+                    hands: total.hands + e.hands,
+                    made: total.made + e.made,
+                    error: total.error + e.error,
+                }),
+        };
+
+        record.error as f64 / (record.hands as f64 + PRIOR_HANDS)
+    }
+
+    /// Returns the number of tricks `player` is expected to take in all in a hand
+    /// of `num_tricks` tricks, having bid `bid`.
+    pub fn expected_tricks(&self, player: PlayerId, bid: isize, num_tricks: usize) -> f64 {
+        (bid as f64 + self.bid_error(player, num_tricks)).clamp(0.0, num_tricks as f64)
+    }
+
+    /// Returns the chance that `player` leads a trump when they hold one.
+    pub fn trump_lead_rate(&self, player: PlayerId) -> f64 {
+        match self.leads.get(&player) {
+            Some(record) => record.trumps as f64 / (record.leads as f64 + PRIOR_LEADS),
+            None => 0.0,
+        }
+    }
+
+    /// Returns how likely `hands`, a deal of the cards still held in the hand seen
+    /// by `view`, is given the leads made so far, relative to a deal in which no
+    /// lead goes against a player's habits.
+    ///
+    /// A player who leads trumps when they hold them makes any deal in which they
+    /// held a trump while leading another suit less likely.
+    pub fn deal_weight(&self, view: &GameView, hands: &HashMap<PlayerId, CardSet>) -> f64 {
+        let trump = view.get_trump_suit();
+        let mut plays: Vec<(PlayerId, Card)> = Vec::new();
+        let mut leads = Vec::new();
+        for trick in view.get_tricks() {
+            leads.push(plays.len());
+            plays.extend_from_slice(trick.get_cards_played());
+        }
+        if !view.get_current_trick().is_empty() {
+            leads.push(plays.len());
+            plays.extend_from_slice(view.get_current_trick());
+        }
+
+        let mut weight = 1.0;
+        for index in leads {
+            let (player, card) = plays[index];
+            let holds_trump = hands.get(&player).is_some_and(|e| e.count_suit(trump) > 0);
+            if player != view.get_player()
+                && card.suit() != trump
+                && (holds_trump || held_trump(&plays, index, player, trump))
+            {
+                weight *= (1.0 - self.trump_lead_rate(player)).max(MIN_WEIGHT);
+            }
+        }
+        weight
+    }
+
+    /// Adds the bids and leads of a finished hand to the model.
+    fn learn(&mut self, hand: &HandEvents) {
+        self.hands += 1;
+
+        for (player, bid) in hand.bids.iter() {
+            let won = hand.tricks_won.get(player).copied().unwrap_or(0);
+            let record = self
+                .bids
+                .entry(*player)
+                .or_default()
+                .entry(hand.num_tricks)
+                .or_default();
+            record.hands += 1;
+            record.made += usize::from(won == *bid);
+            record.error += won - bid;
+        }
+
+        for index in hand.leads.iter() {
+            let (player, card) = hand.plays[*index];
+            if held_trump(&hand.plays, *index, player, hand.trump) {
+                let record = self.leads.entry(player).or_default();
+                record.leads += 1;
+                record.trumps += usize::from(card.suit() == hand.trump);
+            }
+        }
+    }
+}
+
+/// Returns true if `player` is shown to hold a trump at the play at `index` in
+/// `plays`, by playing one then or later.
+fn held_trump(plays: &[(PlayerId, Card)], index: usize, player: PlayerId, trump: Suit) -> bool {
+    plays[index..]
+        .iter()
+        .any(|(id, card)| *id == player && card.suit() == trump)
+}
+
+/// Returns the events of a finished hand.
+fn hand_events(hand: &Hand) -> Vec<GameEvent> {
+    let mut players: Vec<PlayerId> = hand.get_bids().keys().copied().collect();
+    players.sort_by_key(|e| e.seat());
+
+    let mut events = vec![GameEvent::HandDealt {
+        dealer: hand.get_dealer(),
+        trump: hand.get_trump(),
+        deal: players
+            .iter()
+            .map(|e| (*e, hand.get_deal(*e).to_vec()))
+            .collect(),
+    }];
+    for player in players.iter() {
+        events.push(GameEvent::BidPlaced {
+            player: *player,
+            bid: hand.get_bids()[player],
+        });
+    }
+    for trick in hand.get_tricks() {
+        for (player, card) in trick.get_cards_played() {
+            events.push(GameEvent::CardPlayed {
+                player: *player,
+                card: *card,
+            });
+        }
+        events.push(GameEvent::TrickWon {
+            winner: trick.get_winner(),
+        });
+    }
+    events.push(GameEvent::HandScored {
+        scores: hand.get_scores().clone(),
+    });
+
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::card;
+    use crate::game::{Decision, GameState};
+    use crate::rules::Rules;
+    use crate::simulation::sample_likely_hands;
+    use crate::PlayerHands;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    /// Plays a hand of `deal` led by seat 0 in `state`, with each seat bidding
+    /// `bids[seat]` and playing the first card it may in the order dealt.
+    fn play_hand(state: &mut GameState, deal: &[&[&str]], bids: &[isize]) {
+        let hands: PlayerHands = deal
+            .iter()
+            .enumerate()
+            .map(|(seat, e)| (PlayerId::new(seat), e.iter().map(|e| card(e)).collect()))
+            .collect();
+        let dealer = PlayerId::new(0);
+        let rules = state.get_rules().clone();
+        state.start_hand(Hand::from_deal(
+            deal.len(),
+            &rules,
+            dealer,
+            card("2H"),
+            hands.clone(),
+        ));
+
+        let finished = state.get_hands().len() + 1;
+        while state.get_hands().len() < finished {
+            match state.pending_decision().unwrap() {
+                Decision::Bid(player) => state.place_bid(player, bids[player.seat()]).unwrap(),
+                Decision::Play(player) => {
+                    let view = state.view(player).unwrap();
+                    let legal = view.legal_cards(&state.get_cards(player).unwrap().to_vec());
+                    let card = hands[&player].iter().find(|e| legal.contains(e)).unwrap();
+                    state.play_card(player, *card).unwrap();
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn bids_are_learned_by_hand_size() {
+        let mut state =
+            GameState::new(2, Rules::default().hand_sizes(vec![1, 1, 2, 1]), 3).unwrap();
+        play_hand(&mut state, &[&["AS"], &["KS"]], &[1, 1]);
+        play_hand(&mut state, &[&["AD"], &["KD"]], &[1, 1]);
+        play_hand(&mut state, &[&["AC", "QC"], &["KC", "JC"]], &[2, 0]);
+
+        let model = OpponentModel::from_events(state.get_events());
+        let (first, second) = (PlayerId::new(0), PlayerId::new(1));
+
+        assert_eq!(model.get_hands(), 3);
+        assert_eq!(model.bid_accuracy(first, 1), Some(1.0));
+        assert_eq!(model.bid_accuracy(second, 1), Some(0.0));
+        assert_eq!(model.bid_accuracy(second, 2), Some(1.0));
+        assert_eq!(model.bid_accuracy(second, 3), None);
+        assert_eq!(model.bid_error(second, 1), -0.5);
+        assert_eq!(model.bid_error(second, 3), -0.4);
+        assert_eq!(model.expected_tricks(second, 1, 1), 0.5);
+
+        let view = state.view(first).unwrap();
+        assert_eq!(OpponentModel::from_view(&view), model);
+    }
+
+    #[test]
+    fn trump_leads_make_other_leads_unlikely_with_trumps_held() {
+        // Seat 0 leads its trump in every hand while it holds one
+        let mut state = GameState::new(2, Rules::default().hand_sizes(vec![2, 2, 2]), 3).unwrap();
+        for _ in 0..2 {
+            play_hand(&mut state, &[&["3H", "AS"], &["KS", "5D"]], &[1, 1]);
+        }
+        let model = OpponentModel::from_events(state.get_events());
+        let leader = PlayerId::new(0);
+        assert_eq!(model.trump_lead_rate(leader), 0.5);
+
+        // In the next hand it leads a spade, which is likelier if it has no trump
+        let view = GameView::new(PlayerId::new(1), 2, card("2H"), 2)
+            .current_trick(vec![(leader, card("AS"))]);
+        let with_trump: HashMap<PlayerId, CardSet> =
+            [(leader, [card("4H")].iter().collect())].into();
+        let without: HashMap<PlayerId, CardSet> = [(leader, [card("4C")].iter().collect())].into();
+
+        assert_eq!(model.deal_weight(&view, &with_trump), 0.5);
+        assert_eq!(model.deal_weight(&view, &without), 1.0);
+        assert_eq!(OpponentModel::new().deal_weight(&view, &with_trump), 1.0);
+    }
+
+    #[test]
+    fn trump_leaders_are_sampled_with_fewer_trumps_after_another_lead() {
+        // Seat 0 leads its trump in every hand while it holds one
+        let mut state = GameState::new(2, Rules::default().hand_sizes(vec![2; 9]), 3).unwrap();
+        for _ in 0..8 {
+            play_hand(&mut state, &[&["3H", "AS"], &["KS", "5D"]], &[1, 1]);
+        }
+        let model = OpponentModel::from_events(state.get_events());
+        let leader = PlayerId::new(0);
+
+        // In the next hand it leads a spade, holding one card the other seat can not see
+        let view = GameView::new(PlayerId::new(1), 2, card("2H"), 2)
+            .current_trick(vec![(leader, card("AS"))])
+            .played_cards([card("AS")].iter().collect());
+        let cards = [card("KS"), card("5D")];
+        let trumps_dealt = |model: &OpponentModel| {
+            let mut rng = StdRng::seed_from_u64(5);
+            (0..400)
+                .filter_map(|_| sample_likely_hands(&view, &cards, model, &mut rng))
+                .filter(|e| e[&leader].count_suit(Suit::Hearts) > 0)
+                .count()
+        };
+
+        assert!(trumps_dealt(&model) * 2 < trumps_dealt(&OpponentModel::new()));
+    }
+}
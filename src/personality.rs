@@ -0,0 +1,220 @@
+//! Styles of play for the heuristic [AIPlayer].
+//!
+//! A [Personality] sets how an [AIPlayer] bids and plays, without changing how
+//! well it reads its hand:
+//! - Bid aggression is added to the tricks its hand is expected to take before
+//!   rounding to a bid, so bids above zero overbid or underbid the hand.
+//! - Bid-0 risk is the strength of hand below which it bids nothing, gambling
+//!   that it can give away the tricks such a hand would take. It bids at least
+//!   one trick with any stronger hand.
+//! - Trump leads make it lead its highest trump, while short of its bid, once
+//!   trumps are a large enough share of its hand.
+//! - Ruffing sets how keen it is to ruff a trick while short of its bid, rather
+//!   than throwing away a card and keeping its trumps. It always ruffs when it
+//!   needs every trick left.
+//!
+//! The default personality plays as the [AIPlayer] always has. Three named
+//! presets are provided, "Cautious", "Gambler", and "Trump-happy", and each of
+//! the AI opponents of a game played from stdinput plays with one of them.
+//!
+//! # Examples
+//! ```
+//! use contract_whist::personality::Personality;
+//!
+//! let gambler = Personality::preset("gambler").unwrap();
+//! assert_eq!(gambler, Personality::GAMBLER);
+//!
+//! // A hand expected to take 1.6 tricks
+//! assert_eq!(Personality::default().bid(1.6, 5), 2);
+//! assert_eq!(Personality::CAUTIOUS.bid(1.6, 5), 1);
+//!
+//! let custom = Personality::default().aggression(0.5).ruffing(0.0);
+//! assert_eq!(custom.bid(1.6, 5), 2);
+//! ```
+//!
+//! [AIPlayer]: crate::player::AIPlayer
+
+/// The style of play of an [crate::player::AIPlayer].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Personality {
+    aggression: f64,
+    zero_risk: f64,
+    trump_leads: f64,
+    ruffing: f64,
+}
+
+impl Personality {
+    /// Bids its hand as it is expected to play, and ruffs whenever it needs a trick.
+    pub const BALANCED: Personality = Personality {
+        aggression: 0.0,
+        zero_risk: 0.5,
+        trump_leads: 0.0,
+        ruffing: 1.0,
+    };
+
+    /// Underbids, bids nothing with hands a little stronger than the default does,
+    /// and ruffs whenever it needs a trick.
+    pub const CAUTIOUS: Personality = Personality {
+        aggression: -0.3,
+        zero_risk: 0.75,
+        trump_leads: 0.0,
+        ruffing: 1.0,
+    };
+
+    /// Overbids, bids nothing with hands that could take a trick, and keeps its
+    /// trumps until it needs most of the tricks left.
+    pub const GAMBLER: Personality = Personality {
+        aggression: 0.35,
+        zero_risk: 1.0,
+        trump_leads: 0.25,
+        ruffing: 0.4,
+    };
+
+    /// Bids a little high, draws trumps when a quarter of its hand is trumps, and
+    /// ruffs whenever it needs a trick.
+    pub const TRUMP_HAPPY: Personality = Personality {
+        aggression: 0.15,
+        zero_risk: 0.5,
+        trump_leads: 0.75,
+        ruffing: 1.0,
+    };
+
+    /// Every named preset.
+    pub const PRESETS: [(&'static str, Personality); 3] = [
+        ("Cautious", Personality::CAUTIOUS),
+        ("Gambler", Personality::GAMBLER),
+        ("Trump-happy", Personality::TRUMP_HAPPY),
+    ];
+
+    /// Returns the preset called `name`, in any case.
+    pub fn preset(name: &str) -> Option<Personality> {
+        Personality::PRESETS
+            .into_iter()
+            .find(|(preset, _)| preset.eq_ignore_ascii_case(name.trim()))
+            .map(|(_, personality)| personality)
+    }
+
+    /// Sets the number of tricks added to the strength of a hand before it is
+    /// rounded to a bid. Negative values underbid.
+    pub fn aggression(self, aggression: f64) -> Self {
+        Personality { aggression, ..self }
+    }
+
+    /// Sets the strength of hand below which nothing is bid. Stronger hands bid at
+    /// least one trick.
+    pub fn zero_risk(self, zero_risk: f64) -> Self {
+        Personality { zero_risk, ..self }
+    }
+
+    /// Sets the preference for leading trumps, from 0 to never lead them to win
+    /// tricks, to 1 to lead them holding any.
+    pub fn trump_leads(self, trump_leads: f64) -> Self {
+        Personality {
+            trump_leads: trump_leads.clamp(0.0, 1.0),
+            ..self
+        }
+    }
+
+    /// Sets the willingness to ruff while short of a bid, from 0 to ruff only when
+    /// every trick left is needed, to 1 to ruff whenever a trick is needed.
+    pub fn ruffing(self, ruffing: f64) -> Self {
+        Personality {
+            ruffing: ruffing.clamp(0.0, 1.0),
+            ..self
+        }
+    }
+
+    /// Returns the number of tricks added to the strength of a hand for a bid.
+    pub fn get_aggression(&self) -> f64 {
+        self.aggression
+    }
+
+    /// Returns the strength of hand below which nothing is bid.
+    pub fn get_zero_risk(&self) -> f64 {
+        self.zero_risk
+    }
+
+    /// Returns the preference for leading trumps.
+    pub fn get_trump_leads(&self) -> f64 {
+        self.trump_leads
+    }
+
+    /// Returns the willingness to ruff while short of a bid.
+    pub fn get_ruffing(&self) -> f64 {
+        self.ruffing
+    }
+
+    /// Returns the bid for a hand expected to take `strength` tricks, of at most
+    /// `num_tricks`.
+    pub fn bid(&self, strength: f64, num_tricks: usize) -> isize {
+        match strength < self.zero_risk {
+            true => 0,
+            false => {
+                ((strength + self.aggression).round() as isize).clamp(1, num_tricks.max(1) as isize)
+            }
+        }
+    }
+
+    /// Returns true if trumps should be led to win tricks, holding `trumps` of
+    /// `num_cards` cards.
+    pub fn leads_trumps(&self, trumps: usize, num_cards: usize) -> bool {
+        trumps > 0 && trumps as f64 > (1.0 - self.trump_leads) * num_cards as f64
+    }
+
+    /// Returns true if a trick should be ruffed, needing `tricks_needed` of the
+    /// `num_cards` tricks left.
+    pub fn ruffs(&self, tricks_needed: isize, num_cards: usize) -> bool {
+        tricks_needed > 0 && tricks_needed as f64 >= (1.0 - self.ruffing) * num_cards as f64
+    }
+}
+
+impl Default for Personality {
+    fn default() -> Personality {
+        Personality::BALANCED
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_personality_bids_the_rounded_strength() {
+        let personality = Personality::default();
+
+        assert_eq!(personality.bid(0.49, 3), 0);
+        assert_eq!(personality.bid(0.5, 3), 1);
+        assert_eq!(personality.bid(2.4, 3), 2);
+        assert_eq!(personality.bid(3.0, 3), 3);
+        assert!(!personality.leads_trumps(3, 3));
+        assert!(personality.ruffs(1, 7));
+    }
+
+    #[test]
+    fn presets_change_the_bid_and_play() {
+        assert_eq!(Personality::GAMBLER.bid(0.6, 3), 0);
+        assert_eq!(Personality::GAMBLER.bid(1.2, 3), 2);
+        assert_eq!(Personality::CAUTIOUS.bid(0.6, 3), 0);
+        assert_eq!(Personality::CAUTIOUS.bid(1.7, 3), 1);
+        for strength in (0..=30).map(|e| e as f64 / 10.0) {
+            assert!(
+                Personality::CAUTIOUS.bid(strength, 3) <= Personality::BALANCED.bid(strength, 3)
+            );
+        }
+
+        assert!(Personality::TRUMP_HAPPY.leads_trumps(2, 7));
+        assert!(!Personality::TRUMP_HAPPY.leads_trumps(1, 7));
+        assert!(!Personality::GAMBLER.ruffs(2, 7));
+        assert!(Personality::GAMBLER.ruffs(5, 7));
+    }
+
+    #[test]
+    fn presets_are_found_by_name_in_any_case() {
+        assert_eq!(
+            Personality::preset("TRUMP-HAPPY"),
+            Some(Personality::TRUMP_HAPPY)
+        );
+        assert_eq!(Personality::preset(" gambler "), Some(Personality::GAMBLER));
+        assert_eq!(Personality::preset("Balanced"), None);
+    }
+}
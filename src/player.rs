@@ -1,17 +1,27 @@
 //! Functionality related to a [Player] of a Card game, such as bidding and
 //! playing cards from a hand.
 //!
-//! Contains two types of Players, Human and AI. Human players ask for input
-//! from stdinput and AI players make plays based on pre-decided logic.
+//! Contains three types of Players, Human, AI, and Beginner. Human players ask
+//! for input from stdinput, AI players make plays based on pre-decided logic, and
+//! Beginner players bid roughly and play any card they may.
 //!
-//! AI bidding logic is simple. AI players bid one for each card in trump. At a
-//! future point, they will also take into account if they are the lead player
-//! or not and adjust their bid accordingly.
+//! AI players bid the number of tricks their hand is expected to take, as
+//! estimated by [crate::bidding::estimate_bid] from its high cards, trumps, and
+//! short suits.
 //!
-//! AI playing logic is also simple. They prefer playing high in the lead suit.
-//! If no card in the led suit is found, they switch over to trump, playing low
-//! to high. Otherwise, they play the highest slough card with no respect to
-//! rank.
+//! AI players play towards their bid. While short of it they fight for tricks,
+//! winning as cheaply as they can and keeping their trumps to ruff with. Once
+//! the bid is made they duck, playing under the winning card and throwing away
+//! their high cards while they can. With few cards left, they play the rest of
+//! the hand exactly for their bid, as found by [crate::endgame::best_cards].
+//! How high they bid, and how keen they are to lead trumps or ruff, is set by
+//! their [Personality].
+//!
+//! Over a game, AI players learn the other players' habits from the finished
+//! hands with an [OpponentModel]: how far they tend to miss their bids, and how
+//! often they lead trumps. This sets which deals of the unseen cards they think
+//! likely, and whether they can afford to give away a trick to break the bid of
+//! the player winning it.
 //!
 //! Human players are asked for their bid and play from stdinput. Constraints
 //! are placed so that a human player may not bid higher than the number of
@@ -20,32 +30,139 @@
 //!
 //! # Todo
 //! - [ ] Update documentation
-//! - [ ] Add lead player to bidding logic
 
-use std::{fmt, hash, io};
+use std::{fmt, io};
+
+use rand::random;
+use rand::seq::SliceRandom;
+
+use crate::card_set::CardSet;
+use crate::difficulty::Difficulty;
+use crate::endgame;
+use crate::memory::CardMemory;
+use crate::opponents::OpponentModel;
+use crate::personality::Personality;
+use crate::rank::Rank;
+use crate::simulation::decision_rng;
+use crate::trick::TrickComparator;
+use crate::{bidding, card::Card, view::GameView, MAX_DISPLAY_WIDTH};
+
+/// Stable identifier for a [Player], based on their seat at the table.
+///
+/// Seats are numbered from 0 in the order the players were added to the game.
+/// All engine maps (hands, bids, tricks won, and points) are keyed on the
+/// PlayerId, so display names are free-form and need not be unique.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PlayerId(usize);
+
+impl PlayerId {
+    /// Creates the PlayerId for the given seat.
+    pub fn new(seat: usize) -> PlayerId {
+        PlayerId(seat)
+    }
+
+    /// Returns the seat number of the Player.
+    pub fn seat(&self) -> usize {
+        self.0
+    }
+}
 
-use crate::{card::Card, MAX_DISPLAY_WIDTH};
+impl fmt::Display for PlayerId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Seat {}", self.0)
+    }
+}
 
 /// Trait defining base Player behavior.
 ///
-/// All that is expected is that the Player will store a `name`. This
-/// name is used as a key in HashMaps for storing the cards in a Player's hand
-/// and the points for the hand and game. The Hash, PartialEq, and Eq trait
-/// implementations rely only on the player's name, so two players with the same
-/// name are equal to each other and will have the same hash.
-pub trait Player {
+/// All that is expected is that the Player will store a `name`. The name is
+/// only used for display; Players are identified in the engine by their
+/// [PlayerId].
+///
+/// # Examples
+/// ```
+/// use contract_whist::{Card, GameView, Player};
+///
+/// /// Bids nothing and always plays the first card it may legally play.
+/// #[derive(Clone)]
+/// struct FirstCardPlayer {
+///     name: String,
+/// }
+///
+/// impl Player for FirstCardPlayer {
+///     fn get_name(&self) -> &String {
+///         &self.name
+///     }
+///
+///     fn play_card(&self, view: &GameView, cards: &[Card]) -> Card {
+///         view.legal_cards(cards)[0]
+///     }
+///
+///     fn display_hand(&self, _: &[Card]) {}
+///
+///     fn get_player_bid(&self, _: &GameView, _: &[Card]) -> isize {
+///         0
+///     }
+///
+///     fn clone_dyn(&self) -> Box<dyn Player> {
+///         Box::new(self.clone())
+///     }
+/// }
+/// ```
+pub trait Player: Send {
     /// Returns the name of the Player.
-    ///
-    /// Used in the Hash, PartialEq, and Eq implementation.
     fn get_name(&self) -> &String;
     /// Returns a card selected from the Player's hand.
-    fn play_card(&self, trump: &Card, led: Option<&Card>, cards: Vec<Card>) -> (Card, Vec<Card>);
+    ///
+    /// The card must be one of `cards` and must follow the led suit if possible, as
+    /// given by [GameView::legal_cards].
+    /// The [crate::hand::Hand] removes the card from the Player's hand. The
+    /// [GameView] holds the trump, the cards played to the trick so far, the bids,
+    /// and the rest of the public information in the game.
+    fn play_card(&self, view: &GameView, cards: &[Card]) -> Card;
     /// Displays the hand of the Player.
     fn display_hand(&self, cards: &[Card]);
-    /// Returns the Player's bid.
-    fn get_player_bid(&self, trump: &Card, tricks_this_bid: &usize, cards: &[Card]) -> isize;
+    /// Returns the Player's bid, between 0 and the number of tricks in the hand.
+    ///
+    /// The [GameView] holds the trump and the bids placed before this Player's.
+    fn get_player_bid(&self, view: &GameView, cards: &[Card]) -> isize;
     /// Used to implement the Clone trait.
     fn clone_dyn(&self) -> Box<dyn Player>;
+    /// Seeds any randomness used by the Player when making decisions.
+    ///
+    /// Called by [crate::game::ContractWhistGame] before the first hand, so a game
+    /// played with the same seed makes the same decisions. Does nothing by default.
+    fn set_seed(&mut self, _seed: u64) {}
+    /// Returns the [Difficulty] the Player plays at, if it is one of the AI players
+    /// created for a difficulty.
+    ///
+    /// Written to a [crate::save::SavedGame], so a resumed game is played by the
+    /// same opponents. None by default.
+    fn get_difficulty(&self) -> Option<Difficulty> {
+        None
+    }
+    /// Returns true to take back the Player's last bid or card, along with every
+    /// decision made by the other players since.
+    ///
+    /// Asked by [crate::game::ContractWhistGame] in casual games before each of the
+    /// Player's decisions, once they have a decision to take back. Never takes back
+    /// by default.
+    fn take_back(&self, _view: &GameView) -> bool {
+        false
+    }
+    /// Tells the Player that `player` played `card`, which may be one of this
+    /// Player's own cards.
+    ///
+    /// `view` is the Player's view of the hand just before the card was played.
+    /// Called by the game for every card played, so a Player may keep its own
+    /// memory of the hand. Does nothing by default.
+    fn card_played(&mut self, _view: &GameView, _player: PlayerId, _card: Card) {}
+    /// Tells the Player that decisions were taken back, with its view of the game as
+    /// it is now.
+    ///
+    /// A Player keeping its own memory of the hand should rebuild it from the view.
+    /// Does nothing by default.
+    fn decisions_taken_back(&mut self, _view: &GameView) {}
 }
 
 impl fmt::Display for dyn Player {
@@ -60,20 +177,6 @@ impl fmt::Debug for dyn Player {
     }
 }
 
-impl hash::Hash for Box<dyn Player> {
-    fn hash<H: hash::Hasher>(&self, state: &mut H) {
-        self.get_name().hash(state);
-    }
-}
-
-impl PartialEq for Box<dyn Player> {
-    fn eq(&self, other: &Self) -> bool {
-        self.get_name() == other.get_name()
-    }
-}
-
-impl Eq for Box<dyn Player> {}
-
 impl Clone for Box<dyn Player> {
     fn clone(&self) -> Box<dyn Player> {
         self.clone_dyn()
@@ -95,6 +198,27 @@ impl HumanPlayer {
     }
 }
 
+impl HumanPlayer {
+    /// Displays the bids placed so far and the tricks won against them.
+    fn display_bids(&self, view: &GameView) {
+        if view.get_bids().is_empty() {
+            return;
+        }
+
+        println!("Player               Bid  Won");
+        println!("{}", "-".repeat(MAX_DISPLAY_WIDTH - 5));
+        for (id, bid) in view.get_bids() {
+            println!(
+                "{:<20} {:^4} {:^4}",
+                view.get_name(*id),
+                bid,
+                view.get_tricks_won(*id)
+            );
+        }
+        println!();
+    }
+}
+
 impl Player for HumanPlayer {
     fn clone_dyn(&self) -> Box<dyn Player> {
         Box::new(self.clone())
@@ -104,6 +228,22 @@ impl Player for HumanPlayer {
         &self.name
     }
 
+    fn take_back(&self, _view: &GameView) -> bool {
+        loop {
+            let mut input = String::new();
+            println!();
+            println!("Press Enter to continue, or type 'u' to take back your last move.");
+            match io::stdin().read_line(&mut input) {
+                Ok(_) => match input.trim() {
+                    "" => return false,
+                    "u" | "U" => return true,
+                    _ => println!("Please press Enter or type 'u'!"),
+                },
+                Err(_) => println!("Error attempting to read input."),
+            }
+        }
+    }
+
     fn display_hand(&self, cards: &[Card]) {
         println!("Index Card");
         println!("--------------------");
@@ -112,16 +252,17 @@ impl Player for HumanPlayer {
         }
     }
 
-    fn get_player_bid(&self, trump: &Card, tricks_this_hand: &usize, cards: &[Card]) -> isize {
+    fn get_player_bid(&self, view: &GameView, cards: &[Card]) -> isize {
         let bid: isize;
-        let max_bids: usize = *tricks_this_hand;
+        let max_bids: usize = view.get_num_tricks();
 
         println!();
 
         loop {
             let mut input = String::new();
-            println!("Trump this hand is: {}", &trump);
+            println!("Trump this hand is: {}", view.get_trump());
             println!();
+            self.display_bids(view);
             self.display_hand(cards);
             println!();
             println!("What do you bid?");
@@ -147,26 +288,28 @@ impl Player for HumanPlayer {
         bid
     }
 
-    fn play_card(
-        &self,
-        trump: &Card,
-        led: Option<&Card>,
-        mut cards: Vec<Card>,
-    ) -> (Card, Vec<Card>) {
+    fn play_card(&self, view: &GameView, cards: &[Card]) -> Card {
+        let led = view.get_led_card();
+
         println!();
+        self.display_bids(view);
         println!("Here is your hand");
-        self.display_hand(&cards);
+        self.display_hand(cards);
         println!();
-        println!("Trump is: {:>1$}", &trump, MAX_DISPLAY_WIDTH - 10);
+        println!("Trump is: {:>1$}", view.get_trump(), MAX_DISPLAY_WIDTH - 10);
         println!();
-        if let Some(card) = led {
-            println!("Led Card is: {:>1$}", &card, MAX_DISPLAY_WIDTH - 13);
+        if led.is_some() {
+            for (id, card) in view.get_current_trick() {
+                println!("{:<20} {}", view.get_name(*id), card);
+            }
         } else {
             println!("You are the lead player");
         }
         println!();
         println!("What card would you like to play?");
 
+        let legal = view.legal_cards(cards);
+
         // println!();
         // println!("HumanPlayer play_card called");
         // println!("{:#?}", cards);
@@ -181,51 +324,107 @@ impl Player for HumanPlayer {
             };
 
             if index < cards.len() {
-                if let Some(led_card) = led {
-                    let (_, led_suit) = led_card.get_value();
-                    let has_cards_in_led_suit =
-                        cards.iter().filter(|e| e.get_value().1 == led_suit).count() > 0;
-                    let chosen_card_is_in_led_suit =
-                        cards.get(index).unwrap().get_value().1 == led_suit;
-
-                    // println!();
-                    // println!("Has card in led suit: {}", has_cards_in_led_suit);
-                    // println!("Chosen card is in led suit: {}", chosen_card_is_in_led_suit);
-                    // println!();
-
-                    if has_cards_in_led_suit {
-                        if chosen_card_is_in_led_suit {
-                            return (cards.swap_remove(index), cards);
-                        } else {
-                            println!("You must follow suit");
-                        }
-                    } else {
-                        return (cards.swap_remove(index), cards);
-                    }
+                if legal.contains(&cards[index]) {
+                    return cards[index];
                 } else {
-                    return (cards.swap_remove(index), cards);
+                    println!("You must follow suit");
                 }
             } else {
                 println!("Tried selecting a card you don't have.");
                 println!("Here is your hand.");
-                self.display_hand(&cards);
+                self.display_hand(cards);
                 println!("What card would you like to play?");
             };
         }
     }
 }
 
+/// The AI implementation of the Player trait.
+///
+/// Bids and plays based on the pre-decided logic described in the module
+/// documentation, remembering the cards played in the hand with a [CardMemory].
 #[derive(Clone, Debug)]
 pub struct AIPlayer {
     name: String,
+    memory: Option<CardMemory>,
+    model: Option<OpponentModel>,
+    endgame: usize,
+    personality: Personality,
+    seed: u64,
 }
 
 impl AIPlayer {
+    /// The number of cards left in hand at which the AI starts to play exactly by
+    /// default.
+    pub const DEFAULT_ENDGAME: usize = 3;
+
+    /// Creates a new AIPlayer with the default [Personality], which plays exactly
+    /// with [AIPlayer::DEFAULT_ENDGAME] cards left.
     pub fn new(name: String) -> Self {
-        AIPlayer { name }
+        AIPlayer {
+            name,
+            memory: None,
+            model: None,
+            endgame: AIPlayer::DEFAULT_ENDGAME,
+            personality: Personality::default(),
+            seed: random(),
+        }
+    }
+
+    /// Sets the style the AI bids and plays in.
+    pub fn personality(self, personality: Personality) -> Self {
+        AIPlayer {
+            personality,
+            ..self
+        }
+    }
+
+    /// Returns the style the AI bids and plays in.
+    pub fn get_personality(&self) -> &Personality {
+        &self.personality
+    }
+
+    /// Sets the number of cards left in hand at or below which the AI plays by
+    /// solving the rest of the hand exactly. Zero never solves the hand.
+    pub fn endgame(self, endgame: usize) -> Self {
+        AIPlayer { endgame, ..self }
+    }
+
+    /// Returns the number of cards left in hand at or below which the AI plays
+    /// exactly.
+    pub fn get_endgame(&self) -> usize {
+        self.endgame
+    }
+
+    /// Returns the memory of the hand seen by `view`.
+    ///
+    /// The memory kept from the cards played is used if it is up to date, and is
+    /// otherwise rebuilt from the view.
+    fn memory_of(&self, view: &GameView) -> CardMemory {
+        match &self.memory {
+            Some(memory) if is_current(memory, view) => memory.clone(),
+            _ => CardMemory::from_view(view),
+        }
+    }
+
+    /// Returns the model of the players learned from the finished hands seen by
+    /// `view`.
+    ///
+    /// The model kept from earlier decisions is used if it has learned from every
+    /// finished hand, and is otherwise rebuilt from the view.
+    fn model_of(&self, view: &GameView) -> OpponentModel {
+        match &self.model {
+            Some(model) if model.get_hands() == view.get_previous_hands().len() => model.clone(),
+            _ => OpponentModel::from_view(view),
+        }
     }
 }
 
+/// Returns true if `memory` holds every card played in the hand seen by `view`.
+fn is_current(memory: &CardMemory, view: &GameView) -> bool {
+    memory.get_trump() == view.get_trump() && memory.get_played() == view.get_played_cards()
+}
+
 impl Player for AIPlayer {
     /// Used in the trait Player impl of Clone
     fn clone_dyn(&self) -> Box<dyn Player> {
@@ -242,141 +441,324 @@ impl Player for AIPlayer {
         }
     }
 
-    #[allow(unused_variables)]
-    fn get_player_bid(&self, trump: &Card, tricks_this_bid: &usize, cards: &[Card]) -> isize {
-        cards
-            .iter()
-            .filter(|e| e.get_value().1 == trump.get_value().1)
-            .count()
-            .try_into()
-            .unwrap()
+    fn get_player_bid(&self, view: &GameView, cards: &[Card]) -> isize {
+        let strength = bidding::hand_strength(view, cards);
+        self.personality.bid(strength, view.get_num_tricks())
+    }
+
+    fn card_played(&mut self, view: &GameView, player: PlayerId, card: Card) {
+        let mut memory = self.memory_of(view);
+        memory.record(view.get_current_trick(), player, card);
+        self.memory = Some(memory);
+        self.model = Some(self.model_of(view));
+    }
+
+    fn decisions_taken_back(&mut self, view: &GameView) {
+        self.memory = Some(CardMemory::from_view(view));
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    fn get_difficulty(&self) -> Option<Difficulty> {
+        Some(Difficulty::Intermediate)
     }
 
     /// Logic for playing a Card
     /// # Logic
-    /// If there has been a led card:
-    ///   Must follow suit and play the lowest ranking card in the led suit
-    ///   If no cards in the led suit:
-    ///     Play the lowest card in trump suit
-    ///     If no cards in trump suit:
-    ///       Play highest ranking card in whatever suit
-    /// If no led card:
-    ///   Lead with highest trump
-    ///   If no cards in trump:
-    ///    Play highest ranking card
+    /// While short of its bid, the AI fights for tricks:
+    ///   Following suit, it wins as cheaply as it can, or plays its lowest card
+    ///   It gives away a trick to break the bid of the player winning it, if it
+    ///   holds a sure winner for every trick it needs and the other players are
+    ///   expected to leave it enough tricks, judging by how well they have bid
+    ///   Unable to follow, it ruffs with the lowest trump that wins if its
+    ///   personality is willing to, or throws away its lowest card outside trump
+    ///   Leading, it cashes a card sure to win, or leads low from its shortest
+    ///   suit that no opponent can ruff, to be able to ruff it later itself. If
+    ///   its personality prefers to lead trumps, it cashes trumps first, or
+    ///   leads its highest trump
+    /// Once its bid is made, the AI ducks tricks:
+    ///   It plays its highest card that loses to the card winning the trick,
+    ///   trumps first, or its highest card if every card would win
+    ///   Leading, it leads its lowest card, keeping trumps for last
+    /// With the endgame number of cards left or fewer, the AI plays a card that
+    /// makes its bid in the most deals of the cards it cannot see, weighted by how
+    /// likely the other players' leads make them, keeping to the card above if it
+    /// is one of them
     ///
-    fn play_card(
-        &self,
-        trump: &Card,
-        led: Option<&Card>,
-        mut cards: Vec<Card>,
-    ) -> (Card, Vec<Card>) {
-        // Closure to map Card ranks to integers for easy sorting
-        let rank_cards = |e: &Card| e.get_value().0.get_numerical_rank(true);
-        // Since the led card may be either None (current player is the leader) or Some (current player is following)
-        // check for those two states and determine playing logic
-        match led {
-            // Logic for following in a Trick
-            Some(card) => {
-                // Current player is following another player so is bound to the led suit if they have it
-                // Closure to determine if the Card is in the led suit
-                let is_in_led = |e: &&Card| e.get_value().1 == card.get_value().1;
-                let mut led_suit_cards: Vec<i32> =
-                    cards.iter().filter(is_in_led).map(rank_cards).collect();
-                // If player has a led suit card, play the lowest possible
-                if !led_suit_cards.is_empty() {
-                    led_suit_cards.sort_by(|a, b| b.cmp(a));
-                    let card_to_play = cards
-                        .iter()
-                        .position(|e| {
-                            e.get_value().1 == card.get_value().1
-                                && e.get_value().0.get_numerical_rank(true)
-                                    == *led_suit_cards.iter().last().unwrap()
-                        })
-                        .unwrap();
-                    (cards.swap_remove(card_to_play), cards)
-                // Player has no led suit, so play the lowest trump suit card
-                } else {
-                    // Closure to determine if a Card is in the Trump suit
-                    let is_in_trump = |e: &&Card| e.get_value().1 == trump.get_value().1;
-                    let mut trump_suit_cards: Vec<i32> =
-                        cards.iter().filter(is_in_trump).map(rank_cards).collect();
-                    if !trump_suit_cards.is_empty() {
-                        trump_suit_cards.sort_by(|a, b| b.cmp(a));
-                        let card_to_play = cards
-                            .iter()
-                            .position(|e| {
-                                e.get_value().1 == trump.get_value().1
-                                    && e.get_value().0.get_numerical_rank(true)
-                                        == *trump_suit_cards.iter().last().unwrap()
-                            })
-                            .unwrap();
-                        (cards.swap_remove(card_to_play), cards)
-                    // Player has no cards in trump, so play the highest card in whatever suit
-                    } else {
-                        let mut other_cards: Vec<i32> = cards.iter().map(rank_cards).collect();
-                        other_cards.sort_by(|a, b| b.cmp(a));
-                        let card_to_play = cards
-                            .iter()
-                            .position(|e| {
-                                e.get_value().0.get_numerical_rank(true)
-                                    == *other_cards.first().unwrap()
-                            })
-                            .unwrap();
-                        (cards.swap_remove(card_to_play), cards)
-                    }
-                }
+    fn play_card(&self, view: &GameView, cards: &[Card]) -> Card {
+        let card = self.play_towards_bid(view, cards);
+        if cards.len() > self.endgame || view.legal_cards(cards).len() == 1 {
+            return card;
+        }
+
+        let mut rng = decision_rng(self.seed, view, cards.iter().collect());
+        let best = endgame::best_cards(view, cards, &self.model_of(view), &mut rng);
+        match best.lowest() {
+            Some(lowest) if !best.contains(card) => lowest,
+            _ => card,
+        }
+    }
+}
+
+impl AIPlayer {
+    /// Returns the card played by the fixed logic of [AIPlayer::play_card].
+    fn play_towards_bid(&self, view: &GameView, cards: &[Card]) -> Card {
+        let legal = view.legal_cards(cards);
+        let tricks_needed =
+            view.get_bid(view.get_player()).unwrap_or(0) - view.get_tricks_won(view.get_player());
+
+        let winning = match view.get_led_card() {
+            Some(led) => {
+                let comparator = TrickComparator::new(view.get_trump_suit(), led.suit());
+                let winning = *view
+                    .get_current_trick()
+                    .iter()
+                    .max_by(|a, b| comparator.compare(&a.1, &b.1))
+                    .unwrap();
+                Some((comparator, winning))
             }
-            // Logic for leading a trick
-            None => {
-                // Closure to determine if a Card is in the Trump suit
-                let is_in_trump = |e: &&Card| e.get_value().1 == trump.get_value().1;
-                let mut trump_suit_cards: Vec<i32> =
-                    cards.iter().filter(is_in_trump).map(rank_cards).collect();
-                if !trump_suit_cards.is_empty() {
-                    trump_suit_cards.sort_by(|a, b| b.cmp(a));
-                    let card_to_play = cards
-                        .iter()
-                        .position(|e| {
-                            e.get_value().1 == trump.get_value().1
-                                && e.get_value().0.get_numerical_rank(true)
-                                    == *trump_suit_cards.iter().last().unwrap()
-                        })
-                        .unwrap();
-                    (cards.swap_remove(card_to_play), cards)
-                // Player has no cards in trump, so play the highest card in whatever suit
-                } else {
-                    let mut other_cards: Vec<i32> = cards.iter().map(rank_cards).collect();
-                    other_cards.sort_by(|a, b| b.cmp(a));
-                    let card_to_play = cards
-                        .iter()
-                        .position(|e| {
-                            e.get_value().0.get_numerical_rank(true)
-                                == *other_cards.first().unwrap()
-                        })
-                        .unwrap();
-                    (cards.swap_remove(card_to_play), cards)
-                }
+            None => None,
+        };
+
+        let ruffs = self.personality.ruffs(tricks_needed, cards.len());
+        let held: CardSet = cards.iter().collect();
+        let leads_trumps = self
+            .personality
+            .leads_trumps(held.count_suit(view.get_trump_suit()), cards.len());
+
+        let gives_away = |winner: PlayerId| {
+            let memory = self.memory_of(view);
+            gives_away(
+                view,
+                cards,
+                &memory,
+                &self.model_of(view),
+                winner,
+                tricks_needed,
+            )
+        };
+
+        match (winning, tricks_needed > 0) {
+            (Some((comparator, (winner, winning))), true) if gives_away(winner) => {
+                duck(view, &legal, &comparator, winning)
             }
+            (Some((comparator, (_, winning))), true) => {
+                fight(view, &legal, &comparator, winning, ruffs)
+            }
+            (Some((comparator, (_, winning))), false) => duck(view, &legal, &comparator, winning),
+            (None, true) => lead_to_win(&self.memory_of(view), cards, &legal, leads_trumps),
+            (None, false) => lead_to_lose(view, &legal),
         }
     }
 }
 
+/// Returns the lowest ranking card in `cards`, preferring cards outside trump.
+fn lowest_keeping_trumps(view: &GameView, cards: &[Card]) -> Card {
+    *cards
+        .iter()
+        .min_by_key(|e| (e.suit() == view.get_trump_suit(), e.rank()))
+        .unwrap()
+}
+
+/// Plays to a trick the AI needs to win, ruffing it only if `ruffs`.
+fn fight(
+    view: &GameView,
+    legal: &[Card],
+    comparator: &TrickComparator,
+    winning: Card,
+    ruffs: bool,
+) -> Card {
+    let trump = comparator.get_trump();
+    let cheapest_winner = legal
+        .iter()
+        .filter(|e| comparator.beats(e, &winning))
+        .filter(|e| ruffs || e.suit() != trump || comparator.get_led() == trump)
+        .min_by(|a, b| comparator.compare(a, b));
+
+    match cheapest_winner {
+        Some(card) => *card,
+        None => lowest_keeping_trumps(view, legal),
+    }
+}
+
+/// Returns true if the AI, needing `tricks_needed` more tricks, can afford to give
+/// away the trick being won by `winner` when taking it would break their bid.
+///
+/// The AI must hold a card sure to win for each trick it needs, and the tricks
+/// left after this one must cover those it needs and those the other players are
+/// expected to take by `model`.
+fn gives_away(
+    view: &GameView,
+    cards: &[Card],
+    memory: &CardMemory,
+    model: &OpponentModel,
+    winner: PlayerId,
+    tricks_needed: isize,
+) -> bool {
+    let player = view.get_player();
+    if winner == player || view.get_bid(winner) != Some(view.get_tricks_won(winner)) {
+        return false;
+    }
+
+    let held: CardSet = cards.iter().collect();
+    let sure_winners = cards
+        .iter()
+        .filter(|e| memory.is_sure_winner(**e, held))
+        .count();
+    let wanted: f64 = view
+        .get_bids()
+        .iter()
+        .filter(|(id, _)| *id != player && *id != winner)
+        .map(|(id, bid)| {
+            let expected = model.expected_tricks(*id, *bid, view.get_num_tricks());
+            (expected - view.get_tricks_won(*id) as f64).max(0.0)
+        })
+        .sum();
+
+    sure_winners as isize >= tricks_needed
+        && tricks_needed + (wanted.round() as isize) < cards.len() as isize
+}
+
+/// Plays to a trick the AI needs to lose.
+fn duck(view: &GameView, legal: &[Card], comparator: &TrickComparator, winning: Card) -> Card {
+    let trump = view.get_trump_suit();
+    let highest_loser = legal
+        .iter()
+        .filter(|e| !comparator.beats(e, &winning))
+        .max_by_key(|e| (e.suit() == trump, e.rank()));
+
+    match highest_loser {
+        Some(card) => *card,
+        None => *legal
+            .iter()
+            .max_by(|a, b| comparator.compare(a, b))
+            .unwrap(),
+    }
+}
+
+/// Leads to a trick the AI needs to win, preferring trumps if `leads_trumps`.
+fn lead_to_win(memory: &CardMemory, cards: &[Card], legal: &[Card], leads_trumps: bool) -> Card {
+    let trump = memory.get_trump().suit();
+    let held: CardSet = cards.iter().collect();
+
+    let sure_winner = legal
+        .iter()
+        .filter(|e| memory.is_sure_winner(**e, held))
+        .max_by_key(|e| ((e.suit() == trump) == leads_trumps, e.rank()));
+    if let Some(card) = sure_winner {
+        return *card;
+    }
+
+    let highest_trump = legal
+        .iter()
+        .filter(|e| e.suit() == trump)
+        .max_by_key(|e| e.rank());
+    if let (true, Some(card)) = (leads_trumps, highest_trump) {
+        return *card;
+    }
+
+    let shortest_suit = legal.iter().filter(|e| e.suit() != trump).min_by_key(|e| {
+        let ruffed = memory.may_be_ruffed(e.suit(), held);
+        (ruffed, held.count_suit(e.suit()), e.rank())
+    });
+    match shortest_suit {
+        Some(card) => *card,
+        None => *legal.iter().max_by_key(|e| e.rank()).unwrap(),
+    }
+}
+
+/// Leads to a trick the AI needs to lose.
+fn lead_to_lose(view: &GameView, legal: &[Card]) -> Card {
+    lowest_keeping_trumps(view, legal)
+}
+
+/// A beginner AI implementation of the Player trait.
+///
+/// Bids one trick for each ace and each trump above the jack it holds, and plays
+/// a card at random from those it may play.
+#[derive(Clone, Debug)]
+pub struct BeginnerPlayer {
+    name: String,
+    seed: u64,
+}
+
+impl BeginnerPlayer {
+    /// Creates a new BeginnerPlayer.
+    pub fn new(name: String) -> Self {
+        BeginnerPlayer {
+            name,
+            seed: random(),
+        }
+    }
+}
+
+impl Player for BeginnerPlayer {
+    fn clone_dyn(&self) -> Box<dyn Player> {
+        Box::new(self.clone())
+    }
+
+    fn get_name(&self) -> &String {
+        &self.name
+    }
+
+    fn display_hand(&self, _cards: &[Card]) {}
+
+    fn get_player_bid(&self, view: &GameView, cards: &[Card]) -> isize {
+        let high_cards = cards
+            .iter()
+            .filter(|e| {
+                e.rank() == Rank::Ace
+                    || (e.suit() == view.get_trump_suit() && e.rank() > Rank::Jack)
+            })
+            .count();
+        high_cards.min(view.get_num_tricks()) as isize
+    }
+
+    fn play_card(&self, view: &GameView, cards: &[Card]) -> Card {
+        let mut rng = decision_rng(self.seed, view, cards.iter().collect());
+        *view.legal_cards(cards).choose(&mut rng).unwrap()
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    fn get_difficulty(&self) -> Option<Difficulty> {
+        Some(Difficulty::Beginner)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::card::Card;
-    use crate::rank::Rank;
+    use crate::card::{card, Card};
+    use crate::game::GameState;
+    use crate::hand::Hand;
+    use crate::rules::Rules;
     use crate::suit::Suit;
+    use crate::PlayerHands;
+    use std::collections::HashMap;
 
     fn setup() -> (Card, Card, AIPlayer) {
         (
             Card::new(Rank::Ace, Suit::Hearts),
             Card::new(Rank::Three, Suit::Clubs),
-            AIPlayer::new(String::from("Tester")),
+            AIPlayer::new(String::from("Tester")).endgame(0),
         )
     }
 
+    /// Creates a view of a two card hand, with `led` played by the other player, for
+    /// a player who bid `bid` and has won no tricks.
+    fn setup_view(trump_card: Card, led: Option<Card>, bid: isize) -> GameView {
+        let current_trick = led.map(|e| (PlayerId::new(1), e)).into_iter().collect();
+
+        GameView::new(PlayerId::new(0), 2, trump_card, 2)
+            .bids(vec![(PlayerId::new(0), bid), (PlayerId::new(1), 0)])
+            .current_trick(current_trick)
+    }
+
     #[test]
     fn ai_player_follows_suit() {
         let (trump_card, led_card, player) = setup();
@@ -384,114 +766,237 @@ mod tests {
         let other_card = Card::new(Rank::Two, Suit::Spades);
         let player_hand = vec![expected_play, other_card];
 
-        let (card, new_hand) = player.play_card(&trump_card, Some(&led_card), player_hand);
+        let card = player.play_card(&setup_view(trump_card, Some(led_card), 1), &player_hand);
         assert_eq!(card, expected_play);
-        assert_eq!(new_hand.len(), 1);
-        assert!(new_hand.contains(&other_card));
     }
 
     #[test]
-    fn ai_player_sloughs_highest_card_when_no_other_play() {
+    fn ai_player_short_of_bid_wins_as_cheaply_as_it_can() {
         let (trump_card, led_card, player) = setup();
-        let slough_card = Card::new(Rank::Jack, Suit::Diamonds);
-        let other_card = Card::new(Rank::Four, Suit::Spades);
-        let player_hand = vec![slough_card, other_card];
-
-        let (played, new_hand) = player.play_card(&trump_card, Some(&led_card), player_hand);
-
-        assert_eq!(slough_card, played);
-        assert_eq!(new_hand.len(), 1);
-        assert!(new_hand.contains(&other_card));
+        let view = setup_view(trump_card, Some(led_card), 1);
+
+        assert_eq!(
+            player.play_card(&view, &[card("JC"), card("4C")]),
+            card("4C")
+        );
+        assert_eq!(
+            player.play_card(&view, &[card("JH"), card("4H"), card("2S")]),
+            card("4H")
+        );
     }
 
     #[test]
-    fn ai_player_trumps_if_given_chance() {
-        let (trump_card, led_card, player) = setup();
-        let in_trump_play = Card::new(Rank::Jack, trump_card.suit());
-        let other_card = Card::new(Rank::Four, Suit::Spades);
-        let player_hand = vec![in_trump_play, other_card];
-
-        let (played, new_hand) = player.play_card(&trump_card, Some(&led_card), player_hand);
+    fn ai_player_short_of_bid_keeps_trumps_when_it_cannot_win() {
+        let (trump_card, _, player) = setup();
+        let view = setup_view(trump_card, Some(card("10C")), 1);
+
+        assert_eq!(
+            player.play_card(&view, &[card("4C"), card("2C")]),
+            card("2C")
+        );
+
+        let view = setup_view(trump_card, Some(card("10C")), 1).current_trick(vec![
+            (PlayerId::new(1), card("10C")),
+            (PlayerId::new(2), card("KH")),
+        ]);
+        assert_eq!(
+            player.play_card(&view, &[card("QH"), card("JD"), card("4S")]),
+            card("4S")
+        );
+    }
 
-        assert_eq!(in_trump_play, played);
-        assert_eq!(new_hand.len(), 1);
-        assert!(new_hand.contains(&other_card));
+    #[test]
+    fn ai_player_with_bid_made_plays_under_the_winning_card() {
+        let (trump_card, _, player) = setup();
+        let view = setup_view(trump_card, Some(card("10C")), 0);
+
+        assert_eq!(
+            player.play_card(&view, &[card("4C"), card("9C"), card("JC")]),
+            card("9C")
+        );
+        assert_eq!(
+            player.play_card(&view, &[card("JH"), card("JD"), card("4S")]),
+            card("JD")
+        );
     }
 
     #[test]
-    fn ai_player_plays_lowest_trump_if_multiple_trump() {
+    fn ai_player_with_bid_made_wins_with_its_highest_card_when_it_must() {
         let (trump_card, led_card, player) = setup();
-        let in_trump_play = Card::new(Rank::Four, trump_card.suit());
-        let other_card = Card::new(Rank::Jack, trump_card.suit());
-        let player_hand = vec![in_trump_play, other_card];
-
-        let (played, new_hand) = player.play_card(&trump_card, Some(&led_card), player_hand);
+        let view = setup_view(trump_card, Some(led_card), 0);
 
-        assert_eq!(in_trump_play, played);
-        assert_eq!(new_hand.len(), 1);
-        assert!(new_hand.contains(&other_card));
+        assert_eq!(
+            player.play_card(&view, &[card("4C"), card("JC")]),
+            card("JC")
+        );
     }
 
     #[test]
-    fn ai_player_plays_lowest_led_if_multiple_led() {
-        let (trump_card, led_card, player) = setup();
-        let in_trump_play = Card::new(Rank::Four, led_card.suit());
-        let other_card = Card::new(Rank::Jack, led_card.suit());
-        let player_hand = vec![in_trump_play, other_card];
+    fn ai_player_short_of_bid_leads_the_highest_card_left_in_a_suit() {
+        let (trump_card, _, player) = setup();
+        let view =
+            setup_view(trump_card, None, 1).played_cards([card("AC"), card("KC")].iter().collect());
 
-        let (played, new_hand) = player.play_card(&trump_card, Some(&led_card), player_hand);
+        assert_eq!(
+            player.play_card(&view, &[card("QC"), card("5C"), card("KS")]),
+            card("QC")
+        );
+    }
 
-        assert_eq!(in_trump_play, played);
-        assert_eq!(new_hand.len(), 1);
-        assert!(new_hand.contains(&other_card));
+    #[test]
+    fn ai_player_short_of_bid_leads_low_from_its_shortest_suit() {
+        let (trump_card, _, player) = setup();
+        let view = setup_view(trump_card, None, 1);
+
+        assert_eq!(
+            player.play_card(&view, &[card("QC"), card("5C"), card("9S"), card("QH")]),
+            card("9S")
+        );
+        assert_eq!(
+            player.play_card(&view, &[card("4H"), card("JH")]),
+            card("JH")
+        );
     }
 
     #[test]
-    fn ai_player_leads_with_highest_trump() {
+    fn ai_player_with_bid_made_leads_its_lowest_card_outside_trump() {
         let (trump_card, _, player) = setup();
-        let led_card = Card::new(Rank::Four, trump_card.suit());
-        let other_card = Card::new(Rank::Jack, trump_card.suit());
-        let player_hand = vec![led_card, other_card];
+        let view = setup_view(trump_card, None, 0);
 
-        let (played, new_hand) = player.play_card(&trump_card, None, player_hand);
+        assert_eq!(
+            player.play_card(&view, &[card("2H"), card("JS"), card("9D")]),
+            card("9D")
+        );
+    }
 
-        assert_eq!(played, led_card);
-        assert_eq!(new_hand.len(), 1);
-        assert!(new_hand.contains(&other_card));
+    #[test]
+    fn ai_player_solves_the_last_tricks_exactly() {
+        // Every card but the opponent's nine of hearts and ten of clubs has been seen,
+        // so leading the two of diamonds lets the opponent throw the ten under it
+        let cards = [card("6H"), card("2D")];
+        let mut played =
+            !(cards.iter().collect::<CardSet>() | [card("9H"), card("10C")].iter().collect());
+        played.remove(card("2H"));
+        let view = setup_view(card("2H"), None, 0).played_cards(played);
+
+        let player = AIPlayer::new("Tester".to_string());
+        assert_eq!(player.play_card(&view, &cards), card("6H"));
+        assert_eq!(player.endgame(0).play_card(&view, &cards), card("2D"));
     }
 
     #[test]
-    fn ai_player_leads_with_highest_non_trump() {
+    fn ai_player_personality_leads_trumps_and_ruffs() {
         let (trump_card, _, player) = setup();
-        let led_card = Card::new(Rank::Queen, Suit::Clubs);
-        let other_card = Card::new(Rank::Five, Suit::Clubs);
-        let player_hand = vec![led_card, other_card];
+        let trump_happy = player.clone().personality(Personality::TRUMP_HAPPY);
+        let view = setup_view(trump_card, None, 1);
+        let cards = [card("QC"), card("9S"), card("QH"), card("4H")];
+
+        assert_eq!(player.play_card(&view, &cards), card("9S"));
+        assert_eq!(trump_happy.play_card(&view, &cards), card("QH"));
+
+        // A gambler keeps its trumps unless it needs most of the tricks left
+        let gambler = player.clone().personality(Personality::GAMBLER);
+        let cards = [card("4H"), card("3S"), card("5D")];
+        let view = setup_view(trump_card, Some(card("10C")), 1);
+        assert_eq!(player.play_card(&view, &cards), card("4H"));
+        assert_eq!(gambler.play_card(&view, &cards), card("3S"));
+
+        let view = setup_view(trump_card, Some(card("10C")), 2);
+        assert_eq!(gambler.play_card(&view, &cards), card("4H"));
+    }
 
-        let (played, new_hand) = player.play_card(&trump_card, None, player_hand);
+    /// Returns the hands of a game in which seat 2 bid one trick in each of three
+    /// one card hands, and lost it to the ace of trumps every time.
+    fn overbidding_history() -> Vec<Hand> {
+        let mut state =
+            GameState::new(3, Rules::default().hand_sizes(vec![1, 1, 1, 3]), 1).unwrap();
+        let deal: PlayerHands = [(0, "AH"), (1, "2C"), (2, "3D")]
+            .iter()
+            .map(|(seat, text)| (PlayerId::new(*seat), vec![card(text)]))
+            .collect();
+
+        for _ in 0..3 {
+            let rules = state.get_rules().clone();
+            let hand = Hand::from_deal(3, &rules, PlayerId::new(0), card("2H"), deal.clone());
+            state.start_hand(hand);
+            for (seat, bid) in [1, 0, 1].into_iter().enumerate() {
+                state.place_bid(PlayerId::new(seat), bid).unwrap();
+            }
+            for seat in 0..3 {
+                let id = PlayerId::new(seat);
+                state.play_card(id, deal[&id][0]).unwrap();
+            }
+        }
+        state.get_hands().to_vec()
+    }
 
-        assert_eq!(played, led_card);
-        assert_eq!(new_hand.len(), 1);
-        assert!(new_hand.contains(&other_card));
+    #[test]
+    fn ai_player_gives_away_a_trick_once_opponents_are_known_to_overbid() {
+        // Seat 1 has made its bid of nothing, so losing the ten of clubs to it breaks
+        // the bid, and the two top trumps still make the AI's bid of two
+        let (_, _, player) = setup();
+        let cards = [card("AH"), card("KH"), card("5S")];
+        let view = GameView::new(PlayerId::new(0), 3, card("2H"), 3)
+            .dealer(PlayerId::new(0))
+            .bids(vec![
+                (PlayerId::new(1), 0),
+                (PlayerId::new(2), 1),
+                (PlayerId::new(0), 2),
+            ])
+            .current_trick(vec![
+                (PlayerId::new(1), card("10C")),
+                (PlayerId::new(2), card("4C")),
+            ])
+            .played_cards([card("10C"), card("4C")].iter().collect());
+
+        // Seat 2 is expected to take the trick it bid, leaving too few to give one away
+        assert_eq!(player.play_card(&view, &cards), card("KH"));
+
+        // Seat 2 has always taken fewer tricks than it bid
+        let view = view.history(overbidding_history(), HashMap::new());
+        assert_eq!(player.play_card(&view, &cards), card("5S"));
     }
 
     #[test]
-    fn ai_player_bids_one_for_each_card_in_trump() {
+    fn beginner_player_bids_high_cards_and_plays_legal_cards() {
+        let player = BeginnerPlayer::new("Tester".to_string());
+        let view = setup_view(card("2H"), Some(card("10C")), 1);
+        let cards = [card("AS"), card("QH"), card("JH"), card("4C"), card("9C")];
+
+        assert_eq!(player.get_player_bid(&view, &cards), 2);
+        let played = player.play_card(&view, &cards);
+        assert!(played == card("4C") || played == card("9C"));
+    }
+
+    #[test]
+    fn ai_player_bids_the_strength_of_its_hand() {
         let (trump_card, _, player) = setup();
-        let in_trump_play = Card::new(Rank::Four, trump_card.suit());
-        let other_card = Card::new(Rank::Jack, trump_card.suit());
-        let player_hand = vec![in_trump_play, other_card];
+        let strong_hand = vec![
+            Card::new(Rank::King, trump_card.suit()),
+            Card::new(Rank::Ace, Suit::Spades),
+        ];
+        let weak_hand = vec![
+            Card::new(Rank::Two, Suit::Spades),
+            Card::new(Rank::Three, Suit::Diamonds),
+        ];
+        let view = setup_view(trump_card, None, 0);
+
+        assert_eq!(player.get_player_bid(&view, &strong_hand), 2);
+        assert!(player.get_player_bid(&view, &weak_hand) < 2);
+    }
 
-        let bid = player.get_player_bid(&trump_card, &2, &player_hand);
+    #[test]
+    fn player_id_stores_seat() {
+        let id = PlayerId::new(3);
 
-        assert_eq!(bid, 2);
+        assert_eq!(id.seat(), 3);
+        assert_ne!(id, PlayerId::new(2));
     }
 
     #[test]
     fn ai_player_stores_name() {
         let player_name = "Tester";
-        let player = AIPlayer {
-            name: player_name.to_string(),
-        };
+        let player = AIPlayer::new(player_name.to_string());
 
         assert_eq!(player.get_name(), player_name);
     }
@@ -1,8 +1,15 @@
 //! All Ranks in a French deck of cards.
 
+use std::cmp::Ordering;
 use std::fmt;
+use std::str::FromStr;
+
+use crate::card::{Notation, ParseCardError};
 
 /// Enum of all Ranks in a French deck of cards.
+///
+/// Ranks are ordered with aces high. Use [Rank::get_numerical_rank] to compare
+/// them with aces low.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum Rank {
     Ace,
@@ -46,6 +53,21 @@ impl Rank {
         }
     }
 
+    /// Returns the Rank written in the given [Notation].
+    ///
+    /// Short and Unicode notation use "A", "K", "Q", "J", and the number of the
+    /// other ranks. Verbose notation spells out the court cards.
+    pub fn format(&self, notation: Notation) -> String {
+        match (notation, self) {
+            (Notation::Verbose, rank) => rank.to_string(),
+            (_, Rank::Ace) => "A".to_string(),
+            (_, Rank::King) => "K".to_string(),
+            (_, Rank::Queen) => "Q".to_string(),
+            (_, Rank::Jack) => "J".to_string(),
+            (_, rank) => rank.get_numerical_rank(true).to_string(),
+        }
+    }
+
     /// Returns the numerical representation of an Aces High deck.
     fn _aces_high_mapping(&self) -> i32 {
         match &self {
@@ -105,3 +127,86 @@ impl fmt::Display for Rank {
         }
     }
 }
+
+impl Ord for Rank {
+    fn cmp(&self, other: &Rank) -> Ordering {
+        self.get_numerical_rank(true)
+            .cmp(&other.get_numerical_rank(true))
+    }
+}
+
+impl PartialOrd for Rank {
+    fn partial_cmp(&self, other: &Rank) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Reads a Rank from a letter ("A", "K", "Q", "J", or "T" for ten), a number from 2
+/// to 10, or its name, ignoring case.
+impl FromStr for Rank {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Rank, ParseCardError> {
+        let rank = match s.to_lowercase().as_str() {
+            "a" | "ace" => Rank::Ace,
+            "k" | "king" => Rank::King,
+            "q" | "queen" => Rank::Queen,
+            "j" | "jack" => Rank::Jack,
+            "t" | "ten" => Rank::Ten,
+            "nine" => Rank::Nine,
+            "eight" => Rank::Eight,
+            "seven" => Rank::Seven,
+            "six" => Rank::Six,
+            "five" => Rank::Five,
+            "four" => Rank::Four,
+            "three" => Rank::Three,
+            "two" => Rank::Two,
+            number => {
+                let value: i32 = number
+                    .parse()
+                    .map_err(|_| ParseCardError::Rank(s.to_string()))?;
+                *Rank::VALUES
+                    .iter()
+                    .find(|e| e.get_numerical_rank(true) == value && value <= 10)
+                    .ok_or_else(|| ParseCardError::Rank(s.to_string()))?
+            }
+        };
+
+        Ok(rank)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranks_are_read_from_letters_numbers_and_names() {
+        assert_eq!("q".parse(), Ok(Rank::Queen));
+        assert_eq!("T".parse(), Ok(Rank::Ten));
+        assert_eq!("10".parse(), Ok(Rank::Ten));
+        assert_eq!("King".parse(), Ok(Rank::King));
+        assert_eq!("2".parse(), Ok(Rank::Two));
+        assert_eq!(
+            "1".parse::<Rank>(),
+            Err(ParseCardError::Rank("1".to_string()))
+        );
+        assert!("11".parse::<Rank>().is_err());
+    }
+
+    #[test]
+    fn ranks_are_ordered_aces_high() {
+        assert!(Rank::Ace > Rank::King);
+        assert!(Rank::Two < Rank::Three);
+        assert_eq!(Rank::VALUES.iter().max(), Some(&Rank::Ace));
+    }
+
+    #[test]
+    fn every_rank_is_read_back_from_every_notation() {
+        for rank in Rank::VALUES {
+            for notation in [Notation::Short, Notation::Unicode, Notation::Verbose] {
+                assert_eq!(rank.format(notation).parse(), Ok(rank));
+            }
+        }
+    }
+}
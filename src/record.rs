@@ -0,0 +1,1063 @@
+//! A plain-text record of a game, in the spirit of the PGN notation for chess.
+//!
+//! A record starts with tag pairs for the seed, the [Rules], and the players,
+//! followed by a section for each hand. A hand section starts with tag pairs for
+//! the hand number, the dealer, the trump card, and the cards dealt to each seat,
+//! followed by the bids in the order they were placed, one line per trick with
+//! the cards played and the winner, and the points scored by each seat. Seats are
+//! referred to by number and cards by their short code, such as "AH" or "10D".
+//!
+//! ```text
+//! [Game "Contract Whist"]
+//! [Seed "42"]
+//! [HandSizes "1"]
+//! [MadeBidBonus "10"]
+//! [Seat0 "Mickey Mouse"]
+//! [Seat1 "Minnie Mouse"]
+//!
+//! [Hand "1"]
+//! [Dealer "0"]
+//! [Trump "7D"]
+//! [Deal0 "AH"]
+//! [Deal1 "10C"]
+//! Bids: 0=1 1=0
+//! 1. 0:AH 1:10C -> 0
+//! Scores: 0=11 1=10
+//! ```
+//!
+//! Bids and cards taken back in a casual game are followed by a line such as
+//! `Undo: 0 3`, giving the seat that took back their move and the number of bids
+//! and cards taken back. The game carries on from that point, so a trick may be
+//! continued on a new line with the same number. Likewise, a `Timeout: 1` line
+//! comes before a bid or card made for seat 1 after they ran out of time.
+//!
+//! A [RecordWriter] is fed the [GameEvent]s of a game as they happen, so a record
+//! may be written while the game is played. A [GameRecord] is read back with
+//! [GameRecord::parse] and replayed through a [GameState] with
+//! [GameRecord::replay], which checks the recorded winners and scores against the
+//! ones the engine computes.
+//!
+//! # Examples
+//! ```
+//! use contract_whist::{AIPlayer, ContractWhistGame, GameRecord, Player, Rules};
+//!
+//! let players: Vec<Box<dyn Player>> = vec![
+//!     Box::new(AIPlayer::new("Mickey Mouse".to_string())),
+//!     Box::new(AIPlayer::new("Minnie Mouse".to_string())),
+//! ];
+//! let mut game = ContractWhistGame::new(players, Rules::default().hand_sizes(vec![3, 1])).unwrap();
+//! while game.play_next() {}
+//!
+//! let record = GameRecord::parse(&game.record()).unwrap();
+//! let state = record.replay().unwrap();
+//!
+//! assert_eq!(state.get_totals(), game.get_state().get_totals());
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Write};
+
+use crate::card::{Card, Notation};
+use crate::game::{effective_events, take_back, GameEvent, GameState, HandPhase};
+use crate::hand::{check_deal, Hand, HandError};
+use crate::player::PlayerId;
+use crate::rules::{Rules, RulesError};
+use crate::PlayerHands;
+
+/// Writes the record of a game as it is played, one [GameEvent] at a time.
+///
+/// Decisions taken back in a casual game are written as an `Undo:` line with the
+/// seat and the number of bids and cards taken back, and the game carries on
+/// from there, so a record may be written to a stream that can not be rewound.
+pub struct RecordWriter<W: Write> {
+    out: W,
+    num_players: usize,
+    events: Vec<GameEvent>,
+    line: Line,
+}
+
+/// The line of movetext a [RecordWriter] has left unfinished.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Line {
+    Finished,
+    Bids,
+    Trick,
+}
+
+impl<W: Write> RecordWriter<W> {
+    /// Creates a writer for a game between the named players, indexed by seat, and
+    /// writes the tags for the game.
+    pub fn new(
+        mut out: W,
+        names: &[String],
+        rules: &Rules,
+        seed: u64,
+    ) -> io::Result<RecordWriter<W>> {
+        let hand_sizes: Vec<String> = rules
+            .get_hand_sizes()
+            .iter()
+            .map(|e| e.to_string())
+            .collect();
+
+        write_tag(&mut out, "Game", "Contract Whist")?;
+        write_tag(&mut out, "Seed", &seed.to_string())?;
+        write_tag(&mut out, "HandSizes", &hand_sizes.join(" "))?;
+        write_tag(
+            &mut out,
+            "MadeBidBonus",
+            &rules.get_made_bid_bonus().to_string(),
+        )?;
+        for (seat, name) in names.iter().enumerate() {
+            write_tag(&mut out, &format!("Seat{}", seat), name)?;
+        }
+
+        Ok(RecordWriter {
+            out,
+            num_players: names.len(),
+            events: Vec::new(),
+            line: Line::Finished,
+        })
+    }
+
+    /// Writes an event to the record.
+    ///
+    /// Returns an error if the record can not be written to, or if a deal or the
+    /// scores of a hand leave out a seat.
+    pub fn write_event(&mut self, event: &GameEvent) -> io::Result<()> {
+        match event {
+            GameEvent::HandDealt {
+                dealer,
+                trump,
+                deal,
+            } => {
+                self.finish_line()?;
+                let hand = count_hands(&self.events) + 1;
+
+                writeln!(self.out)?;
+                write_tag(&mut self.out, "Hand", &hand.to_string())?;
+                write_tag(&mut self.out, "Dealer", &dealer.seat().to_string())?;
+                write_tag(&mut self.out, "Trump", &trump.format(Notation::Short))?;
+                for seat in 0..self.num_players {
+                    let cards: Vec<String> = deal
+                        .get(&PlayerId::new(seat))
+                        .ok_or_else(|| missing_seat("cards dealt", seat))?
+                        .iter()
+                        .map(|e| e.format(Notation::Short))
+                        .collect();
+                    write_tag(&mut self.out, &format!("Deal{}", seat), &cards.join(" "))?;
+                }
+            }
+            GameEvent::BidPlaced { player, bid } => {
+                self.start_line(Line::Bids)?;
+                write!(self.out, " {}={}", player.seat(), bid)?;
+            }
+            GameEvent::CardPlayed { player, card } => {
+                self.start_line(Line::Trick)?;
+                write!(
+                    self.out,
+                    " {}:{}",
+                    player.seat(),
+                    card.format(Notation::Short)
+                )?;
+            }
+            GameEvent::TrickWon { winner } => {
+                self.start_line(Line::Trick)?;
+                writeln!(self.out, " -> {}", winner.seat())?;
+                self.line = Line::Finished;
+            }
+            GameEvent::HandScored { scores } => {
+                self.finish_line()?;
+                let scores = (0..self.num_players)
+                    .map(|seat| match scores.get(&PlayerId::new(seat)) {
+                        Some(score) => Ok(format!("{}={}", seat, score)),
+                        None => Err(missing_seat("score", seat)),
+                    })
+                    .collect::<io::Result<Vec<String>>>()?;
+                writeln!(self.out, "Scores: {}", scores.join(" "))?;
+            }
+            GameEvent::Undo { player, moves } => {
+                self.finish_line()?;
+                writeln!(self.out, "Undo: {} {}", player.seat(), moves)?;
+            }
+            GameEvent::TimedOut { player } => {
+                self.finish_line()?;
+                writeln!(self.out, "Timeout: {}", player.seat())?;
+            }
+        }
+
+        match event {
+            GameEvent::Undo { moves, .. } => take_back(&mut self.events, *moves),
+            event => self.events.push(event.clone()),
+        }
+        // The bids are finished once every player has bid
+        let bids = current_hand(&self.events)
+            .iter()
+            .filter(|e| matches!(e, GameEvent::BidPlaced { .. }))
+            .count();
+        if self.line == Line::Bids && bids == self.num_players {
+            self.finish_line()?;
+        }
+
+        self.out.flush()
+    }
+
+    /// Starts a line of bids or of a trick, unless one is already started.
+    fn start_line(&mut self, line: Line) -> io::Result<()> {
+        if self.line == line {
+            return Ok(());
+        }
+        self.finish_line()?;
+
+        match line {
+            Line::Bids => write!(self.out, "Bids:")?,
+            Line::Trick => write!(self.out, "{}.", count_tricks(&self.events) + 1)?,
+            Line::Finished => (),
+        }
+        self.line = line;
+
+        Ok(())
+    }
+
+    /// Ends the line being written, if there is one.
+    fn finish_line(&mut self) -> io::Result<()> {
+        if self.line != Line::Finished {
+            writeln!(self.out)?;
+            self.line = Line::Finished;
+        }
+        Ok(())
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+/// Returns the events of the last hand dealt.
+fn current_hand(events: &[GameEvent]) -> &[GameEvent] {
+    match events
+        .iter()
+        .rposition(|e| matches!(e, GameEvent::HandDealt { .. }))
+    {
+        Some(index) => &events[index..],
+        None => &[],
+    }
+}
+
+/// Returns the number of hands dealt.
+fn count_hands(events: &[GameEvent]) -> usize {
+    events
+        .iter()
+        .filter(|e| matches!(e, GameEvent::HandDealt { .. }))
+        .count()
+}
+
+/// Returns the number of tricks finished in the last hand dealt.
+fn count_tricks(events: &[GameEvent]) -> usize {
+    current_hand(events)
+        .iter()
+        .filter(|e| matches!(e, GameEvent::TrickWon { .. }))
+        .count()
+}
+
+fn write_tag<W: Write>(out: &mut W, key: &str, value: &str) -> io::Result<()> {
+    let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+    writeln!(out, "[{} \"{}\"]", key, value)
+}
+
+/// Errors raised while reading or replaying a [GameRecord].
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecordError {
+    /// A line of the record could not be understood.
+    Parse(usize, String),
+    /// The rules in the record can not be played by the recorded players.
+    Rules(RulesError),
+    /// A bid or card in the numbered hand is not legal in the game.
+    Hand(usize, HandError),
+    /// The recorded result of the numbered hand does not match the one computed
+    /// when replaying it.
+    Mismatch(usize, String),
+}
+
+impl fmt::Display for RecordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordError::Parse(line, message) => write!(f, "line {}: {}", line, message),
+            RecordError::Rules(e) => write!(f, "invalid rules: {}", e),
+            RecordError::Hand(hand, e) => write!(f, "hand {}: {}", hand, e),
+            RecordError::Mismatch(hand, message) => write!(f, "hand {}: {}", hand, message),
+        }
+    }
+}
+
+impl std::error::Error for RecordError {}
+
+/// A trick as recorded, with the cards played in order and the recorded winner.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrickRecord {
+    cards: Vec<(PlayerId, Card)>,
+    winner: Option<PlayerId>,
+}
+
+impl TrickRecord {
+    /// Returns the cards played, in the order they were played.
+    pub fn get_cards_played(&self) -> &[(PlayerId, Card)] {
+        &self.cards
+    }
+
+    /// Returns the recorded winner, or None if the trick was not finished.
+    pub fn get_winner(&self) -> Option<PlayerId> {
+        self.winner
+    }
+}
+
+/// A hand as recorded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HandRecord {
+    dealer: PlayerId,
+    trump: Card,
+    deal: PlayerHands,
+    bids: Vec<(PlayerId, isize)>,
+    tricks: Vec<TrickRecord>,
+    scores: Option<HashMap<PlayerId, isize>>,
+}
+
+impl HandRecord {
+    /// Returns the player who dealt the hand.
+    pub fn get_dealer(&self) -> PlayerId {
+        self.dealer
+    }
+
+    /// Returns the trump card for the hand.
+    pub fn get_trump(&self) -> Card {
+        self.trump
+    }
+
+    /// Returns the cards dealt to a player.
+    pub fn get_deal(&self, player: PlayerId) -> &[Card] {
+        self.deal.get(&player).map_or(&[], |e| e.as_slice())
+    }
+
+    /// Returns the cards dealt to every player.
+    pub fn get_deals(&self) -> &PlayerHands {
+        &self.deal
+    }
+
+    /// Returns the bids, in the order they were placed.
+    pub fn get_bids(&self) -> &[(PlayerId, isize)] {
+        &self.bids
+    }
+
+    /// Returns the tricks, in the order they were played.
+    pub fn get_tricks(&self) -> &[TrickRecord] {
+        &self.tricks
+    }
+
+    /// Returns the recorded points for every player, or None if the hand was not
+    /// finished.
+    pub fn get_scores(&self) -> Option<&HashMap<PlayerId, isize>> {
+        self.scores.as_ref()
+    }
+
+    /// Returns the events of the hand, in the order they happened.
+    fn events(&self) -> Vec<GameEvent> {
+        let mut events = vec![GameEvent::HandDealt {
+            dealer: self.dealer,
+            trump: self.trump,
+            deal: self.deal.clone(),
+        }];
+        for (player, bid) in self.bids.iter() {
+            events.push(GameEvent::BidPlaced {
+                player: *player,
+                bid: *bid,
+            });
+        }
+        for trick in self.tricks.iter() {
+            for (player, card) in trick.cards.iter() {
+                events.push(GameEvent::CardPlayed {
+                    player: *player,
+                    card: *card,
+                });
+            }
+            if let Some(winner) = trick.winner {
+                events.push(GameEvent::TrickWon { winner });
+            }
+        }
+        if let Some(scores) = &self.scores {
+            events.push(GameEvent::HandScored {
+                scores: scores.clone(),
+            });
+        }
+        events
+    }
+
+    /// Checks the recorded result of the hand against the finished [Hand].
+    pub fn check(&self, hand: &Hand) -> Result<(), String> {
+        for (index, (recorded, played)) in self.tricks.iter().zip(hand.get_tricks()).enumerate() {
+            if recorded.winner != Some(played.get_winner()) {
+                return Err(format!(
+                    "trick {} was recorded as won by seat {}, but seat {} won it",
+                    index + 1,
+                    recorded
+                        .winner
+                        .map_or("none".to_string(), |e| e.seat().to_string()),
+                    played.get_winner().seat()
+                ));
+            }
+        }
+
+        let scores = match &self.scores {
+            Some(scores) => scores,
+            None => return Err("the scores were not recorded".to_string()),
+        };
+        let mut seats: Vec<&PlayerId> = hand.get_scores().keys().collect();
+        seats.sort();
+        for id in seats {
+            let computed = hand.get_scores()[id];
+            match scores.get(id) {
+                Some(recorded) if *recorded == computed => (),
+                recorded => {
+                    return Err(format!(
+                        "seat {} was recorded as scoring {}, but scored {}",
+                        id.seat(),
+                        recorded.map_or("nothing".to_string(), |e| e.to_string()),
+                        computed
+                    ))
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The record of a game, as read from text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameRecord {
+    names: Vec<String>,
+    rules: Rules,
+    seed: u64,
+    hands: Vec<HandRecord>,
+}
+
+impl GameRecord {
+    /// Returns the names of the players, indexed by seat.
+    pub fn get_names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Returns the rules the game was played with.
+    pub fn get_rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    /// Returns the seed of the game.
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns the recorded hands, in the order they were played.
+    pub fn get_hands(&self) -> &[HandRecord] {
+        &self.hands
+    }
+
+    /// Writes the record as text.
+    pub fn to_text(&self) -> String {
+        let mut writer = RecordWriter::new(Vec::new(), &self.names, &self.rules, self.seed)
+            .expect("writing to memory can not fail");
+        for event in self.hands.iter().flat_map(|e| e.events()) {
+            writer
+                .write_event(&event)
+                .expect("every seat of a record is dealt and scored");
+        }
+
+        String::from_utf8(writer.into_inner()).expect("records are written as UTF-8")
+    }
+
+    /// Reads a record from text.
+    ///
+    /// Bids and cards taken back by an `Undo:` line are left out of the record.
+    pub fn parse(text: &str) -> Result<GameRecord, RecordError> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty())
+            .peekable();
+
+        let mut seed = None;
+        let mut rules = Rules::default();
+        let mut names = Vec::new();
+        while let Some((number, line)) = lines.next_if(|(_, e)| !e.starts_with("[Hand ")) {
+            let error = |message: &str| RecordError::Parse(number, message.to_string());
+            let (key, value) = parse_tag(line).ok_or_else(|| error("expected a tag"))?;
+
+            match key {
+                "Seed" => seed = Some(value.parse().map_err(|_| error("invalid seed"))?),
+                "HandSizes" => {
+                    let hand_sizes = value
+                        .split_whitespace()
+                        .map(|e| e.parse())
+                        .collect::<Result<Vec<usize>, _>>()
+                        .map_err(|_| error("invalid hand sizes"))?;
+                    rules = rules.hand_sizes(hand_sizes);
+                }
+                "MadeBidBonus" => {
+                    let bonus = value.parse().map_err(|_| error("invalid bonus"))?;
+                    rules = rules.made_bid_bonus(bonus);
+                }
+                key if key.starts_with("Seat") => {
+                    if key[4..].parse() != Ok(names.len()) {
+                        return Err(error("seats must be recorded in order"));
+                    }
+                    names.push(value);
+                }
+                // Unknown tags, such as the name or date of an event, are ignored
+                _ => (),
+            }
+        }
+        let seed = seed.ok_or_else(|| RecordError::Parse(1, "missing Seed tag".to_string()))?;
+        let num_players = names.len();
+
+        // Read the hands as the events of the game
+        let mut events: Vec<GameEvent> = Vec::new();
+        while let Some((number, line)) = lines.next() {
+            let error = |message: &str| RecordError::Parse(number, message.to_string());
+
+            if line.starts_with('[') {
+                let (key, value) = parse_tag(line).ok_or_else(|| error("invalid tag"))?;
+                if key != "Hand" {
+                    return Err(error("tags must come before the bids and tricks of a hand"));
+                }
+                if value.parse() != Ok(count_hands(&events) + 1) {
+                    return Err(error("hands must be recorded in order"));
+                }
+
+                let mut tags = Vec::new();
+                while let Some((number, line)) =
+                    lines.next_if(|(_, e)| e.starts_with('[') && !e.starts_with("[Hand "))
+                {
+                    let tag = parse_tag(line)
+                        .ok_or_else(|| RecordError::Parse(number, "invalid tag".to_string()))?;
+                    tags.push((number, tag));
+                }
+                events.push(parse_hand_tags(number, tags, num_players)?);
+                continue;
+            }
+
+            if count_hands(&events) == 0 {
+                return Err(error("expected a tag"));
+            }
+            let (key, value) = match line.find(' ') {
+                Some(split) => (&line[..split], line[split + 1..].trim()),
+                None => (line, ""),
+            };
+
+            match key {
+                "Bids:" => {
+                    let bids = parse_pairs(value, '=', num_players, |e| e.parse().ok())
+                        .ok_or_else(|| error("invalid bids"))?;
+                    for (player, bid) in bids {
+                        events.push(GameEvent::BidPlaced { player, bid });
+                    }
+                }
+                "Scores:" => {
+                    let scores: HashMap<PlayerId, isize> =
+                        parse_pairs(value, '=', num_players, |e| e.parse().ok())
+                            .ok_or_else(|| error("invalid scores"))?
+                            .into_iter()
+                            .collect();
+                    if scores.len() != num_players {
+                        return Err(error("every seat must be scored"));
+                    }
+                    events.push(GameEvent::HandScored { scores });
+                }
+                "Undo:" => {
+                    let (seat, moves) = match value.split_once(' ') {
+                        Some((seat, moves)) => (parse_seat(seat, num_players), moves.parse().ok()),
+                        None => (None, None),
+                    };
+                    match (seat, moves) {
+                        (Some(_), Some(moves)) => take_back(&mut events, moves),
+                        _ => return Err(error("expected `Undo: <seat> <moves>`")),
+                    }
+                }
+                "Timeout:" => {
+                    let player =
+                        parse_seat(value, num_players).ok_or_else(|| error("invalid seat"))?;
+                    events.push(GameEvent::TimedOut { player });
+                }
+                key if key.ends_with('.') => {
+                    if key[..key.len() - 1].parse() != Ok(count_tricks(&events) + 1) {
+                        return Err(error("tricks must be recorded in order"));
+                    }
+                    let (cards, winner) = match value.split_once("->") {
+                        Some((cards, winner)) => (cards, Some(winner.trim())),
+                        None => (value, None),
+                    };
+                    let cards = parse_pairs(cards, ':', num_players, |e| e.parse().ok())
+                        .ok_or_else(|| error("invalid cards played"))?;
+                    for (player, card) in cards {
+                        events.push(GameEvent::CardPlayed { player, card });
+                    }
+                    if let Some(winner) = winner {
+                        let winner = parse_seat(winner, num_players)
+                            .ok_or_else(|| error("invalid winner"))?;
+                        events.push(GameEvent::TrickWon { winner });
+                    }
+                }
+                _ => return Err(error("unexpected line in hand")),
+            }
+        }
+
+        Ok(GameRecord::from_events(names, rules, seed, &events))
+    }
+
+    /// Replays the record through a [GameState], checking that every deal, bid, and
+    /// card is legal and that the recorded results match the ones computed by the
+    /// engine.
+    ///
+    /// Returns the state of the game after the last recorded event.
+    pub fn replay(&self) -> Result<GameState, RecordError> {
+        let num_players = self.names.len();
+        let mut state = GameState::new(num_players, self.rules.clone(), self.seed)
+            .map_err(RecordError::Rules)?;
+
+        for (index, record) in self.hands.iter().enumerate() {
+            let number = index + 1;
+            let mismatch = |message: &str| RecordError::Mismatch(number, message.to_string());
+
+            let hand = match state.get_phase() {
+                HandPhase::Bidding(hand) if hand.get_bids().is_empty() => hand,
+                _ => return Err(mismatch("the previous hand was not finished")),
+            };
+            check_deal(
+                num_players,
+                index,
+                hand.get_num_tricks(),
+                record.dealer,
+                record.trump,
+                &record.deal,
+            )
+            .map_err(mismatch)?;
+            let hand = Hand::from_deal(
+                num_players,
+                state.get_rules(),
+                record.dealer,
+                record.trump,
+                record.deal.clone(),
+            );
+            state.start_hand(hand);
+
+            for (player, bid) in record.bids.iter() {
+                state
+                    .place_bid(*player, *bid)
+                    .map_err(|e| RecordError::Hand(number, e))?;
+            }
+            for (player, card) in record.tricks.iter().flat_map(|e| e.cards.iter()) {
+                state
+                    .play_card(*player, *card)
+                    .map_err(|e| RecordError::Hand(number, e))?;
+            }
+
+            if let Some(hand) = state.get_hands().get(index) {
+                record.check(hand).map_err(|e| mismatch(&e))?;
+            } else if record.scores.is_some() {
+                return Err(mismatch("the hand was scored before it was finished"));
+            }
+        }
+
+        Ok(state)
+    }
+
+    /// Returns the record of a game between the named players from its events.
+    pub fn from_events(
+        names: Vec<String>,
+        rules: Rules,
+        seed: u64,
+        events: &[GameEvent],
+    ) -> GameRecord {
+        let mut hands: Vec<HandRecord> = Vec::new();
+
+        for event in effective_events(events).iter() {
+            if let GameEvent::HandDealt {
+                dealer,
+                trump,
+                deal,
+            } = event
+            {
+                hands.push(HandRecord {
+                    dealer: *dealer,
+                    trump: *trump,
+                    deal: deal.clone(),
+                    bids: Vec::new(),
+                    tricks: Vec::new(),
+                    scores: None,
+                });
+                continue;
+            }
+
+            let hand = match hands.last_mut() {
+                Some(hand) => hand,
+                None => continue,
+            };
+            match event {
+                GameEvent::BidPlaced { player, bid } => hand.bids.push((*player, *bid)),
+                GameEvent::CardPlayed { player, card } => match hand.tricks.last_mut() {
+                    Some(trick) if trick.winner.is_none() => trick.cards.push((*player, *card)),
+                    _ => hand.tricks.push(TrickRecord {
+                        cards: vec![(*player, *card)],
+                        winner: None,
+                    }),
+                },
+                GameEvent::TrickWon { winner } => {
+                    if let Some(trick) = hand.tricks.last_mut() {
+                        trick.winner = Some(*winner);
+                    }
+                }
+                GameEvent::HandScored { scores } => hand.scores = Some(scores.clone()),
+                GameEvent::HandDealt { .. }
+                | GameEvent::Undo { .. }
+                | GameEvent::TimedOut { .. } => (),
+            }
+        }
+
+        GameRecord {
+            names,
+            rules,
+            seed,
+            hands,
+        }
+    }
+}
+
+/// Reads the tags of the hand starting on line `number` into the event dealing it.
+fn parse_hand_tags(
+    number: usize,
+    tags: Vec<(usize, (&str, String))>,
+    num_players: usize,
+) -> Result<GameEvent, RecordError> {
+    let mut dealer = None;
+    let mut trump = None;
+    let mut deal = PlayerHands::new();
+    for (number, (key, value)) in tags {
+        let error = |message: &str| RecordError::Parse(number, message.to_string());
+        match key {
+            "Dealer" => {
+                dealer =
+                    Some(parse_seat(&value, num_players).ok_or_else(|| error("invalid dealer"))?)
+            }
+            "Trump" => {
+                trump = Some(
+                    value
+                        .parse()
+                        .ok()
+                        .ok_or_else(|| error("invalid trump card"))?,
+                )
+            }
+            key if key.starts_with("Deal") => {
+                let id = parse_seat(&key[4..], num_players).ok_or_else(|| error("invalid seat"))?;
+                let cards = value
+                    .split_whitespace()
+                    .map(|e| e.parse().ok())
+                    .collect::<Option<Vec<Card>>>()
+                    .ok_or_else(|| error("invalid card"))?;
+                deal.insert(id, cards);
+            }
+            _ => (),
+        }
+    }
+
+    let missing = |tag: &str| RecordError::Parse(number, format!("missing {} tag", tag));
+    if let Some(seat) = (0..num_players).find(|e| !deal.contains_key(&PlayerId::new(*e))) {
+        return Err(missing(&format!("Deal{}", seat)));
+    }
+    Ok(GameEvent::HandDealt {
+        dealer: dealer.ok_or_else(|| missing("Dealer"))?,
+        trump: trump.ok_or_else(|| missing("Trump"))?,
+        deal,
+    })
+}
+
+/// Reads a tag pair, such as `[Seed "42"]`, into its key and unescaped value.
+fn parse_tag(line: &str) -> Option<(&str, String)> {
+    let line = line.strip_prefix('[')?.strip_suffix(']')?;
+    let split = line.find(' ')?;
+    let value = line[split + 1..]
+        .trim()
+        .strip_prefix('"')?
+        .strip_suffix('"')?;
+
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.push(chars.next()?),
+            c => unescaped.push(c),
+        }
+    }
+
+    Some((&line[..split], unescaped))
+}
+
+/// Reads a list of seats paired with values, such as `0=1 1=0`.
+fn parse_pairs<T>(
+    text: &str,
+    separator: char,
+    num_players: usize,
+    parse: impl Fn(&str) -> Option<T>,
+) -> Option<Vec<(PlayerId, T)>> {
+    text.split_whitespace()
+        .map(|pair| {
+            let split = pair.find(separator)?;
+            let seat = parse_seat(&pair[..split], num_players)?;
+            Some((seat, parse(&pair[split + 1..])?))
+        })
+        .collect()
+}
+
+/// Returns the error for an event missing the `what` of a seat, which can not be
+/// written to a record.
+fn missing_seat(what: &str, seat: usize) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("no {} for seat {}", what, seat),
+    )
+}
+
+/// Reads a seat number, checking it belongs to one of the players.
+fn parse_seat(seat: &str, num_players: usize) -> Option<PlayerId> {
+    match seat.parse() {
+        Ok(seat) if seat < num_players => Some(PlayerId::new(seat)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::ContractWhistGame;
+    use crate::player::{AIPlayer, Player};
+    use std::sync::{Arc, Mutex};
+
+    /// Collects everything written to it, so it may be read after being given away.
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn setup_game() -> ContractWhistGame {
+        let players: Vec<Box<dyn Player>> = vec![
+            Box::new(AIPlayer::new("Mickey \"The Mouse\"".to_string())),
+            Box::new(AIPlayer::new("Minnie Mouse".to_string())),
+            Box::new(AIPlayer::new("Donald Duck".to_string())),
+        ];
+        let rules = Rules::default().hand_sizes(vec![3, 5, 1]);
+
+        ContractWhistGame::with_seed(players, rules, 42).unwrap()
+    }
+
+    fn setup_record() -> String {
+        let mut game = setup_game();
+        while game.play_next() {}
+        game.record()
+    }
+
+    #[test]
+    fn round_trip_reproduces_scores() {
+        let mut game = setup_game();
+        while game.play_next() {}
+
+        let record = GameRecord::parse(&game.record()).unwrap();
+        let state = record.replay().unwrap();
+
+        assert!(state.is_finished());
+        assert_eq!(state.get_totals(), game.get_state().get_totals());
+        assert_eq!(record.get_names()[0], "Mickey \"The Mouse\"");
+        assert_eq!(record.to_text(), game.record());
+    }
+
+    #[test]
+    fn record_is_written_as_the_game_is_played() {
+        let buffer = SharedBuffer::default();
+        let mut game = setup_game();
+        game.play_next();
+        game.record_to(buffer.clone()).unwrap();
+        while game.play_next() {}
+
+        let written = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+
+        assert_eq!(written, game.record());
+    }
+
+    #[test]
+    fn take_backs_are_written_and_read_back() {
+        let mut game = setup_game().casual(true);
+        game.play_next();
+        let mut state = game.get_state().clone();
+        let buffer = SharedBuffer::default();
+        let names: Vec<String> = ["Mickey", "Minnie", "Donald"]
+            .iter()
+            .map(|e| e.to_string())
+            .collect();
+        let mut writer =
+            RecordWriter::new(buffer.clone(), &names, state.get_rules(), state.get_seed()).unwrap();
+
+        let dealer_bid = state.view(PlayerId::new(0)).unwrap().get_bids()[0];
+
+        // Seat 1 bids, seat 2 bids, then seat 1 takes back both bids
+        let earlier = state.clone();
+        state.place_bid(PlayerId::new(1), 0).unwrap();
+        state.place_bid(PlayerId::new(2), 0).unwrap();
+        state.rewind(earlier, PlayerId::new(1));
+        state.place_bid(PlayerId::new(1), 1).unwrap();
+        for event in state.get_events() {
+            writer.write_event(event).unwrap();
+        }
+
+        let written = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let record = GameRecord::parse(&written).unwrap();
+
+        assert!(written.contains("Undo: 1 2"));
+        assert_eq!(
+            record.get_hands()[0].get_bids(),
+            &[dealer_bid, (PlayerId::new(1), 1)][..]
+        );
+    }
+
+    #[test]
+    fn timeouts_are_written_and_read_back() {
+        let mut game = setup_game();
+        game.play_next();
+        let mut state = game.get_state().clone();
+        let buffer = SharedBuffer::default();
+        let names = game.to_record().get_names().to_vec();
+        let mut writer = RecordWriter::new(buffer.clone(), &names, state.get_rules(), 42).unwrap();
+
+        state.time_out(PlayerId::new(1)).unwrap();
+        state.place_bid(PlayerId::new(1), 0).unwrap();
+        state.place_bid(PlayerId::new(2), 1).unwrap();
+        for event in state.get_events() {
+            writer.write_event(event).unwrap();
+        }
+
+        let written = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let record = GameRecord::parse(&written).unwrap();
+
+        assert!(written.contains("Timeout: 1\nBids: 1=0 2=1"));
+        assert_eq!(record.get_hands()[0].get_bids().len(), 3);
+        assert!(record.replay().is_ok());
+    }
+
+    #[test]
+    fn unfinished_game_is_replayed_to_the_same_decision() {
+        let mut game = setup_game();
+        for _ in 0..10 {
+            game.play_next();
+        }
+
+        let state = GameRecord::parse(&game.record()).unwrap().replay().unwrap();
+
+        assert_eq!(
+            state.pending_decision(),
+            game.get_state().pending_decision()
+        );
+        assert_eq!(state.get_events(), game.get_state().get_events());
+    }
+
+    #[test]
+    fn changed_score_is_flagged() {
+        let text = setup_record();
+        let line = text.lines().find(|e| e.starts_with("Scores:")).unwrap();
+        let text = text.replacen(line, "Scores: 0=100 1=0 2=0", 1);
+
+        match GameRecord::parse(&text).unwrap().replay() {
+            Err(RecordError::Mismatch(1, _)) => (),
+            other => panic!("expected a mismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn card_dealt_twice_is_flagged() {
+        let text = setup_record();
+        let deal = |seat: usize| {
+            let prefix = format!("[Deal{} \"", seat);
+            let line = text.lines().find(|e| e.starts_with(&prefix)).unwrap();
+            (
+                line,
+                line[prefix.len()..].split_whitespace().next().unwrap(),
+            )
+        };
+        let (_, card) = deal(0);
+        let (line, replaced) = deal(1);
+        let text = text.replacen(line, &line.replacen(replaced, card, 1), 1);
+
+        match GameRecord::parse(&text).unwrap().replay() {
+            Err(RecordError::Mismatch(1, _)) => (),
+            other => panic!("expected a mismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn illegal_card_is_flagged() {
+        let text = setup_record();
+        // Have the second seat in the trick lead instead of the first
+        let trick = text.lines().find(|e| e.starts_with("1.")).unwrap();
+        let leader: usize = trick[3..4].parse().unwrap();
+        let illegal = format!("1. {}{}", (leader + 1) % 3, &trick[4..]);
+        let text = text.replacen(trick, &illegal, 1);
+
+        assert!(matches!(
+            GameRecord::parse(&text).unwrap().replay(),
+            Err(RecordError::Hand(1, _))
+        ));
+    }
+
+    #[test]
+    fn hand_missing_a_deal_or_score_is_rejected() {
+        let text = setup_record();
+        let deal = text.lines().find(|e| e.starts_with("[Deal1 ")).unwrap();
+        match GameRecord::parse(&text.replacen(&format!("{}\n", deal), "", 1)) {
+            Err(RecordError::Parse(_, message)) => assert_eq!(message, "missing Deal1 tag"),
+            other => panic!("expected a missing deal, got {:?}", other),
+        }
+
+        let scores = text.lines().find(|e| e.starts_with("Scores:")).unwrap();
+        assert!(matches!(
+            GameRecord::parse(&text.replacen(scores, "Scores: 0=1", 1)),
+            Err(RecordError::Parse(_, _))
+        ));
+    }
+
+    #[test]
+    fn event_missing_a_seat_is_not_written() {
+        let names = vec!["Mickey Mouse".to_string(), "Minnie Mouse".to_string()];
+        let mut writer = RecordWriter::new(Vec::new(), &names, &Rules::default(), 1).unwrap();
+        let scores = [(PlayerId::new(0), 1)].into_iter().collect();
+
+        assert!(writer
+            .write_event(&GameEvent::HandScored { scores })
+            .is_err());
+    }
+
+    #[test]
+    fn unknown_tags_are_ignored_and_bad_lines_rejected() {
+        let text = format!("[Date \"2020.01.01\"]\n{}", setup_record());
+        assert!(GameRecord::parse(&text).is_ok());
+
+        let text = format!("{}\nnonsense", setup_record());
+        assert!(matches!(
+            GameRecord::parse(&text),
+            Err(RecordError::Parse(_, _))
+        ));
+    }
+}
@@ -0,0 +1,825 @@
+//! Asynchronous players and games, for players who answer over a socket, from a UI
+//! event loop, or from a long-running search.
+//!
+//! An [AsyncPlayer] returns each bid and card as a future, so a decision may be left
+//! pending until the answer arrives without blocking the thread. An [AsyncGame] is
+//! played like a [crate::game::ContractWhistGame], awaiting each decision in turn.
+//! Any [Player] may take part in an AsyncGame by wrapping it in a [BlockingPlayer],
+//! or in a [ThreadedPlayer] to make its decisions on another thread.
+//!
+//! An AsyncGame may be given [TimeLimits], so a player who takes too long has a
+//! decision made in their place and the game carries on. A [BlockingPlayer] has
+//! always decided by the time its decision is awaited, so is never timed out.
+//!
+//! Games do not need an async runtime to be played. [run_games] drives several games
+//! at once on the current thread, polling each game only when the decision it is
+//! waiting on is ready. An AsyncGame may also be run on any other executor, as the
+//! future returned by [AsyncGame::run] is [Send].
+//!
+//! # Examples
+//! ```
+//! use contract_whist::remote::{run_games, AsyncGame, AsyncPlayer, BlockingPlayer};
+//! use contract_whist::{AIPlayer, Rules};
+//!
+//! let games: Vec<AsyncGame> = (0..3)
+//!     .map(|seed| {
+//!         let players: Vec<Box<dyn AsyncPlayer>> = vec![
+//!             Box::new(BlockingPlayer::new(Box::new(AIPlayer::new("Mickey Mouse".to_string())))),
+//!             Box::new(BlockingPlayer::new(Box::new(AIPlayer::new("Minnie Mouse".to_string())))),
+//!         ];
+//!         AsyncGame::with_seed(players, Rules::default().hand_sizes(vec![3, 2, 1]), seed).unwrap()
+//!     })
+//!     .collect();
+//!
+//! let results = run_games(games);
+//!
+//! assert_eq!(results.len(), 3);
+//! assert!(results.iter().all(|e| e.as_ref().unwrap().get_hands().len() == 3));
+//! ```
+
+use std::future::{self, Future};
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::{random, SeedableRng};
+
+use crate::card::Card;
+use crate::game::{derive_seed, Decision, GameResult, GameState, PLAYER_SEEDS, TIMEOUT_SEEDS};
+use crate::hand::HandError;
+use crate::player::{Player, PlayerId};
+use crate::rules::{Rules, RulesError};
+use crate::timeout::TimeLimits;
+use crate::view::GameView;
+
+/// A decision that an [AsyncPlayer] may still be making.
+pub type PendingDecision<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Trait defining the behavior of a Player whose decisions may take time to arrive.
+///
+/// The [GameView] and cards are handed over with each decision, so they may be sent
+/// on to wherever the decision is made. The same rules apply to the decisions as for
+/// a [Player].
+pub trait AsyncPlayer: Send {
+    /// Returns the name of the Player.
+    fn get_name(&self) -> &String;
+    /// Returns the Player's bid, between 0 and the number of tricks in the hand.
+    fn get_player_bid(&self, view: GameView, cards: Vec<Card>) -> PendingDecision<'_, isize>;
+    /// Returns a card selected from `cards`, following the led suit if possible.
+    fn play_card(&self, view: GameView, cards: Vec<Card>) -> PendingDecision<'_, Card>;
+    /// Seeds any randomness used by the Player when making decisions.
+    ///
+    /// Called by [AsyncGame] before the first hand. Does nothing by default.
+    fn set_seed(&mut self, _seed: u64) {}
+    /// Tells the Player that `player` played `card`, with the Player's view of the
+    /// hand just before it was played.
+    ///
+    /// See [Player::card_played]. Does nothing by default.
+    fn card_played(&mut self, _view: GameView, _player: PlayerId, _card: Card) {}
+}
+
+/// An [AsyncPlayer] that makes the decisions of a [Player] straight away.
+#[derive(Debug, Clone)]
+pub struct BlockingPlayer {
+    player: Box<dyn Player>,
+}
+
+impl BlockingPlayer {
+    /// Creates an AsyncPlayer from a Player.
+    pub fn new(player: Box<dyn Player>) -> BlockingPlayer {
+        BlockingPlayer { player }
+    }
+}
+
+impl From<Box<dyn Player>> for BlockingPlayer {
+    fn from(player: Box<dyn Player>) -> BlockingPlayer {
+        BlockingPlayer::new(player)
+    }
+}
+
+impl AsyncPlayer for BlockingPlayer {
+    fn get_name(&self) -> &String {
+        self.player.get_name()
+    }
+
+    fn get_player_bid(&self, view: GameView, cards: Vec<Card>) -> PendingDecision<'_, isize> {
+        Box::pin(future::ready(self.player.get_player_bid(&view, &cards)))
+    }
+
+    fn play_card(&self, view: GameView, cards: Vec<Card>) -> PendingDecision<'_, Card> {
+        Box::pin(future::ready(self.player.play_card(&view, &cards)))
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.player.set_seed(seed);
+    }
+
+    fn card_played(&mut self, view: GameView, player: PlayerId, card: Card) {
+        self.player.card_played(&view, player, card);
+    }
+}
+
+/// An [AsyncPlayer] that makes the decisions of a [Player] on another thread.
+///
+/// Each decision is made on a new thread, so a slow Player does not block the game
+/// and may be timed out. The Player makes one decision at a time, so a decision that
+/// was timed out is finished before the Player starts on the next one.
+///
+/// The cards played are passed on to the Player on the thread of its next decision,
+/// so the game never waits on a Player still busy with a timed out decision.
+#[derive(Clone)]
+pub struct ThreadedPlayer {
+    name: String,
+    player: Arc<Mutex<Box<dyn Player>>>,
+    played: Arc<Mutex<Vec<(GameView, PlayerId, Card)>>>,
+}
+
+impl ThreadedPlayer {
+    /// Creates an AsyncPlayer from a Player.
+    pub fn new(player: Box<dyn Player>) -> ThreadedPlayer {
+        ThreadedPlayer {
+            name: player.get_name().to_string(),
+            player: Arc::new(Mutex::new(player)),
+            played: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Returns a decision made by the Player on a new thread.
+    fn spawn<T, F>(&self, decide: F) -> PendingDecision<'_, T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&dyn Player) -> T + Send + 'static,
+    {
+        let player = self.player.clone();
+        let played = std::mem::take(&mut *self.played.lock().unwrap());
+        Box::pin(Spawned::new(move || {
+            let mut player = player.lock().unwrap_or_else(|e| e.into_inner());
+            for (view, id, card) in played {
+                player.card_played(&view, id, card);
+            }
+            decide(player.as_ref())
+        }))
+    }
+}
+
+impl AsyncPlayer for ThreadedPlayer {
+    fn get_name(&self) -> &String {
+        &self.name
+    }
+
+    fn get_player_bid(&self, view: GameView, cards: Vec<Card>) -> PendingDecision<'_, isize> {
+        self.spawn(move |player| player.get_player_bid(&view, &cards))
+    }
+
+    fn play_card(&self, view: GameView, cards: Vec<Card>) -> PendingDecision<'_, Card> {
+        self.spawn(move |player| player.play_card(&view, &cards))
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.player
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .set_seed(seed);
+    }
+
+    fn card_played(&mut self, view: GameView, player: PlayerId, card: Card) {
+        self.played.lock().unwrap().push((view, player, card));
+    }
+}
+
+/// The answer to a [Spawned] decision, and the waker to call once it is given.
+type Answer<T> = Arc<Mutex<(Option<thread::Result<T>>, Option<Waker>)>>;
+
+/// A decision being made on another thread.
+///
+/// If the decision panics, the panic is resumed when the decision is awaited.
+struct Spawned<T> {
+    answer: Answer<T>,
+}
+
+impl<T: Send + 'static> Spawned<T> {
+    fn new<F: FnOnce() -> T + Send + 'static>(decide: F) -> Spawned<T> {
+        let answer: Answer<T> = Arc::new(Mutex::new((None, None)));
+        let given = answer.clone();
+
+        thread::spawn(move || {
+            let decision = panic::catch_unwind(AssertUnwindSafe(decide));
+            let mut given = given.lock().unwrap();
+            given.0 = Some(decision);
+            if let Some(waker) = given.1.take() {
+                waker.wake();
+            }
+        });
+
+        Spawned { answer }
+    }
+}
+
+impl<T> Future for Spawned<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut answer = self.answer.lock().unwrap();
+        match answer.0.take() {
+            Some(Ok(decision)) => Poll::Ready(decision),
+            Some(Err(e)) => panic::resume_unwind(e),
+            None => {
+                answer.1 = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// A game of Contract Whist between [AsyncPlayer]s.
+///
+/// Unlike a [crate::game::ContractWhistGame], an AsyncGame prints nothing, and a
+/// decision the rules do not allow ends the game with an error rather than a panic.
+pub struct AsyncGame {
+    players: Vec<Box<dyn AsyncPlayer>>,
+    state: GameState,
+    time_limits: TimeLimits,
+    rng: StdRng,
+}
+
+impl AsyncGame {
+    /// Creates a new game with a random seed, checking that the [Rules] can be played
+    /// by the players.
+    pub fn new(players: Vec<Box<dyn AsyncPlayer>>, rules: Rules) -> Result<AsyncGame, RulesError> {
+        AsyncGame::with_seed(players, rules, random())
+    }
+
+    /// Creates a new game with the given seed, checking that the [Rules] can be played
+    /// by the players.
+    ///
+    /// The game is dealt and the players are seeded as for a
+    /// [crate::game::ContractWhistGame] with the same seed.
+    pub fn with_seed(
+        players: Vec<Box<dyn AsyncPlayer>>,
+        rules: Rules,
+        seed: u64,
+    ) -> Result<AsyncGame, RulesError> {
+        let state = GameState::new(players.len(), rules, seed)?;
+
+        AsyncGame::from_state(players, state)
+    }
+
+    /// Creates a game that continues from a [GameState].
+    ///
+    /// Returns an error if the number of players does not match the state.
+    pub fn from_state(
+        mut players: Vec<Box<dyn AsyncPlayer>>,
+        state: GameState,
+    ) -> Result<AsyncGame, RulesError> {
+        if players.len() != state.get_num_players() {
+            return Err(RulesError::PlayerCount(players.len()));
+        }
+
+        for (seat, player) in players.iter_mut().enumerate() {
+            player.set_seed(derive_seed(state.get_seed(), PLAYER_SEEDS, seat as u64));
+        }
+
+        let rng = StdRng::seed_from_u64(derive_seed(state.get_seed(), TIMEOUT_SEEDS, 0));
+
+        Ok(AsyncGame {
+            players,
+            state,
+            time_limits: TimeLimits::default(),
+            rng,
+        })
+    }
+
+    /// Sets the time players have to make each decision.
+    ///
+    /// A player who runs out of time has their decision made for them as set out in
+    /// the [TimeLimits], and the timeout is logged as a
+    /// [crate::game::GameEvent::TimedOut].
+    pub fn time_limits(self, time_limits: TimeLimits) -> AsyncGame {
+        AsyncGame {
+            time_limits,
+            ..self
+        }
+    }
+
+    /// Returns the time players have to make each decision.
+    pub fn get_time_limits(&self) -> &TimeLimits {
+        &self.time_limits
+    }
+
+    /// Returns the players seated at the game.
+    pub fn get_players(&self) -> &[Box<dyn AsyncPlayer>] {
+        &self.players
+    }
+
+    /// Returns the current state of the game.
+    pub fn get_state(&self) -> &GameState {
+        &self.state
+    }
+
+    /// Returns the names of the players, indexed by seat.
+    fn get_names(&self) -> Vec<String> {
+        self.players
+            .iter()
+            .map(|e| e.get_name().to_string())
+            .collect()
+    }
+
+    /// Waits for the player to make the pending decision and applies it to the game.
+    ///
+    /// If the player runs out of time, the decision is made in their place. Returns
+    /// false once the game is finished. If the decision is not allowed by the rules,
+    /// the game is left as it was and the error is returned.
+    pub async fn play_next(&mut self) -> Result<bool, HandError> {
+        let decision = match self.state.pending_decision() {
+            Some(decision) => decision,
+            None => return Ok(false),
+        };
+        let id = decision.get_player();
+        let view = self.view(id);
+        let cards = self.state.get_cards(id).unwrap().to_vec();
+
+        match decision {
+            Decision::Bid(_) => {
+                let pending = self.players[id.seat()].get_player_bid(view.clone(), cards);
+                let bid = match Deadline::new(pending, self.time_limits.get_bid_limit()).await {
+                    Some(bid) => bid,
+                    None => {
+                        self.state.time_out(id)?;
+                        self.time_limits.fallback_bid(&view)
+                    }
+                };
+                self.state.place_bid(id, bid)?;
+            }
+            Decision::Play(_) => {
+                let pending = self.players[id.seat()].play_card(view.clone(), cards.clone());
+                let card = match Deadline::new(pending, self.time_limits.get_play_limit()).await {
+                    Some(card) => card,
+                    None => {
+                        self.state.time_out(id)?;
+                        self.time_limits.fallback_card(&view, &cards, &mut self.rng)
+                    }
+                };
+                let views: Vec<GameView> = (0..self.players.len())
+                    .map(|seat| self.view(PlayerId::new(seat)))
+                    .collect();
+                self.state.play_card(id, card)?;
+
+                for (player, view) in self.players.iter_mut().zip(views) {
+                    player.card_played(view, id, card);
+                }
+            }
+        }
+
+        Ok(!self.state.is_finished())
+    }
+
+    /// Plays every hand of the game and returns the results.
+    pub async fn run(mut self) -> Result<GameResult, HandError> {
+        while self.play_next().await? {}
+
+        let names = self.get_names();
+        Ok(self.state.into_result(names))
+    }
+
+    /// Returns the public information in the game as seen by `player`, including the
+    /// names of the players.
+    fn view(&self, player: PlayerId) -> GameView {
+        self.state
+            .view(player)
+            .expect("the game is not finished")
+            .names(self.get_names())
+    }
+}
+
+/// A decision that is given up on once its time limit has passed.
+///
+/// The time limit starts when the decision is first awaited, and is timed on another
+/// thread that wakes the decision once the time has run out.
+struct Deadline<'a, T> {
+    pending: PendingDecision<'a, T>,
+    limit: Option<Duration>,
+    timer: Option<Timer>,
+}
+
+/// Times a [Deadline], stopping once the Deadline is dropped.
+struct Timer {
+    expired: Arc<AtomicBool>,
+    waker: Arc<Mutex<Option<Waker>>>,
+    _stop: Sender<()>,
+}
+
+impl<'a, T> Deadline<'a, T> {
+    fn new(pending: PendingDecision<'a, T>, limit: Option<Duration>) -> Deadline<'a, T> {
+        Deadline {
+            pending,
+            limit,
+            timer: None,
+        }
+    }
+}
+
+impl Timer {
+    fn start(limit: Duration) -> Timer {
+        let expired = Arc::new(AtomicBool::new(false));
+        let waker: Arc<Mutex<Option<Waker>>> = Arc::new(Mutex::new(None));
+        let (stop, stopped) = mpsc::channel::<()>();
+
+        let (timer_expired, timer_waker) = (expired.clone(), waker.clone());
+        thread::spawn(move || {
+            if let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(limit) {
+                timer_expired.store(true, Ordering::SeqCst);
+                if let Some(waker) = timer_waker.lock().unwrap().take() {
+                    waker.wake();
+                }
+            }
+        });
+
+        Timer {
+            expired,
+            waker,
+            _stop: stop,
+        }
+    }
+}
+
+impl<T> Future for Deadline<'_, T> {
+    type Output = Option<T>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        if let Poll::Ready(decision) = self.pending.as_mut().poll(cx) {
+            return Poll::Ready(Some(decision));
+        }
+        let limit = match self.limit {
+            Some(limit) => limit,
+            None => return Poll::Pending,
+        };
+
+        let timer = self.timer.get_or_insert_with(|| Timer::start(limit));
+        *timer.waker.lock().unwrap() = Some(cx.waker().clone());
+        match timer.expired.load(Ordering::SeqCst) {
+            true => Poll::Ready(None),
+            false => Poll::Pending,
+        }
+    }
+}
+
+/// Plays every game to the end on the current thread and returns the results in the
+/// order the games were given.
+pub fn run_games(games: Vec<AsyncGame>) -> Vec<Result<GameResult, HandError>> {
+    block_on_all(games.into_iter().map(AsyncGame::run).collect())
+}
+
+/// Wakes a future driven by [block_on_all].
+struct TaskWaker {
+    woken: AtomicBool,
+    thread: Thread,
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.woken.store(true, Ordering::SeqCst);
+        self.thread.unpark();
+    }
+}
+
+/// A future driven by [block_on_all], with its output once it is ready.
+struct Task<F: Future> {
+    future: Pin<Box<F>>,
+    waker: Arc<TaskWaker>,
+    output: Option<F::Output>,
+}
+
+/// Drives every future to completion on the current thread and returns their
+/// outputs in the order the futures were given.
+///
+/// A future is only polled again once it has been woken, and the thread sleeps while
+/// every future is waiting.
+pub fn block_on_all<F: Future>(futures: Vec<F>) -> Vec<F::Output> {
+    let thread = thread::current();
+    let mut tasks: Vec<Task<F>> = futures
+        .into_iter()
+        .map(|future| Task {
+            future: Box::pin(future),
+            waker: Arc::new(TaskWaker {
+                woken: AtomicBool::new(true),
+                thread: thread.clone(),
+            }),
+            output: None,
+        })
+        .collect();
+    let mut remaining = tasks.len();
+
+    while remaining > 0 {
+        let mut polled = false;
+
+        for task in tasks.iter_mut().filter(|e| e.output.is_none()) {
+            if !task.waker.woken.swap(false, Ordering::SeqCst) {
+                continue;
+            }
+            polled = true;
+
+            let waker = Waker::from(task.waker.clone());
+            if let Poll::Ready(output) = task.future.as_mut().poll(&mut Context::from_waker(&waker))
+            {
+                task.output = Some(output);
+                remaining -= 1;
+            }
+        }
+
+        if !polled {
+            thread::park();
+        }
+    }
+
+    tasks.into_iter().map(|e| e.output.unwrap()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{ContractWhistGame, GameEvent};
+    use crate::player::AIPlayer;
+    use crate::timeout::CardFallback;
+
+    fn setup_players() -> Vec<Box<dyn Player>> {
+        vec![
+            Box::new(AIPlayer::new("Mickey Mouse".to_string())),
+            Box::new(AIPlayer::new("Minnie Mouse".to_string())),
+            Box::new(AIPlayer::new("Donald Duck".to_string())),
+        ]
+    }
+
+    fn setup_blocking_players() -> Vec<Box<dyn AsyncPlayer>> {
+        setup_players()
+            .into_iter()
+            .map(|e| Box::new(BlockingPlayer::new(e)) as Box<dyn AsyncPlayer>)
+            .collect()
+    }
+
+    fn rules() -> Rules {
+        Rules::default().hand_sizes(vec![4, 3, 2])
+    }
+
+    /// Returns the results of the game played by a [ContractWhistGame].
+    fn play_blocking(seed: u64) -> GameResult {
+        ContractWhistGame::with_seed(setup_players(), rules(), seed)
+            .unwrap()
+            .run()
+    }
+
+    /// Leaves every decision pending for a number of polls before making it.
+    struct Yield<T> {
+        polls: usize,
+        decision: Option<T>,
+    }
+
+    impl<T: Unpin> Future for Yield<T> {
+        type Output = T;
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+            if self.polls == 0 {
+                return Poll::Ready(self.decision.take().unwrap());
+            }
+            self.polls -= 1;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+
+    /// An AI player that takes a few polls to make up its mind.
+    struct YieldingPlayer {
+        ai: AIPlayer,
+        polls: usize,
+    }
+
+    impl AsyncPlayer for YieldingPlayer {
+        fn get_name(&self) -> &String {
+            self.ai.get_name()
+        }
+
+        fn get_player_bid(&self, view: GameView, cards: Vec<Card>) -> PendingDecision<'_, isize> {
+            let decision = Some(self.ai.get_player_bid(&view, &cards));
+            Box::pin(Yield {
+                polls: self.polls,
+                decision,
+            })
+        }
+
+        fn play_card(&self, view: GameView, cards: Vec<Card>) -> PendingDecision<'_, Card> {
+            let decision = Some(self.ai.play_card(&view, &cards));
+            Box::pin(Yield {
+                polls: self.polls,
+                decision,
+            })
+        }
+
+        fn set_seed(&mut self, seed: u64) {
+            self.ai.set_seed(seed);
+        }
+    }
+
+    /// An AI player that thinks for a while before making each decision.
+    #[derive(Clone)]
+    struct SlowPlayer {
+        ai: AIPlayer,
+        delay: Duration,
+    }
+
+    impl Player for SlowPlayer {
+        fn get_name(&self) -> &String {
+            self.ai.get_name()
+        }
+
+        fn play_card(&self, view: &GameView, cards: &[Card]) -> Card {
+            thread::sleep(self.delay);
+            self.ai.play_card(view, cards)
+        }
+
+        fn display_hand(&self, _: &[Card]) {}
+
+        fn get_player_bid(&self, view: &GameView, cards: &[Card]) -> isize {
+            thread::sleep(self.delay);
+            self.ai.get_player_bid(view, cards)
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Player> {
+            Box::new(self.clone())
+        }
+    }
+
+    /// A player who never makes a decision.
+    struct IdlePlayer(String);
+
+    impl AsyncPlayer for IdlePlayer {
+        fn get_name(&self) -> &String {
+            &self.0
+        }
+
+        fn get_player_bid(&self, _: GameView, _: Vec<Card>) -> PendingDecision<'_, isize> {
+            Box::pin(future::pending())
+        }
+
+        fn play_card(&self, _: GameView, _: Vec<Card>) -> PendingDecision<'_, Card> {
+            Box::pin(future::pending())
+        }
+    }
+
+    #[test]
+    fn blocking_players_play_the_same_game_as_a_contract_whist_game() {
+        let game = AsyncGame::with_seed(setup_blocking_players(), rules(), 42).unwrap();
+
+        let result = block_on_all(vec![game.run()]).pop().unwrap().unwrap();
+
+        assert_eq!(result.get_names(), play_blocking(42).get_names());
+        assert_eq!(result.get_totals(), play_blocking(42).get_totals());
+    }
+
+    #[test]
+    fn several_games_are_played_at_once() {
+        let games: Vec<AsyncGame> = (0..4)
+            .map(|seed| {
+                let players: Vec<Box<dyn AsyncPlayer>> = setup_players()
+                    .into_iter()
+                    .enumerate()
+                    .map(|(seat, e)| {
+                        Box::new(YieldingPlayer {
+                            ai: AIPlayer::new(e.get_name().to_string()),
+                            polls: seat,
+                        }) as Box<dyn AsyncPlayer>
+                    })
+                    .collect();
+                AsyncGame::with_seed(players, rules(), seed).unwrap()
+            })
+            .collect();
+
+        let results = run_games(games);
+
+        assert_eq!(results.len(), 4);
+        for (seed, result) in results.into_iter().enumerate() {
+            let result = result.unwrap();
+            assert_eq!(result.get_hands().len(), 3);
+            assert_eq!(result.get_totals(), play_blocking(seed as u64).get_totals());
+        }
+    }
+
+    #[test]
+    fn decisions_may_be_made_on_other_threads() {
+        let players: Vec<Box<dyn AsyncPlayer>> = setup_players()
+            .into_iter()
+            .map(|e| Box::new(ThreadedPlayer::new(e)) as Box<dyn AsyncPlayer>)
+            .collect();
+        let games = vec![
+            AsyncGame::with_seed(players, rules(), 7).unwrap(),
+            AsyncGame::with_seed(setup_blocking_players(), rules(), 8).unwrap(),
+        ];
+
+        let results = run_games(games);
+
+        assert_eq!(
+            results[0].as_ref().unwrap().get_totals(),
+            play_blocking(7).get_totals()
+        );
+        assert_eq!(
+            results[1].as_ref().unwrap().get_totals(),
+            play_blocking(8).get_totals()
+        );
+    }
+
+    #[test]
+    fn idle_player_is_timed_out() {
+        let mut players = setup_blocking_players();
+        players[0] = Box::new(IdlePlayer("Mickey Mouse".to_string()));
+        let limits = TimeLimits::default()
+            .bid_limit(Duration::from_millis(5))
+            .play_limit(Duration::from_millis(5))
+            .card_fallback(CardFallback::LowestCard)
+            .default_bid(1);
+        let mut game = AsyncGame::with_seed(players, rules(), 42)
+            .unwrap()
+            .time_limits(limits);
+
+        block_on_all(vec![async { while game.play_next().await.unwrap() {} }]);
+
+        let events = game.get_state().get_events();
+        let timeouts = events
+            .iter()
+            .filter(|e| matches!(e, GameEvent::TimedOut { .. }))
+            .count();
+        assert_eq!(timeouts, 3 + 4 + 3 + 2);
+        for (timeout, decision) in events.iter().zip(events.iter().skip(1)) {
+            if let GameEvent::TimedOut { player } = timeout {
+                assert_eq!(*player, PlayerId::new(0));
+                match decision {
+                    GameEvent::BidPlaced { bid, .. } => assert_eq!(*bid, 1),
+                    GameEvent::CardPlayed { player, .. } => assert_eq!(*player, PlayerId::new(0)),
+                    event => panic!("{:?} followed a timeout", event),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn slow_player_is_timed_out_and_plays_on() {
+        let slow = SlowPlayer {
+            ai: AIPlayer::new("Mickey Mouse".to_string()),
+            delay: Duration::from_millis(20),
+        };
+        let mut players = setup_blocking_players();
+        players[0] = Box::new(ThreadedPlayer::new(Box::new(slow)));
+        let limits = TimeLimits::default()
+            .bid_limit(Duration::from_millis(1))
+            .play_limit(Duration::from_millis(1));
+        let game = AsyncGame::with_seed(players, Rules::default().hand_sizes(vec![2]), 42)
+            .unwrap()
+            .time_limits(limits);
+
+        let results = run_games(vec![game]);
+
+        assert_eq!(results[0].as_ref().unwrap().get_hands().len(), 1);
+    }
+
+    #[test]
+    fn invalid_decision_ends_the_game_with_an_error() {
+        struct OverBidder(String);
+
+        impl AsyncPlayer for OverBidder {
+            fn get_name(&self) -> &String {
+                &self.0
+            }
+
+            fn get_player_bid(&self, _: GameView, _: Vec<Card>) -> PendingDecision<'_, isize> {
+                Box::pin(future::ready(99))
+            }
+
+            fn play_card(&self, _: GameView, cards: Vec<Card>) -> PendingDecision<'_, Card> {
+                Box::pin(future::ready(cards[0]))
+            }
+        }
+
+        let mut players = setup_blocking_players();
+        players[0] = Box::new(OverBidder("Mickey Mouse".to_string()));
+        let game = AsyncGame::with_seed(players, rules(), 42).unwrap();
+
+        let results = run_games(vec![game]);
+
+        assert_eq!(results[0].as_ref().err(), Some(&HandError::InvalidBid(99)));
+    }
+
+    #[test]
+    fn running_game_is_send() {
+        fn assert_send<T: Send>(_: &T) {}
+
+        let game = AsyncGame::with_seed(setup_blocking_players(), rules(), 42).unwrap();
+        assert_send(&game.run());
+    }
+}
@@ -0,0 +1,462 @@
+//! Stepping through a recorded game, hand by hand and trick by trick.
+//!
+//! A [Replay] is created from a [GameRecord] by playing every recorded hand again
+//! through the [Hand] and [crate::trick::Trick] state machines, so the winner of
+//! each trick and the points for each hand are computed by the engine rather than
+//! read from the record. Any difference between the recorded and recomputed
+//! results is kept as a mismatch on the [ReplayedHand], rather than stopping the
+//! replay.
+//!
+//! The replay has a position, which starts after the bids of the first hand and
+//! is moved forwards and backwards a trick or a hand at a time. At any position,
+//! the replay gives the cards still held by every player, the last trick played,
+//! and the points so far.
+//!
+//! # Examples
+//! ```
+//! use contract_whist::{AIPlayer, ContractWhistGame, GameRecord, Player, PlayerId, Replay, Rules};
+//!
+//! let players: Vec<Box<dyn Player>> = vec![
+//!     Box::new(AIPlayer::new("Mickey Mouse".to_string())),
+//!     Box::new(AIPlayer::new("Minnie Mouse".to_string())),
+//! ];
+//! let mut game = ContractWhistGame::new(players, Rules::default().hand_sizes(vec![2, 1])).unwrap();
+//! while game.play_next() {}
+//!
+//! let mut replay = Replay::new(GameRecord::parse(&game.record()).unwrap()).unwrap();
+//! assert_eq!(replay.get_cards_held(PlayerId::new(0)).len(), 2);
+//!
+//! replay.next_trick();
+//! assert_eq!(replay.get_cards_held(PlayerId::new(0)).len(), 1);
+//! assert!(replay.get_last_trick().is_some());
+//!
+//! while replay.next_trick() {}
+//! assert_eq!(&replay.get_totals(), game.get_state().get_totals());
+//! assert!(replay.get_mismatches().is_empty());
+//! ```
+
+use std::collections::HashMap;
+
+use crate::card::Card;
+use crate::hand::{check_deal, Hand, HandError};
+use crate::player::PlayerId;
+use crate::record::{GameRecord, HandRecord, RecordError};
+use crate::rules::Rules;
+use crate::trick::Trick;
+
+/// A recorded hand after being played again by the engine.
+#[derive(Debug, Clone)]
+pub struct ReplayedHand {
+    number: usize,
+    record: HandRecord,
+    tricks: Vec<Trick>,
+    scores: Option<HashMap<PlayerId, isize>>,
+    mismatch: Option<String>,
+}
+
+impl ReplayedHand {
+    /// Plays the recorded hand numbered `number` with the [Hand] state machine.
+    fn play(
+        number: usize,
+        num_players: usize,
+        rules: &Rules,
+        record: &HandRecord,
+    ) -> Result<ReplayedHand, RecordError> {
+        let mismatch = |message: &str| RecordError::Mismatch(number, message.to_string());
+        let num_tricks = rules
+            .get_hand_sizes()
+            .get(number - 1)
+            .copied()
+            .ok_or_else(|| mismatch("the hand is not in the rules"))?;
+        check_deal(
+            num_players,
+            number - 1,
+            num_tricks,
+            record.get_dealer(),
+            record.get_trump(),
+            record.get_deals(),
+        )
+        .map_err(mismatch)?;
+
+        let illegal = |e: HandError| RecordError::Hand(number, e);
+        let mut replayed = ReplayedHand {
+            number,
+            record: record.clone(),
+            tricks: Vec::new(),
+            scores: None,
+            mismatch: None,
+        };
+
+        let mut hand = Hand::from_deal(
+            num_players,
+            rules,
+            record.get_dealer(),
+            record.get_trump(),
+            record.get_deals().clone(),
+        );
+        for (player, bid) in record.get_bids() {
+            hand.place_bid(*player, *bid).map_err(illegal)?;
+        }
+        let mut hand = match hand.finish_bidding() {
+            Ok(hand) => hand,
+            Err(_) => return Ok(replayed),
+        };
+
+        for trick in record.get_tricks() {
+            for (player, card) in trick.get_cards_played() {
+                hand.play_card(*player, *card).map_err(illegal)?;
+            }
+        }
+        replayed.tricks = hand.get_tricks().to_vec();
+        let hand = match hand.finish_playing() {
+            Ok(hand) => hand.score_hand(),
+            Err(_) => return Ok(replayed),
+        };
+
+        replayed.scores = Some(hand.get_scores().clone());
+        replayed.mismatch = record.check(&hand).err();
+
+        Ok(replayed)
+    }
+
+    /// Returns the number of the hand, starting from 1.
+    pub fn get_number(&self) -> usize {
+        self.number
+    }
+
+    /// Returns the hand as recorded.
+    pub fn get_record(&self) -> &HandRecord {
+        &self.record
+    }
+
+    /// Returns the player who dealt the hand.
+    pub fn get_dealer(&self) -> PlayerId {
+        self.record.get_dealer()
+    }
+
+    /// Returns the trump card for the hand.
+    pub fn get_trump(&self) -> Card {
+        self.record.get_trump()
+    }
+
+    /// Returns the bids, in the order they were placed.
+    pub fn get_bids(&self) -> &[(PlayerId, isize)] {
+        self.record.get_bids()
+    }
+
+    /// Returns the tricks finished in the hand, with the winners computed by the engine.
+    pub fn get_tricks(&self) -> &[Trick] {
+        &self.tricks
+    }
+
+    /// Returns the points for every player computed by the engine, or None if the
+    /// hand was not finished.
+    pub fn get_scores(&self) -> Option<&HashMap<PlayerId, isize>> {
+        self.scores.as_ref()
+    }
+
+    /// Returns how the recorded results differ from the computed ones, if they do.
+    pub fn get_mismatch(&self) -> Option<&str> {
+        self.mismatch.as_deref()
+    }
+}
+
+/// A recorded game with a position that may be moved forwards and backwards.
+#[derive(Debug, Clone)]
+pub struct Replay {
+    names: Vec<String>,
+    hands: Vec<ReplayedHand>,
+    hand: usize,
+    trick: usize,
+}
+
+impl Replay {
+    /// Plays every hand in the record again and starts the replay after the bids
+    /// of the first hand.
+    ///
+    /// Returns an error if the record holds no hands, or a deal, bid, or card that is
+    /// not legal.
+    pub fn new(record: GameRecord) -> Result<Replay, RecordError> {
+        let num_players = record.get_names().len();
+        record
+            .get_rules()
+            .validate(num_players)
+            .map_err(RecordError::Rules)?;
+
+        let hands = record
+            .get_hands()
+            .iter()
+            .enumerate()
+            .map(|(index, e)| ReplayedHand::play(index + 1, num_players, record.get_rules(), e))
+            .collect::<Result<Vec<ReplayedHand>, RecordError>>()?;
+        if hands.is_empty() {
+            return Err(RecordError::Parse(
+                0,
+                "the record holds no hands".to_string(),
+            ));
+        }
+
+        Ok(Replay {
+            names: record.get_names().to_vec(),
+            hands,
+            hand: 0,
+            trick: 0,
+        })
+    }
+
+    /// Returns the names of the players, indexed by seat.
+    pub fn get_names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Returns every replayed hand.
+    pub fn get_hands(&self) -> &[ReplayedHand] {
+        &self.hands
+    }
+
+    /// Returns the hand at the current position.
+    pub fn get_hand(&self) -> &ReplayedHand {
+        &self.hands[self.hand]
+    }
+
+    /// Returns the number of tricks of the current hand played at the current position.
+    pub fn get_tricks_played(&self) -> usize {
+        self.trick
+    }
+
+    /// Returns true if every trick of the current hand has been played at the
+    /// current position.
+    pub fn is_end_of_hand(&self) -> bool {
+        self.trick == self.get_hand().tricks.len()
+    }
+
+    /// Moves forward one trick, or to the start of the next hand. Returns false if
+    /// already at the end of the game.
+    pub fn next_trick(&mut self) -> bool {
+        if !self.is_end_of_hand() {
+            self.trick += 1;
+            true
+        } else {
+            self.next_hand()
+        }
+    }
+
+    /// Moves back one trick, or to the end of the previous hand. Returns false if
+    /// already at the start of the game.
+    pub fn prev_trick(&mut self) -> bool {
+        if self.trick > 0 {
+            self.trick -= 1;
+            true
+        } else if self.hand > 0 {
+            self.hand -= 1;
+            self.trick = self.get_hand().tricks.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Moves to the start of the next hand. Returns false if there are no more hands.
+    pub fn next_hand(&mut self) -> bool {
+        if self.hand + 1 < self.hands.len() {
+            self.hand += 1;
+            self.trick = 0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Moves to the start of the current hand, or to the start of the previous hand
+    /// if already there. Returns false if already at the start of the game.
+    pub fn prev_hand(&mut self) -> bool {
+        if self.trick > 0 {
+            self.trick = 0;
+            true
+        } else if self.hand > 0 {
+            self.hand -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns the cards held by a player at the current position.
+    pub fn get_cards_held(&self, player: PlayerId) -> Vec<Card> {
+        let hand = self.get_hand();
+        let played: Vec<Card> = hand.tricks[..self.trick]
+            .iter()
+            .flat_map(|e| e.get_cards_played().iter())
+            .filter(|(id, _)| *id == player)
+            .map(|(_, card)| *card)
+            .collect();
+
+        hand.record
+            .get_deal(player)
+            .iter()
+            .filter(|e| !played.contains(e))
+            .copied()
+            .collect()
+    }
+
+    /// Returns the last trick played at the current position, if any.
+    pub fn get_last_trick(&self) -> Option<&Trick> {
+        match self.trick {
+            0 => None,
+            trick => self.get_hand().tricks.get(trick - 1),
+        }
+    }
+
+    /// Returns the number of tricks a player has won in the current hand at the
+    /// current position.
+    pub fn get_tricks_won(&self, player: PlayerId) -> isize {
+        self.get_hand().tricks[..self.trick]
+            .iter()
+            .filter(|e| e.get_winner() == player)
+            .count() as isize
+    }
+
+    /// Returns the total points for every player at the current position.
+    ///
+    /// The points for a hand are counted once its last trick has been played.
+    pub fn get_totals(&self) -> HashMap<PlayerId, isize> {
+        let mut totals: HashMap<PlayerId, isize> = (0..self.names.len())
+            .map(|seat| (PlayerId::new(seat), 0))
+            .collect();
+        let counted = match self.is_end_of_hand() {
+            true => self.hand + 1,
+            false => self.hand,
+        };
+
+        for scores in self.hands[..counted]
+            .iter()
+            .filter_map(|e| e.scores.as_ref())
+        {
+            for (id, points) in scores.iter() {
+                *totals.entry(*id).or_insert(0) += points;
+            }
+        }
+        totals
+    }
+
+    /// Returns every hand whose recorded results differ from the computed ones.
+    pub fn get_mismatches(&self) -> Vec<&ReplayedHand> {
+        self.hands.iter().filter(|e| e.mismatch.is_some()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::ContractWhistGame;
+    use crate::player::{AIPlayer, Player};
+
+    fn setup_record() -> String {
+        let players: Vec<Box<dyn Player>> = vec![
+            Box::new(AIPlayer::new("Mickey Mouse".to_string())),
+            Box::new(AIPlayer::new("Minnie Mouse".to_string())),
+            Box::new(AIPlayer::new("Donald Duck".to_string())),
+        ];
+        let rules = Rules::default().hand_sizes(vec![2, 3]);
+        let mut game = ContractWhistGame::with_seed(players, rules, 42).unwrap();
+        while game.play_next() {}
+
+        game.record()
+    }
+
+    fn setup_replay(text: &str) -> Replay {
+        Replay::new(GameRecord::parse(text).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn replay_steps_forwards_and_backwards_through_tricks() {
+        let mut replay = setup_replay(&setup_record());
+
+        assert!(!replay.prev_trick());
+        assert!(replay.next_trick());
+        assert!(replay.next_trick());
+        assert!(replay.is_end_of_hand());
+
+        assert!(replay.next_trick());
+        assert_eq!(replay.get_hand().get_number(), 2);
+        assert_eq!(replay.get_tricks_played(), 0);
+
+        assert!(replay.prev_trick());
+        assert_eq!(replay.get_hand().get_number(), 1);
+        assert_eq!(replay.get_tricks_played(), 2);
+
+        while replay.next_trick() {}
+        assert_eq!(replay.get_tricks_played(), 3);
+        assert!(replay.prev_hand());
+        assert!(replay.prev_hand());
+        assert_eq!(replay.get_hand().get_number(), 1);
+    }
+
+    #[test]
+    fn cards_held_shrink_as_tricks_are_played() {
+        let mut replay = setup_replay(&setup_record());
+        let id = PlayerId::new(1);
+        let first = replay.get_cards_held(id);
+
+        replay.next_trick();
+        let played = replay
+            .get_last_trick()
+            .unwrap()
+            .get_cards_played()
+            .iter()
+            .find(|(e, _)| *e == id)
+            .unwrap()
+            .1;
+
+        assert_eq!(replay.get_cards_held(id).len(), first.len() - 1);
+        assert!(!replay.get_cards_held(id).contains(&played));
+        assert!(first.contains(&played));
+    }
+
+    #[test]
+    fn totals_count_hands_once_finished() {
+        let mut replay = setup_replay(&setup_record());
+        let first = replay.get_hands()[0].get_scores().unwrap().clone();
+
+        assert!(replay.get_totals().values().all(|e| *e == 0));
+        replay.next_trick();
+        replay.next_trick();
+        assert_eq!(replay.get_totals(), first);
+    }
+
+    #[test]
+    fn trump_card_in_a_deal_is_rejected() {
+        let text = setup_record();
+        let trump = text.lines().find(|e| e.starts_with("[Trump ")).unwrap();
+        let trump = trump.split('"').nth(1).unwrap();
+        let deal = text.lines().find(|e| e.starts_with("[Deal0 ")).unwrap();
+        let card = deal
+            .split('"')
+            .nth(1)
+            .unwrap()
+            .split_whitespace()
+            .next()
+            .unwrap();
+        let text = text.replacen(deal, &deal.replacen(card, trump, 1), 1);
+
+        assert!(matches!(
+            Replay::new(GameRecord::parse(&text).unwrap()),
+            Err(RecordError::Mismatch(1, _))
+        ));
+    }
+
+    #[test]
+    fn changed_scores_are_flagged_without_stopping() {
+        let text = setup_record();
+        let line = text.lines().find(|e| e.starts_with("Scores:")).unwrap();
+        let text = text.replacen(line, "Scores: 0=100 1=0 2=0", 1);
+
+        let replay = setup_replay(&text);
+        let mismatches = replay.get_mismatches();
+
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].get_number(), 1);
+        assert!(mismatches[0].get_mismatch().unwrap().contains("100"));
+        assert_eq!(replay.get_hands().len(), 2);
+    }
+}
@@ -0,0 +1,141 @@
+use std::{env, fs, io, process};
+
+use contract_whist::{GameRecord, PlayerId, Replay, MAX_DISPLAY_WIDTH};
+
+const USAGE: &str = "Usage: contract_whist_replay <record file> [--show-hands]";
+
+fn main() {
+    let path = match env::args().skip(1).find(|e| !e.starts_with("--")) {
+        Some(path) => path,
+        None => {
+            println!("{}", USAGE);
+            process::exit(2);
+        }
+    };
+    let mut show_hands = env::args().any(|e| e == "--show-hands");
+
+    let text = fs::read_to_string(&path).unwrap_or_else(|e| {
+        println!("Could not read {}: {}", path, e);
+        process::exit(1);
+    });
+    let mut replay = GameRecord::parse(&text)
+        .and_then(Replay::new)
+        .unwrap_or_else(|e| {
+            println!("Could not replay {}: {}", path, e);
+            process::exit(1);
+        });
+
+    println!();
+    println!("{:^1$}", "Contract Whist Replay", MAX_DISPLAY_WIDTH);
+    println!();
+    for hand in replay.get_mismatches() {
+        println!(
+            "Mismatch in hand {}: {}",
+            hand.get_number(),
+            hand.get_mismatch().unwrap()
+        );
+    }
+
+    loop {
+        display_position(&replay, show_hands);
+
+        println!();
+        println!(
+            "[n]ext trick, [p]revious trick, next [h]and, [b]ack a hand, [s]how hands, [q]uit"
+        );
+        let mut input = String::new();
+        match io::stdin().read_line(&mut input) {
+            Ok(0) => return,
+            Ok(_) => (),
+            Err(_) => println!("Error attempting to read input."),
+        }
+
+        let moved = match input.trim() {
+            "" | "n" => replay.next_trick(),
+            "p" => replay.prev_trick(),
+            "h" => replay.next_hand(),
+            "b" => replay.prev_hand(),
+            "s" => {
+                show_hands = !show_hands;
+                true
+            }
+            "q" => return,
+            other => {
+                println!("{} is not a command!", other);
+                true
+            }
+        };
+        if !moved {
+            println!("There is nowhere further to go!");
+        }
+    }
+}
+
+fn display_position(replay: &Replay, show_hands: bool) {
+    let names = replay.get_names();
+    let hand = replay.get_hand();
+
+    println!();
+    println!(
+        "{:-^1$}",
+        format!(
+            " Hand {} of {}, Trick {} of {} ",
+            hand.get_number(),
+            replay.get_hands().len(),
+            replay.get_tricks_played(),
+            hand.get_record().get_deal(hand.get_dealer()).len()
+        ),
+        MAX_DISPLAY_WIDTH
+    );
+    println!("{} dealt", names[hand.get_dealer().seat()]);
+    println!("Trump is: {}", hand.get_trump());
+
+    println!();
+    println!("Player               Bid  Won");
+    println!("{}", "-".repeat(30));
+    for (id, bid) in hand.get_bids() {
+        println!(
+            "{:<20} {:^3}  {:^3}",
+            names[id.seat()],
+            bid,
+            replay.get_tricks_won(*id)
+        );
+    }
+
+    if let Some(trick) = replay.get_last_trick() {
+        println!();
+        for (id, card) in trick.get_cards_played() {
+            println!("{} played the {}", names[id.seat()], card);
+        }
+        println!("{} is the winner!", names[trick.get_winner().seat()]);
+    }
+
+    if show_hands {
+        println!();
+        for (seat, name) in names.iter().enumerate() {
+            let cards: Vec<String> = replay
+                .get_cards_held(PlayerId::new(seat))
+                .iter()
+                .map(|e| e.to_string())
+                .collect();
+            println!("{}: {}", name, cards.join(", "));
+        }
+    }
+
+    if replay.is_end_of_hand() {
+        if let Some(scores) = hand.get_scores() {
+            let totals = replay.get_totals();
+            println!();
+            println!("     Player         Score  Total");
+            println!("{}", "-".repeat(33));
+            for (seat, name) in names.iter().enumerate() {
+                let id = PlayerId::new(seat);
+                println!("{:<20} {:^5}  {:^5}", name, scores[&id], totals[&id]);
+            }
+        }
+        if let Some(mismatch) = hand.get_mismatch() {
+            println!();
+            println!("Mismatch: {}", mismatch);
+        }
+    }
+}
@@ -0,0 +1,289 @@
+//! Configuration of the rules used to play a game of Contract Whist.
+//!
+//! The Rules determine how many cards are dealt in each [crate::hand::Hand] of
+//! the game, how many points are awarded for making a bid, and which cards may be
+//! played to a trick. `Rules::default()`
+//! gives the standard 13 hand game, playing up from 1 card to 7 and back down
+//! to 1. Rules are configured by chaining methods on a Rules value.
+//!
+//! # Examples
+//! ```
+//! use contract_whist::Rules;
+//!
+//! // A short game of three hands where making a bid is worth 5 points
+//! let rules = Rules::default().hand_sizes(vec![1, 2, 1]).made_bid_bonus(5);
+//! assert_eq!(rules.get_hand_sizes(), &[1, 2, 1]);
+//! assert_eq!(rules.score(2, 2), 7);
+//! assert_eq!(rules.score(2, 0), -7);
+//! ```
+//!
+//! [Rules::legal_cards] is the one place the engine, the players, and any bots
+//! find out which cards may be played.
+//!
+//! ```
+//! use contract_whist::{Card, PlayerId, Rank, Rules, Suit};
+//!
+//! let hand = [Card::new(Rank::Ace, Suit::Spades), Card::new(Rank::Two, Suit::Hearts)];
+//! let trick = [(PlayerId::new(0), Card::new(Rank::King, Suit::Hearts))];
+//!
+//! assert_eq!(Rules::default().legal_cards(&hand, &trick), vec![hand[1]]);
+//! assert_eq!(Rules::default().legal_cards(&hand, &[]), hand.to_vec());
+//! ```
+
+use std::fmt;
+
+use crate::card::Card;
+use crate::card_set::CardSet;
+use crate::hand::HandError;
+use crate::player::PlayerId;
+
+/// The number of cards in a full deck, shared by all hands.
+const DECK_SIZE: usize = 52;
+
+/// The rules in use for a game of Contract Whist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    hand_sizes: Vec<usize>,
+    made_bid_bonus: isize,
+}
+
+/// Errors found when checking the [Rules] against the players of a game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RulesError {
+    /// The game has fewer than [Rules::MIN_PLAYERS] or more than [Rules::MAX_PLAYERS].
+    PlayerCount(usize),
+    /// The game has no hands to play.
+    NoHands,
+    /// A hand has no cards, or more cards than the deck can deal to every player
+    /// while still turning over a trump card.
+    HandSize(usize),
+}
+
+impl fmt::Display for RulesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RulesError::PlayerCount(num) => write!(
+                f,
+                "{} players is not between {} and {}",
+                num,
+                Rules::MIN_PLAYERS,
+                Rules::MAX_PLAYERS
+            ),
+            RulesError::NoHands => write!(f, "The game must have at least one hand"),
+            RulesError::HandSize(num) => {
+                write!(f, "A hand of {} cards can't be dealt to every player", num)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RulesError {}
+
+impl Rules {
+    /// The fewest players a game may have.
+    pub const MIN_PLAYERS: usize = 2;
+    /// The most players a game may have.
+    pub const MAX_PLAYERS: usize = 7;
+    /// Cards dealt in each hand of a standard game.
+    pub const STANDARD_HAND_SIZES: [usize; 13] = [1, 2, 3, 4, 5, 6, 7, 6, 5, 4, 3, 2, 1];
+    /// Cards dealt in each hand of a short game, used for debugging.
+    pub const DEBUG_HAND_SIZES: [usize; 5] = [1, 3, 5, 7, 1];
+
+    /// Rules for a short game of 5 hands, used for debugging.
+    pub fn debug() -> Rules {
+        Rules::default().hand_sizes(Rules::DEBUG_HAND_SIZES.to_vec())
+    }
+
+    /// Sets the number of cards dealt in each hand, in the order the hands are played.
+    pub fn hand_sizes(self, hand_sizes: Vec<usize>) -> Rules {
+        Rules { hand_sizes, ..self }
+    }
+
+    /// Sets the points awarded for making a bid, on top of one point per trick bid.
+    pub fn made_bid_bonus(self, made_bid_bonus: isize) -> Rules {
+        Rules {
+            made_bid_bonus,
+            ..self
+        }
+    }
+
+    /// Returns the number of cards dealt in each hand.
+    pub fn get_hand_sizes(&self) -> &[usize] {
+        &self.hand_sizes
+    }
+
+    /// Returns the points awarded for making a bid.
+    pub fn get_made_bid_bonus(&self) -> isize {
+        self.made_bid_bonus
+    }
+
+    /// Returns the points scored by a player for a [crate::hand::Hand].
+    ///
+    /// Making the bid scores the bonus plus the bid. Missing the bid loses the
+    /// bonus plus the difference between the bid and the tricks won.
+    pub fn score(&self, bid: isize, tricks_won: isize) -> isize {
+        match bid - tricks_won {
+            0 => self.made_bid_bonus + bid,
+            num => -(self.made_bid_bonus + num.abs()),
+        }
+    }
+
+    /// Returns the cards in `cards` that may be played to a trick with `trick` played
+    /// so far, in the order they are held.
+    ///
+    /// A player must follow the led suit if they hold a card in it, and may play any
+    /// card otherwise.
+    pub fn legal_cards(&self, cards: &[Card], trick: &[(PlayerId, Card)]) -> Vec<Card> {
+        let led_suit = match trick.first() {
+            Some((_, led)) => led.suit(),
+            None => return cards.to_vec(),
+        };
+
+        let following: Vec<Card> = cards
+            .iter()
+            .filter(|e| e.suit() == led_suit)
+            .copied()
+            .collect();
+        match following.is_empty() {
+            true => cards.to_vec(),
+            false => following,
+        }
+    }
+
+    /// Returns the cards in `cards` that may be played to a trick with `trick` played
+    /// so far, as a [CardSet].
+    ///
+    /// See [Rules::legal_cards].
+    pub fn legal_set(&self, cards: CardSet, trick: &[(PlayerId, Card)]) -> CardSet {
+        let following = match trick.first() {
+            Some((_, led)) => cards.in_suit(led.suit()),
+            None => return cards,
+        };
+
+        match following.is_empty() {
+            true => cards,
+            false => following,
+        }
+    }
+
+    /// Checks that `card` may be played from `cards` to a trick with `trick` played so
+    /// far.
+    pub fn check_card(
+        &self,
+        cards: CardSet,
+        trick: &[(PlayerId, Card)],
+        card: Card,
+    ) -> Result<(), HandError> {
+        if !cards.contains(card) {
+            return Err(HandError::CardNotHeld(card));
+        }
+        match trick.first() {
+            Some((_, led)) if !self.legal_set(cards, trick).contains(card) => {
+                Err(HandError::MustFollowSuit(led.suit()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that a game with `num_players` players can be played with these Rules.
+    pub fn validate(&self, num_players: usize) -> Result<(), RulesError> {
+        if !(Rules::MIN_PLAYERS..=Rules::MAX_PLAYERS).contains(&num_players) {
+            return Err(RulesError::PlayerCount(num_players));
+        }
+        if self.hand_sizes.is_empty() {
+            return Err(RulesError::NoHands);
+        }
+        match self
+            .hand_sizes
+            .iter()
+            .find(|e| **e == 0 || **e * num_players >= DECK_SIZE)
+        {
+            Some(num) => Err(RulesError::HandSize(*num)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Default for Rules {
+    /// The standard 13 hand game, scoring 10 points plus the bid for making a bid.
+    fn default() -> Rules {
+        Rules {
+            hand_sizes: Rules::STANDARD_HAND_SIZES.to_vec(),
+            made_bid_bonus: 10,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_rules_play_thirteen_hands() {
+        let rules = Rules::default();
+
+        assert_eq!(rules.get_hand_sizes().len(), 13);
+        assert_eq!(rules.get_made_bid_bonus(), 10);
+    }
+
+    #[test]
+    fn making_bid_scores_bonus_plus_bid() {
+        assert_eq!(Rules::default().score(3, 3), 13);
+        assert_eq!(Rules::default().score(0, 0), 10);
+    }
+
+    #[test]
+    fn missing_bid_loses_bonus_plus_difference() {
+        assert_eq!(Rules::default().score(1, 3), -12);
+        assert_eq!(Rules::default().score(4, 2), -12);
+    }
+
+    #[test]
+    fn legal_cards_must_follow_suit_when_able() {
+        use crate::rank::Rank;
+        use crate::suit::Suit;
+
+        let cards = [
+            Card::new(Rank::Ace, Suit::Spades),
+            Card::new(Rank::Two, Suit::Clubs),
+            Card::new(Rank::Nine, Suit::Clubs),
+        ];
+        let trick = [(PlayerId::new(1), Card::new(Rank::Ten, Suit::Clubs))];
+        let held: CardSet = cards.iter().collect();
+        let rules = Rules::default();
+
+        assert_eq!(rules.legal_cards(&cards, &trick), vec![cards[1], cards[2]]);
+        assert_eq!(
+            rules.legal_set(held, &trick).to_vec(),
+            vec![cards[1], cards[2]]
+        );
+        assert_eq!(
+            rules.check_card(held, &trick, cards[0]),
+            Err(HandError::MustFollowSuit(Suit::Clubs))
+        );
+        assert_eq!(rules.check_card(held, &trick, cards[2]), Ok(()));
+
+        let trick = [(PlayerId::new(1), Card::new(Rank::Ten, Suit::Hearts))];
+        assert_eq!(rules.legal_cards(&cards, &trick), cards.to_vec());
+        assert_eq!(
+            rules.check_card(held, &trick, Card::new(Rank::Two, Suit::Hearts)),
+            Err(HandError::CardNotHeld(Card::new(Rank::Two, Suit::Hearts)))
+        );
+    }
+
+    #[test]
+    fn validate_rejects_too_many_players() {
+        assert_eq!(
+            Rules::default().validate(8),
+            Err(RulesError::PlayerCount(8))
+        );
+    }
+
+    #[test]
+    fn validate_rejects_hands_larger_than_the_deck() {
+        let rules = Rules::default().hand_sizes(vec![13]);
+
+        assert_eq!(rules.validate(4), Err(RulesError::HandSize(13)));
+        assert_eq!(rules.validate(3), Ok(()));
+    }
+}
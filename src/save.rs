@@ -0,0 +1,599 @@
+//! Saving a game in progress to a file and resuming it later.
+//!
+//! A [SavedGame] holds the names of the players and the [GameState] of the game.
+//! It is written as plain text, one line per entry, so that a save may be read and
+//! checked by hand. The header holds the seed, the [Rules], the players, and the
+//! [Difficulty] of each AI player, and is followed by a block for each hand played
+//! so far, holding the cards dealt to each player and every bid and card played,
+//! in order. Decisions taken back in a casual game stay in the save, followed by an
+//! `undo` line with the player and the number of bids and cards taken back, and a
+//! `timeout` line comes before a decision made for a player out of time.
+//!
+//! ```text
+//! version 3
+//! seed 42
+//! hand_sizes 1 2
+//! made_bid_bonus 10
+//! player 0 Mickey Mouse
+//! player 1 Minnie Mouse
+//! difficulty 1 Expert
+//! hand 1 dealer 0 trump 7D
+//! deal 0 AH
+//! deal 1 10C
+//! bid 0 1
+//! bid 1 1
+//! undo 1 1
+//! bid 1 0
+//! play 0 AH
+//! ```
+//!
+//! Loading a save checks every deal and replays every bid and card through the
+//! [GameState], so a save that has been edited into an illegal game is rejected
+//! rather than resumed.
+//!
+//! # Examples
+//! ```
+//! use contract_whist::{GameState, PlayerId, Rules, SavedGame};
+//!
+//! let mut state = GameState::new(2, Rules::default().hand_sizes(vec![2, 1]), 42).unwrap();
+//! state.place_bid(PlayerId::new(0), 1).unwrap();
+//!
+//! let names = vec!["Mickey Mouse".to_string(), "Minnie Mouse".to_string()];
+//! let text = SavedGame::new(names, state.clone()).to_text();
+//! let loaded = SavedGame::from_text(&text).unwrap();
+//!
+//! assert_eq!(loaded.get_names()[0], "Mickey Mouse");
+//! assert_eq!(loaded.get_state().pending_decision(), state.pending_decision());
+//! ```
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::card::{Card, Notation};
+use crate::difficulty::Difficulty;
+use crate::game::{take_back, GameEvent, GameState, HandPhase};
+use crate::hand::{check_deal, Hand, HandError};
+use crate::player::PlayerId;
+use crate::rules::{Rules, RulesError};
+use crate::PlayerHands;
+
+/// Version of the save format written by [SavedGame::to_text]. Saves from version 1,
+/// which did not hold the difficulty of the AI players, and version 2, which did not
+/// hold the decisions taken back, are still read.
+const SAVE_VERSION: usize = 3;
+
+/// A game that has been saved, or is about to be.
+#[derive(Debug, Clone)]
+pub struct SavedGame {
+    names: Vec<String>,
+    difficulties: Vec<Option<Difficulty>>,
+    state: GameState,
+}
+
+/// Errors raised while saving or loading a game.
+#[derive(Debug)]
+pub enum SaveError {
+    /// The save could not be read or written.
+    Io(io::Error),
+    /// A line of the save could not be understood.
+    Parse(usize, String),
+    /// The rules in the save can not be played by the saved players.
+    Rules(RulesError),
+    /// A bid or card in the save is not legal in the game.
+    Hand(usize, HandError),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Io(e) => write!(f, "could not access the save: {}", e),
+            SaveError::Parse(line, message) => write!(f, "line {}: {}", line, message),
+            SaveError::Rules(e) => write!(f, "invalid rules: {}", e),
+            SaveError::Hand(line, e) => write!(f, "line {}: {}", line, e),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+impl From<io::Error> for SaveError {
+    fn from(e: io::Error) -> SaveError {
+        SaveError::Io(e)
+    }
+}
+
+impl SavedGame {
+    /// Creates a save of a game between the named players, indexed by seat.
+    pub fn new(names: Vec<String>, state: GameState) -> SavedGame {
+        SavedGame {
+            difficulties: vec![None; names.len()],
+            names,
+            state,
+        }
+    }
+
+    /// Sets the [Difficulty] of the player in each seat, or None for a seat not
+    /// played by one of the AI difficulties.
+    pub fn difficulties(self, difficulties: Vec<Option<Difficulty>>) -> SavedGame {
+        SavedGame {
+            difficulties,
+            ..self
+        }
+    }
+
+    /// Returns the names of the players, indexed by seat.
+    pub fn get_names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Returns the [Difficulty] of the player in each seat, or None for a seat not
+    /// played by one of the AI difficulties.
+    pub fn get_difficulties(&self) -> &[Option<Difficulty>] {
+        &self.difficulties
+    }
+
+    /// Returns the saved state of the game.
+    pub fn get_state(&self) -> &GameState {
+        &self.state
+    }
+
+    /// Returns the names of the players and the state of the game.
+    pub fn into_parts(self) -> (Vec<String>, GameState) {
+        (self.names, self.state)
+    }
+
+    /// Writes the save as text.
+    pub fn to_text(&self) -> String {
+        let state = &self.state;
+        let rules = state.get_rules();
+        let num_players = state.get_num_players();
+        let hand_sizes: Vec<String> = rules
+            .get_hand_sizes()
+            .iter()
+            .map(|e| e.to_string())
+            .collect();
+
+        let mut lines = vec![
+            format!("version {}", SAVE_VERSION),
+            format!("seed {}", state.get_seed()),
+            format!("hand_sizes {}", hand_sizes.join(" ")),
+            format!("made_bid_bonus {}", rules.get_made_bid_bonus()),
+        ];
+        for (seat, name) in self.names.iter().enumerate() {
+            lines.push(format!("player {} {}", seat, name));
+        }
+        for (seat, difficulty) in self.difficulties.iter().enumerate() {
+            if let Some(difficulty) = difficulty {
+                lines.push(format!("difficulty {} {}", seat, difficulty));
+            }
+        }
+
+        // The events as they happened, with the hand numbers as they stood at each
+        let mut effective = Vec::new();
+        for event in state.get_events() {
+            match event {
+                GameEvent::HandDealt {
+                    dealer,
+                    trump,
+                    deal,
+                } => {
+                    let hand = effective
+                        .iter()
+                        .filter(|e| matches!(e, GameEvent::HandScored { .. }))
+                        .count();
+                    lines.push(format!(
+                        "hand {} dealer {} trump {}",
+                        hand + 1,
+                        dealer.seat(),
+                        trump.format(Notation::Short)
+                    ));
+                    for seat in 0..num_players {
+                        let cards: Vec<String> = deal[&PlayerId::new(seat)]
+                            .iter()
+                            .map(|e| e.format(Notation::Short))
+                            .collect();
+                        lines.push(format!("deal {} {}", seat, cards.join(" ")));
+                    }
+                }
+                GameEvent::BidPlaced { player, bid } => {
+                    lines.push(format!("bid {} {}", player.seat(), bid))
+                }
+                GameEvent::CardPlayed { player, card } => lines.push(format!(
+                    "play {} {}",
+                    player.seat(),
+                    card.format(Notation::Short)
+                )),
+                GameEvent::Undo { player, moves } => {
+                    lines.push(format!("undo {} {}", player.seat(), moves))
+                }
+                GameEvent::TimedOut { player } => lines.push(format!("timeout {}", player.seat())),
+                GameEvent::TrickWon { .. } | GameEvent::HandScored { .. } => (),
+            }
+            match event {
+                GameEvent::Undo { moves, .. } => take_back(&mut effective, *moves),
+                event => effective.push(event.clone()),
+            }
+        }
+
+        lines.push(String::new());
+        lines.join("\n")
+    }
+
+    /// Reads a save from text, replaying every bid and card to restore the game.
+    pub fn from_text(text: &str) -> Result<SavedGame, SaveError> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+            .peekable();
+
+        let mut seed = None;
+        let mut rules = Rules::default();
+        let mut names = Vec::new();
+        let mut difficulties = Vec::new();
+        while let Some((number, line)) = lines.next_if(|(_, line)| !line.starts_with("hand ")) {
+            let error = |message: &str| SaveError::Parse(number, message.to_string());
+            let (key, value) = split_key(line);
+
+            match key {
+                "version" => match value.parse() {
+                    Ok(version) if (1..=SAVE_VERSION).contains(&version) => (),
+                    _ => return Err(error("unsupported save version")),
+                },
+                "seed" => seed = Some(value.parse().map_err(|_| error("invalid seed"))?),
+                "hand_sizes" => {
+                    let hand_sizes = value
+                        .split_whitespace()
+                        .map(|e| e.parse())
+                        .collect::<Result<Vec<usize>, _>>()
+                        .map_err(|_| error("invalid hand sizes"))?;
+                    rules = rules.hand_sizes(hand_sizes);
+                }
+                "made_bid_bonus" => {
+                    let bonus = value.parse().map_err(|_| error("invalid bonus"))?;
+                    rules = rules.made_bid_bonus(bonus);
+                }
+                "player" => {
+                    let (seat, name) = split_key(value);
+                    if seat.parse() != Ok(names.len()) || name.is_empty() {
+                        return Err(error("expected `player <seat> <name>` in seat order"));
+                    }
+                    names.push(name.to_string());
+                }
+                "difficulty" => {
+                    let (seat, difficulty) = split_key(value);
+                    let id = parse_seat(seat, &names).ok_or_else(|| error("invalid seat"))?;
+                    let difficulty = difficulty
+                        .parse()
+                        .map_err(|_| error("invalid difficulty"))?;
+                    difficulties.push((id, difficulty));
+                }
+                _ => return Err(error("unexpected line in header")),
+            }
+        }
+
+        let seed = seed.ok_or_else(|| SaveError::Parse(0, "missing seed".to_string()))?;
+        let mut state = GameState::new(names.len(), rules, seed).map_err(SaveError::Rules)?;
+        let mut seats = vec![None; names.len()];
+        for (id, difficulty) in difficulties {
+            seats[id.seat()] = Some(difficulty);
+        }
+
+        while let Some((number, line)) = lines.next() {
+            let error = |message: &str| SaveError::Parse(number, message.to_string());
+            let (key, value) = split_key(line);
+            let values: Vec<&str> = value.split_whitespace().collect();
+
+            match key {
+                "hand" => {
+                    let (dealer, trump) = match values.as_slice() {
+                        [hand, "dealer", dealer, "trump", trump]
+                            if hand.parse() == Ok(state.get_hands().len() + 1) =>
+                        {
+                            (parse_seat(dealer, &names), trump.parse().ok())
+                        }
+                        _ => return Err(error("expected `hand <n> dealer <seat> trump <card>`")),
+                    };
+                    let dealer = dealer.ok_or_else(|| error("invalid dealer"))?;
+                    let trump = trump.ok_or_else(|| error("invalid trump card"))?;
+
+                    let mut deal = PlayerHands::new();
+                    while let Some((number, line)) = lines.next_if(|(_, e)| e.starts_with("deal "))
+                    {
+                        let error = |message: &str| SaveError::Parse(number, message.to_string());
+                        let (seat, cards) = split_key(split_key(line).1);
+                        let id = parse_seat(seat, &names).ok_or_else(|| error("invalid seat"))?;
+                        let cards = cards
+                            .split_whitespace()
+                            .map(|e| e.parse().ok())
+                            .collect::<Option<Vec<Card>>>()
+                            .ok_or_else(|| error("invalid card"))?;
+                        deal.insert(id, cards);
+                    }
+
+                    SavedGame::deal_hand(&mut state, dealer, trump, deal)
+                        .map_err(|message| SaveError::Parse(number, message.to_string()))?;
+                }
+                "undo" => {
+                    let (seat, moves) = match values.as_slice() {
+                        [seat, moves] => (seat, moves),
+                        _ => return Err(error("expected `undo <seat> <moves>`")),
+                    };
+                    let id = parse_seat(seat, &names).ok_or_else(|| error("invalid seat"))?;
+                    let moves = moves.parse().map_err(|_| error("invalid moves"))?;
+                    state.take_back_moves(id, moves).map_err(error)?;
+                }
+                "timeout" => {
+                    let id = parse_seat(value, &names).ok_or_else(|| error("invalid seat"))?;
+                    state.time_out(id).map_err(|e| SaveError::Hand(number, e))?;
+                }
+                "bid" | "play" => {
+                    let (seat, value) = match values.as_slice() {
+                        [seat, value] => (seat, value),
+                        _ => return Err(error("expected `<bid|play> <seat> <value>`")),
+                    };
+                    let id = parse_seat(seat, &names).ok_or_else(|| error("invalid seat"))?;
+                    let result = match key {
+                        "bid" => {
+                            let bid = value.parse().map_err(|_| error("invalid bid"))?;
+                            state.place_bid(id, bid)
+                        }
+                        _ => {
+                            let card = value.parse().ok().ok_or_else(|| error("invalid card"))?;
+                            state.play_card(id, card).map(|_| ())
+                        }
+                    };
+                    result.map_err(|e| SaveError::Hand(number, e))?;
+                }
+                _ => return Err(error("unexpected line in hand")),
+            }
+        }
+
+        Ok(SavedGame::new(names, state).difficulties(seats))
+    }
+
+    /// Deals a hand read from the save in place of the hand dealt from the seed.
+    fn deal_hand(
+        state: &mut GameState,
+        dealer: PlayerId,
+        trump: Card,
+        deal: PlayerHands,
+    ) -> Result<(), &'static str> {
+        let num_tricks = match state.get_phase() {
+            HandPhase::Bidding(hand) if hand.get_bids().is_empty() => hand.get_num_tricks(),
+            _ => return Err("the previous hand is not finished"),
+        };
+        check_deal(
+            state.get_num_players(),
+            state.get_hands().len(),
+            num_tricks,
+            dealer,
+            trump,
+            &deal,
+        )?;
+
+        let hand = Hand::from_deal(
+            state.get_num_players(),
+            state.get_rules(),
+            dealer,
+            trump,
+            deal,
+        );
+        state.start_hand(hand);
+
+        Ok(())
+    }
+
+    /// Writes the save to a file.
+    pub fn write_to<P: AsRef<Path>>(&self, path: P) -> Result<(), SaveError> {
+        fs::write(path, self.to_text())?;
+        Ok(())
+    }
+
+    /// Reads a save from a file.
+    pub fn read_from<P: AsRef<Path>>(path: P) -> Result<SavedGame, SaveError> {
+        SavedGame::from_text(&fs::read_to_string(path)?)
+    }
+}
+
+/// Reads a seat number, checking it belongs to one of the players.
+fn parse_seat(seat: &str, names: &[String]) -> Option<PlayerId> {
+    match seat.parse() {
+        Ok(seat) if seat < names.len() => Some(PlayerId::new(seat)),
+        _ => None,
+    }
+}
+
+/// Splits a line into its first word and the rest of the line.
+fn split_key(line: &str) -> (&str, &str) {
+    match line.find(' ') {
+        Some(split) => (&line[..split], line[split + 1..].trim()),
+        None => (line, ""),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Decision;
+
+    fn setup_names() -> Vec<String> {
+        vec!["Mickey Mouse".to_string(), "Minnie Mouse".to_string()]
+    }
+
+    /// Plays the first `decisions` decisions of a game, always choosing the first card.
+    fn setup_state(decisions: usize) -> GameState {
+        let mut state = GameState::new(2, Rules::default().hand_sizes(vec![2, 3, 1]), 42).unwrap();
+        for _ in 0..decisions {
+            match state.pending_decision() {
+                Some(Decision::Bid(id)) => state.place_bid(id, 1).unwrap(),
+                Some(Decision::Play(id)) => {
+                    let view = state.view(id).unwrap();
+                    let cards = state.get_cards(id).unwrap();
+                    let card = match view.get_led_card() {
+                        Some(led) => cards.in_suit(led.suit()).lowest(),
+                        None => None,
+                    };
+                    let card = card.or_else(|| cards.lowest()).unwrap();
+                    state.play_card(id, card).map(|_| ()).unwrap()
+                }
+                None => break,
+            }
+        }
+        state
+    }
+
+    fn round_trip(state: &GameState) -> GameState {
+        let text = SavedGame::new(setup_names(), state.clone()).to_text();
+        SavedGame::from_text(&text).unwrap().into_parts().1
+    }
+
+    #[test]
+    fn game_is_restored_in_the_middle_of_a_hand() {
+        let state = setup_state(7);
+        let loaded = round_trip(&state);
+
+        assert_eq!(state.get_hands().len(), 1);
+        assert_eq!(loaded.pending_decision(), state.pending_decision());
+        assert_eq!(loaded.get_totals(), state.get_totals());
+        for seat in 0..2 {
+            let id = PlayerId::new(seat);
+            assert_eq!(loaded.get_cards(id), state.get_cards(id));
+            assert_eq!(
+                loaded.view(id).unwrap().get_current_trick(),
+                state.view(id).unwrap().get_current_trick()
+            );
+        }
+    }
+
+    #[test]
+    fn finished_game_is_restored() {
+        let state = setup_state(100);
+        let loaded = round_trip(&state);
+
+        assert!(loaded.is_finished());
+        assert_eq!(loaded.get_totals(), state.get_totals());
+        assert_eq!(
+            loaded.get_hands()[1].get_tricks(),
+            state.get_hands()[1].get_tricks()
+        );
+    }
+
+    #[test]
+    fn decisions_taken_back_are_restored() {
+        // Takes back into the first hand from the second
+        let earlier = setup_state(5);
+        let id = earlier.pending_decision().unwrap().get_player();
+        let mut state = setup_state(8);
+        state.rewind(earlier, id);
+
+        let text = SavedGame::new(setup_names(), state.clone()).to_text();
+        let loaded = SavedGame::from_text(&text).unwrap().into_parts().1;
+
+        assert!(text.contains(&format!("undo {} 3", id.seat())));
+        assert_eq!(loaded.get_events(), state.get_events());
+        assert_eq!(loaded.pending_decision(), state.pending_decision());
+        assert_eq!(loaded.get_hands().len(), state.get_hands().len());
+
+        let text = text.replace(&format!("undo {} 3", id.seat()), "undo 0 50");
+        assert!(matches!(
+            SavedGame::from_text(&text),
+            Err(SaveError::Parse(_, _))
+        ));
+    }
+
+    #[test]
+    fn names_with_spaces_are_restored() {
+        let text = SavedGame::new(setup_names(), setup_state(0)).to_text();
+        let loaded = SavedGame::from_text(&text).unwrap();
+
+        assert_eq!(loaded.get_names(), setup_names().as_slice());
+    }
+
+    #[test]
+    fn difficulties_are_restored() {
+        let difficulties = vec![None, Some(Difficulty::Expert)];
+        let text = SavedGame::new(setup_names(), setup_state(0))
+            .difficulties(difficulties.clone())
+            .to_text();
+        let loaded = SavedGame::from_text(&text).unwrap();
+
+        assert!(text.contains("difficulty 1 Expert"));
+        assert_eq!(loaded.get_difficulties(), difficulties.as_slice());
+
+        let old = text
+            .replace("version 3", "version 1")
+            .replace("difficulty 1 Expert\n", "");
+        let loaded = SavedGame::from_text(&old).unwrap();
+        assert_eq!(loaded.get_difficulties(), [None, None].as_slice());
+    }
+
+    #[test]
+    fn illegal_play_in_save_is_rejected() {
+        let text = SavedGame::new(setup_names(), setup_state(3)).to_text();
+        let play = text.lines().position(|e| e.starts_with("play ")).unwrap();
+        let text = text.replace("play 0", "play 1");
+
+        match SavedGame::from_text(&text) {
+            Err(SaveError::Hand(line, HandError::NotPlayersTurn(_))) => assert_eq!(line, play + 1),
+            other => panic!("expected an illegal play, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn deal_with_a_repeated_card_is_rejected() {
+        let text = SavedGame::new(setup_names(), setup_state(0)).to_text();
+        let deal = |seat: usize| {
+            let prefix = format!("deal {} ", seat);
+            text.lines().find(|e| e.starts_with(&prefix)).unwrap()
+        };
+        let card = deal(0).split_whitespace().nth(2).unwrap();
+        let repeated = format!("{} {}", &deal(1)[..deal(1).rfind(' ').unwrap()], card);
+        let edited = text.replacen(deal(1), &repeated, 1);
+
+        match SavedGame::from_text(&edited) {
+            Err(SaveError::Parse(line, _)) => {
+                assert_eq!(
+                    line,
+                    text.lines().position(|e| e.starts_with("hand ")).unwrap() + 1
+                )
+            }
+            other => panic!("expected a repeated card to be rejected, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn deal_holding_the_trump_card_or_from_the_wrong_dealer_is_rejected() {
+        let text = SavedGame::new(setup_names(), setup_state(0)).to_text();
+        let hand = text.lines().find(|e| e.starts_with("hand ")).unwrap();
+        let trump = hand.split_whitespace().last().unwrap();
+        let deal = text.lines().find(|e| e.starts_with("deal 0 ")).unwrap();
+        let with_trump = format!("{} {}", &deal[..deal.rfind(' ').unwrap()], trump);
+
+        let edited = text.replacen(deal, &with_trump, 1);
+        assert!(matches!(
+            SavedGame::from_text(&edited),
+            Err(SaveError::Parse(_, _))
+        ));
+
+        let edited = text.replacen("dealer 0", "dealer 1", 1);
+        assert!(matches!(
+            SavedGame::from_text(&edited),
+            Err(SaveError::Parse(_, _))
+        ));
+    }
+
+    #[test]
+    fn unreadable_line_is_rejected() {
+        let text = SavedGame::new(setup_names(), setup_state(0)).to_text();
+        let text = text.replace("trump ", "trump XX");
+
+        assert!(matches!(
+            SavedGame::from_text(&text),
+            Err(SaveError::Parse(_, _))
+        ));
+    }
+}
@@ -0,0 +1,490 @@
+//! Simulation of the rest of a hand, for AI players that search ahead.
+//!
+//! A player cannot see the other players' cards, so the search plays out deals
+//! that could be true:
+//! - [sample_hands] deals the unseen cards to the other players, consistent with
+//!   everything the player has seen: their own cards, the turned up trump, the
+//!   cards played, and the suits each player has shown they are out of. Such a
+//!   deal is searched for card by card when dealing at random keeps failing.
+//!   [sample_likely_hands] also keeps each deal in proportion to how likely the
+//!   other players' habits, as learned by an [OpponentModel], make it.
+//! - [rebuild_hand] sets up the hand in the engine with such a deal, with the bids
+//!   and cards played so far, so the rest of it is played by the engine's rules.
+//!   [rebuild_bidding] does the same for a hand still being bid.
+//! - [play_out] plays the rest of the hand with a [Player] in every seat, and
+//!   returns the scored [Hand].
+//!
+//! # Examples
+//! ```
+//! use contract_whist::simulation::{play_out, rebuild_hand, rollout_players, sample_hands};
+//! use contract_whist::{GameState, PlayerId, Rules};
+//! use rand::rngs::StdRng;
+//! use rand::SeedableRng;
+//!
+//! let mut state = GameState::new(3, Rules::default().hand_sizes(vec![7]), 7).unwrap();
+//! for seat in [0, 1, 2] {
+//!     state.place_bid(PlayerId::new(seat), 1).unwrap();
+//! }
+//! let id = PlayerId::new(0);
+//! let view = state.view(id).unwrap();
+//! let cards = state.get_cards(id).unwrap().to_vec();
+//!
+//! let hands = sample_hands(&view, &cards, &mut StdRng::seed_from_u64(1)).unwrap();
+//! let hand = rebuild_hand(&view, &hands).unwrap();
+//! let scored = play_out(hand, &mut rollout_players(3));
+//!
+//! assert_eq!(scored.get_tricks().len(), 7);
+//! ```
+
+use std::collections::HashMap;
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use crate::card::Card;
+use crate::card_set::CardSet;
+use crate::game::derive_seed;
+use crate::hand::{Bidding, Hand, HandError, InProgressHand, Playing};
+use crate::memory::CardMemory;
+use crate::opponents::OpponentModel;
+use crate::player::{AIPlayer, Player, PlayerId};
+use crate::view::GameView;
+
+/// The number of times the unseen cards are shuffled looking for a deal that fits
+/// every void shown, before such a deal is searched for card by card.
+const DEAL_ATTEMPTS: usize = 50;
+
+/// The number of deals drawn looking for one to keep, before the likeliest of them
+/// is taken.
+const WEIGHT_ATTEMPTS: usize = 20;
+
+/// Returns the number of cards `player` still holds in the hand seen by `view`.
+pub fn cards_left(view: &GameView, player: PlayerId) -> usize {
+    let played = view.get_current_trick().iter().any(|(id, _)| *id == player);
+
+    view.get_num_tricks() - view.get_tricks().len() - usize::from(played)
+}
+
+/// Returns a deal of the cards still held by every player, that could be true for
+/// the player seeing `view` and holding `cards`.
+///
+/// The player's own cards are kept, and the unseen cards are dealt at random to the
+/// other players. No player is dealt a card in a suit they have shown they are out
+/// of. Returns None if there is no such deal, which is only the case if the view is
+/// not of a real hand.
+pub fn sample_hands<R: Rng>(
+    view: &GameView,
+    cards: &[Card],
+    rng: &mut R,
+) -> Option<HashMap<PlayerId, CardSet>> {
+    let memory = CardMemory::from_view(view);
+    let held: CardSet = cards.iter().collect();
+    let mut unseen = memory.unseen(held).to_vec();
+
+    // The players who have shown the most voids are dealt to first, as they have
+    // the fewest cards to choose from
+    let mut others: Vec<PlayerId> = (0..view.get_num_players())
+        .map(PlayerId::new)
+        .filter(|e| *e != view.get_player())
+        .collect();
+    others.sort_by_key(|e| std::cmp::Reverse(memory.get_voids(*e).len()));
+
+    let mut hands = HashMap::with_capacity(view.get_num_players());
+    hands.insert(view.get_player(), held);
+
+    for _ in 0..DEAL_ATTEMPTS {
+        unseen.shuffle(rng);
+        let mut dealt = CardSet::new();
+        for player in &others {
+            let hand: CardSet = unseen
+                .iter()
+                .filter(|e| !dealt.contains(**e) && !memory.get_voids(*player).contains(**e))
+                .take(cards_left(view, *player))
+                .collect();
+            dealt = dealt | hand;
+            hands.insert(*player, hand);
+        }
+
+        if others
+            .iter()
+            .all(|e| hands[e].len() == cards_left(view, *e))
+        {
+            return Some(hands);
+        }
+    }
+
+    // Dealing at random keeps failing when the voids leave few ways to deal, so the
+    // cards are dealt one at a time, taking back any that leave no way to go on.
+    // The cards the fewest players may hold are dealt first.
+    let mut deal = Deal {
+        voids: others.iter().map(|e| memory.get_voids(*e)).collect(),
+        room: others.iter().map(|e| cards_left(view, *e)).collect(),
+        hands: vec![CardSet::new(); others.len()],
+    };
+    let undealt = unseen.len().saturating_sub(deal.room.iter().sum());
+    unseen.sort_by_key(|card| deal.voids.iter().filter(|e| !e.contains(*card)).count());
+    if !deal.deal_rest(&unseen, undealt, rng) {
+        return None;
+    }
+
+    hands.extend(others.into_iter().zip(deal.hands));
+    Some(hands)
+}
+
+/// Returns a deal of the cards still held by every player as [sample_hands] does,
+/// keeping each deal drawn in proportion to its [OpponentModel::deal_weight] in
+/// `model`, so deals that go against the other players' habits are dealt less often.
+///
+/// If no deal is kept in a few draws, the likeliest of them is returned.
+pub fn sample_likely_hands<R: Rng>(
+    view: &GameView,
+    cards: &[Card],
+    model: &OpponentModel,
+    rng: &mut R,
+) -> Option<HashMap<PlayerId, CardSet>> {
+    let mut likeliest: Option<(f64, HashMap<PlayerId, CardSet>)> = None;
+    for _ in 0..WEIGHT_ATTEMPTS {
+        let hands = sample_hands(view, cards, rng)?;
+        let weight = model.deal_weight(view, &hands);
+        if rng.gen::<f64>() < weight {
+            return Some(hands);
+        }
+        if likeliest.as_ref().is_none_or(|(e, _)| weight > *e) {
+            likeliest = Some((weight, hands));
+        }
+    }
+    likeliest.map(|(_, hands)| hands)
+}
+
+/// A deal of the unseen cards in progress, to the players indexed alike in each
+/// field.
+struct Deal {
+    voids: Vec<CardSet>,
+    room: Vec<usize>,
+    hands: Vec<CardSet>,
+}
+
+impl Deal {
+    /// Deals `cards` in order to the players with room left who are not out of the
+    /// card's suit, trying the players in a random order, and leaving `undealt` of
+    /// the cards in the deck. Returns false if there is no way to deal them all.
+    fn deal_rest<R: Rng>(&mut self, cards: &[Card], undealt: usize, rng: &mut R) -> bool {
+        let (card, rest) = match cards.split_first() {
+            Some(split) => split,
+            None => return true,
+        };
+        if !self.can_fill(cards) {
+            return false;
+        }
+
+        let mut players: Vec<Option<usize>> = (0..self.hands.len())
+            .filter(|e| self.room[*e] > 0 && !self.voids[*e].contains(*card))
+            .map(Some)
+            .collect();
+        if undealt > 0 {
+            players.push(None);
+        }
+        players.shuffle(rng);
+
+        for player in players {
+            match player {
+                Some(index) => {
+                    self.room[index] -= 1;
+                    self.hands[index].insert(*card);
+                    if self.deal_rest(rest, undealt, rng) {
+                        return true;
+                    }
+                    self.room[index] += 1;
+                    self.hands[index].remove(*card);
+                }
+                None => {
+                    if self.deal_rest(rest, undealt - 1, rng) {
+                        return true;
+                    }
+                }
+            }
+        }
+        false
+    }
+
+    /// Returns false if some player is sure to be left short, as fewer of `cards`
+    /// are in suits they may hold than they have room for.
+    fn can_fill(&self, cards: &[Card]) -> bool {
+        (0..self.hands.len()).all(|index| {
+            let allowed = cards
+                .iter()
+                .filter(|e| !self.voids[index].contains(**e))
+                .count();
+            allowed >= self.room[index]
+        })
+    }
+}
+
+/// Returns the hand seen by `view` set up in the engine with `hands` as the cards
+/// still held, and the bids placed so far.
+///
+/// Every player must hold the cards they were dealt, so this is only used before
+/// any card is played. Returns an error if a bid in the view cannot be placed.
+pub fn rebuild_bidding(
+    view: &GameView,
+    hands: &HashMap<PlayerId, CardSet>,
+) -> Result<InProgressHand<Bidding>, HandError> {
+    rebuild_deal(view, hands, &[])
+}
+
+/// Returns the hand seen by `view` set up in the engine with `hands` as the cards
+/// still held, and the bids and cards played so far.
+///
+/// Returns an error if the hand cannot be played out to the same point with these
+/// cards, such as when a player is given a card already played.
+pub fn rebuild_hand(
+    view: &GameView,
+    hands: &HashMap<PlayerId, CardSet>,
+) -> Result<InProgressHand<Playing>, HandError> {
+    let tricks = view.get_tricks().iter().map(|e| e.get_cards_played());
+    let played: Vec<(PlayerId, Card)> = tricks
+        .chain(std::iter::once(view.get_current_trick()))
+        .flatten()
+        .copied()
+        .collect();
+
+    let hand = rebuild_deal(view, hands, &played)?;
+    let mut hand = match hand.finish_bidding() {
+        Ok(hand) => hand,
+        Err(hand) => return Err(HandError::NotPlayersTurn(hand.next_bidder().unwrap())),
+    };
+    for (id, card) in played {
+        hand.play_card(id, card)?;
+    }
+    Ok(hand)
+}
+
+/// Returns the hand dealt the cards in `hands` and `played`, with the bids in the
+/// view placed.
+fn rebuild_deal(
+    view: &GameView,
+    hands: &HashMap<PlayerId, CardSet>,
+    played: &[(PlayerId, Card)],
+) -> Result<InProgressHand<Bidding>, HandError> {
+    // Every player was dealt the cards they still hold and the cards they played
+    let mut deal: HashMap<PlayerId, Vec<Card>> = hands
+        .iter()
+        .map(|(id, cards)| (*id, cards.to_vec()))
+        .collect();
+    for (id, card) in played {
+        deal.entry(*id).or_default().push(*card);
+    }
+
+    let mut hand = Hand::from_deal(
+        view.get_num_players(),
+        view.get_rules(),
+        view.get_dealer(),
+        view.get_trump(),
+        deal,
+    );
+    for (id, bid) in view.get_bids() {
+        hand.place_bid(*id, *bid)?;
+    }
+    Ok(hand)
+}
+
+/// Returns the random number generator for a decision seen by `view` by a player
+/// holding `held`, seeded with `seed`.
+///
+/// The generator is seeded from the seed, the cards seen, and the bids, each mixed
+/// in with [derive_seed], so the same decision in a game with the same seed is made
+/// the same way on every toolchain.
+pub(crate) fn decision_rng(seed: u64, view: &GameView, held: CardSet) -> StdRng {
+    let mut index = derive_seed(view.get_played_cards().bits(), 0, held.bits());
+    for (id, bid) in view.get_bids() {
+        index = derive_seed(index, id.seat() as u64, *bid as u64);
+    }
+    let hands_played = view.get_previous_hands().len() as u64;
+
+    StdRng::seed_from_u64(derive_seed(seed, hands_played, index))
+}
+
+/// Returns a Player for every seat that plays quickly, to play out simulated hands.
+///
+/// The players never solve the end of the hand exactly, which would be too slow
+/// to do for every simulated hand.
+pub fn rollout_players(num_players: usize) -> Vec<Box<dyn Player>> {
+    (0..num_players)
+        .map(|seat| {
+            let player = AIPlayer::new(format!("Seat {}", seat)).endgame(0);
+            Box::new(player) as Box<dyn Player>
+        })
+        .collect()
+}
+
+/// Plays the rest of the hand with `players`, indexed by seat, and returns the
+/// scored Hand.
+pub fn play_out(hand: InProgressHand<Playing>, players: &mut [Box<dyn Player>]) -> Hand {
+    hand.play_tricks(players).score_hand()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{Decision, GameState};
+    use crate::ismcts::IsmctsPlayer;
+    use crate::monte_carlo::MonteCarloPlayer;
+    use crate::rules::Rules;
+    use crate::suit::Suit;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    /// Returns a game of three players with the first trick started by `leads`.
+    fn setup_state(leads: usize) -> GameState {
+        let mut state = GameState::new(3, Rules::default().hand_sizes(vec![5]), 11).unwrap();
+        for seat in 0..3 {
+            state.place_bid(PlayerId::new(seat), 1).unwrap();
+        }
+        for _ in 0..leads {
+            let id = state.pending_decision().unwrap().get_player();
+            let cards = state.get_cards(id).unwrap();
+            let card = state.view(id).unwrap().legal_cards(&cards.to_vec())[0];
+            state.play_card(id, card).unwrap();
+        }
+        state
+    }
+
+    #[test]
+    fn decision_rng_is_seeded_from_the_game_alone() {
+        let state = setup_state(1);
+        let id = state.pending_decision().unwrap().get_player();
+        let view = state.view(id).unwrap();
+        let held = state.get_cards(id).unwrap();
+
+        // Pinned, so a change to how decisions are seeded is noticed
+        assert_eq!(
+            decision_rng(7, &view, held).gen::<u64>(),
+            5236793587454015846
+        );
+        assert_ne!(
+            decision_rng(7, &view, held).gen::<u64>(),
+            decision_rng(8, &view, held).gen::<u64>()
+        );
+    }
+
+    #[test]
+    fn sampled_hands_keep_own_cards_and_fit_the_cards_left() {
+        let state = setup_state(4);
+        let id = state.pending_decision().unwrap().get_player();
+        let view = state.view(id).unwrap();
+        let cards = state.get_cards(id).unwrap();
+        let mut rng = StdRng::seed_from_u64(3);
+
+        for _ in 0..20 {
+            let hands = sample_hands(&view, &cards.to_vec(), &mut rng).unwrap();
+
+            assert_eq!(hands[&id], cards);
+            for seat in 0..3 {
+                let player = PlayerId::new(seat);
+                assert_eq!(hands[&player].len(), cards_left(&view, player));
+                assert!((hands[&player] & view.get_played_cards()).is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn sampled_hands_respect_voids_shown() {
+        let trick = vec![
+            (PlayerId::new(1), "AC".parse().unwrap()),
+            (PlayerId::new(2), "3D".parse().unwrap()),
+        ];
+        let view = GameView::new(PlayerId::new(0), 3, "2H".parse().unwrap(), 6)
+            .current_trick(trick)
+            .played_cards(
+                ["AC".parse().unwrap(), "3D".parse().unwrap()]
+                    .iter()
+                    .collect(),
+            );
+        let cards: Vec<Card> = ["KC", "4S", "5S", "6S", "7S", "8S"]
+            .iter()
+            .map(|e| e.parse().unwrap())
+            .collect();
+        let mut rng = StdRng::seed_from_u64(5);
+
+        for _ in 0..20 {
+            let hands = sample_hands(&view, &cards, &mut rng).unwrap();
+            assert_eq!(hands[&PlayerId::new(2)].count_suit(Suit::Clubs), 0);
+        }
+    }
+
+    /// Returns a game of five players with hands of ten, played by the rollout
+    /// players up to the decision of seat 1 in the eighth trick. The voids shown by
+    /// then leave few ways to deal the unseen cards.
+    fn setup_voids_state() -> GameState {
+        let mut state = GameState::new(5, Rules::default().hand_sizes(vec![10]), 360).unwrap();
+        let players = rollout_players(5);
+        loop {
+            let decision = state.pending_decision().unwrap();
+            let id = decision.get_player();
+            let view = state.view(id).unwrap();
+            let cards = state.get_cards(id).unwrap().to_vec();
+            match decision {
+                Decision::Bid(_) => {
+                    let bid = players[id.seat()].get_player_bid(&view, &cards);
+                    state.place_bid(id, bid).unwrap();
+                }
+                Decision::Play(_) if id.seat() == 1 && view.get_tricks().len() == 7 => {
+                    return state;
+                }
+                Decision::Play(_) => {
+                    let card = players[id.seat()].play_card(&view, &cards);
+                    state.play_card(id, card).unwrap();
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn sampled_hands_keep_to_voids_with_few_ways_to_deal() {
+        let state = setup_voids_state();
+        let id = PlayerId::new(1);
+        let view = state.view(id).unwrap();
+        let cards = state.get_cards(id).unwrap().to_vec();
+        let memory = CardMemory::from_view(&view);
+
+        for seed in 0..20 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let hands = sample_hands(&view, &cards, &mut rng).unwrap();
+
+            for (player, hand) in hands.iter() {
+                assert!((*hand & memory.get_voids(*player)).is_empty());
+            }
+            assert!(rebuild_hand(&view, &hands).is_ok());
+        }
+
+        let legal = view.legal_cards(&cards);
+        let player = MonteCarloPlayer::new("Tester".to_string()).samples(5);
+        assert!(legal.contains(&player.play_card(&view, &cards)));
+        let player = IsmctsPlayer::new("Tester".to_string()).iterations(50);
+        assert!(legal.contains(&player.play_card(&view, &cards)));
+    }
+
+    #[test]
+    fn rebuilt_hand_matches_the_game() {
+        let state = setup_state(4);
+        let id = state.pending_decision().unwrap().get_player();
+        let view = state.view(id).unwrap();
+        let hands: HashMap<PlayerId, CardSet> = (0..3)
+            .map(|seat| {
+                let player = PlayerId::new(seat);
+                (player, state.get_cards(player).unwrap())
+            })
+            .collect();
+
+        let hand = rebuild_hand(&view, &hands).unwrap();
+
+        assert_eq!(hand.next_player(), Some(id));
+        assert_eq!(hand.get_played_cards(), view.get_played_cards());
+        assert_eq!(hand.view(id).get_current_trick(), view.get_current_trick());
+        assert_eq!(
+            play_out(hand, &mut rollout_players(3)).get_tricks().len(),
+            5
+        );
+    }
+}
@@ -1,9 +1,15 @@
 //! All Suits in a French deck of cards.
 
 use std::fmt;
+use std::str::FromStr;
+
+use crate::card::{Notation, ParseCardError};
 
 /// Enum of all Suits in a French deck of cards
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+///
+/// Suits have no rank in Contract Whist. They are ordered Hearts, Spades, Diamonds,
+/// then Clubs, so that sorted cards are grouped by suit.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Suit {
     Hearts,
     Spades,
@@ -14,6 +20,24 @@ pub enum Suit {
 impl Suit {
     /// All Suit values for easy iteration
     pub const VALUES: [Suit; 4] = [Self::Hearts, Self::Clubs, Self::Diamonds, Self::Spades];
+
+    /// Returns the Suit written in the given [Notation].
+    ///
+    /// Short notation uses the first letter of the Suit, Unicode notation the suit
+    /// symbol, and verbose notation the name of the Suit.
+    pub fn format(&self, notation: Notation) -> String {
+        match (notation, self) {
+            (Notation::Short, Suit::Hearts) => "H".to_string(),
+            (Notation::Short, Suit::Spades) => "S".to_string(),
+            (Notation::Short, Suit::Diamonds) => "D".to_string(),
+            (Notation::Short, Suit::Clubs) => "C".to_string(),
+            (Notation::Unicode, Suit::Hearts) => "\u{2665}".to_string(),
+            (Notation::Unicode, Suit::Spades) => "\u{2660}".to_string(),
+            (Notation::Unicode, Suit::Diamonds) => "\u{2666}".to_string(),
+            (Notation::Unicode, Suit::Clubs) => "\u{2663}".to_string(),
+            (Notation::Verbose, suit) => suit.to_string(),
+        }
+    }
 }
 
 /// Returns a user-friendly string representation of the Suit
@@ -28,6 +52,22 @@ impl fmt::Display for Suit {
     }
 }
 
+/// Reads a Suit from its first letter, its name, or its black or white suit symbol,
+/// ignoring case.
+impl FromStr for Suit {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Suit, ParseCardError> {
+        match s.to_lowercase().as_str() {
+            "h" | "hearts" | "heart" | "\u{2665}" | "\u{2661}" => Ok(Suit::Hearts),
+            "s" | "spades" | "spade" | "\u{2660}" | "\u{2664}" => Ok(Suit::Spades),
+            "d" | "diamonds" | "diamond" | "\u{2666}" | "\u{2662}" => Ok(Suit::Diamonds),
+            "c" | "clubs" | "club" | "\u{2663}" | "\u{2667}" => Ok(Suit::Clubs),
+            _ => Err(ParseCardError::Suit(s.to_string())),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -44,4 +84,21 @@ mod tests {
 
         assert_eq!(expected_names, actual_names);
     }
+
+    #[test]
+    fn suits_are_read_from_letters_names_and_symbols() {
+        assert_eq!("h".parse(), Ok(Suit::Hearts));
+        assert_eq!("Spades".parse(), Ok(Suit::Spades));
+        assert_eq!("\u{2663}".parse(), Ok(Suit::Clubs));
+        assert_eq!("\u{2662}".parse(), Ok(Suit::Diamonds));
+        assert_eq!(
+            "X".parse::<Suit>(),
+            Err(ParseCardError::Suit("X".to_string()))
+        );
+        for suit in Suit::VALUES {
+            for notation in [Notation::Short, Notation::Unicode, Notation::Verbose] {
+                assert_eq!(suit.format(notation).parse(), Ok(suit));
+            }
+        }
+    }
 }
@@ -0,0 +1,197 @@
+//! Time limits on the decisions of players, for bot tournaments and hosted tables
+//! where a slow bot or an idle human must not stall the game.
+//!
+//! [TimeLimits] set how long a player has to bid and to play a card, and what is
+//! done in their place when the time runs out. A late bid is replaced with a
+//! default bid, and a late card with a legal card chosen by the [CardFallback]
+//! policy. Every timeout is logged as a [crate::game::GameEvent::TimedOut] just
+//! before the bid or card made in the player's place.
+//!
+//! Time limits are applied by an [crate::remote::AsyncGame], which can stop waiting
+//! for a decision that is still pending.
+//!
+//! # Examples
+//! ```
+//! use std::time::Duration;
+//!
+//! use contract_whist::timeout::{CardFallback, TimeLimits};
+//!
+//! let limits = TimeLimits::default()
+//!     .bid_limit(Duration::from_secs(30))
+//!     .play_limit(Duration::from_secs(10))
+//!     .card_fallback(CardFallback::LowestCard);
+//!
+//! assert_eq!(limits.get_play_limit(), Some(Duration::from_secs(10)));
+//! assert_eq!(limits.get_default_bid(), 0);
+//! ```
+
+use std::time::Duration;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::card::Card;
+use crate::view::GameView;
+
+/// How a card is chosen for a player who ran out of time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CardFallback {
+    /// Plays a random card that follows the rules.
+    RandomCard,
+    /// Plays the lowest ranking card that follows the rules, with aces high.
+    LowestCard,
+}
+
+/// The time players have to make each decision, and the decisions made for them
+/// once the time runs out.
+///
+/// By default there is no time limit, a random legal card is played for a late
+/// player, and a late bid is 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeLimits {
+    bid_limit: Option<Duration>,
+    play_limit: Option<Duration>,
+    card_fallback: CardFallback,
+    default_bid: isize,
+}
+
+impl Default for TimeLimits {
+    fn default() -> TimeLimits {
+        TimeLimits {
+            bid_limit: None,
+            play_limit: None,
+            card_fallback: CardFallback::RandomCard,
+            default_bid: 0,
+        }
+    }
+}
+
+impl TimeLimits {
+    /// Sets the time a player has to bid.
+    pub fn bid_limit(self, limit: Duration) -> TimeLimits {
+        TimeLimits {
+            bid_limit: Some(limit),
+            ..self
+        }
+    }
+
+    /// Sets the time a player has to play a card.
+    pub fn play_limit(self, limit: Duration) -> TimeLimits {
+        TimeLimits {
+            play_limit: Some(limit),
+            ..self
+        }
+    }
+
+    /// Sets how a card is chosen for a player who ran out of time.
+    pub fn card_fallback(self, card_fallback: CardFallback) -> TimeLimits {
+        TimeLimits {
+            card_fallback,
+            ..self
+        }
+    }
+
+    /// Sets the bid placed for a player who ran out of time.
+    ///
+    /// The bid is lowered to the number of tricks in hands too small for it.
+    pub fn default_bid(self, default_bid: isize) -> TimeLimits {
+        TimeLimits {
+            default_bid: default_bid.max(0),
+            ..self
+        }
+    }
+
+    /// Returns the time a player has to bid, or None if there is no limit.
+    pub fn get_bid_limit(&self) -> Option<Duration> {
+        self.bid_limit
+    }
+
+    /// Returns the time a player has to play a card, or None if there is no limit.
+    pub fn get_play_limit(&self) -> Option<Duration> {
+        self.play_limit
+    }
+
+    /// Returns how a card is chosen for a player who ran out of time.
+    pub fn get_card_fallback(&self) -> CardFallback {
+        self.card_fallback
+    }
+
+    /// Returns the bid placed for a player who ran out of time.
+    pub fn get_default_bid(&self) -> isize {
+        self.default_bid
+    }
+
+    /// Returns the bid to place for a player who ran out of time.
+    pub fn fallback_bid(&self, view: &GameView) -> isize {
+        let num_tricks: isize = view.get_num_tricks().try_into().unwrap();
+        self.default_bid.min(num_tricks)
+    }
+
+    /// Returns the card to play from `cards` for a player who ran out of time.
+    ///
+    /// Panics if `cards` is empty.
+    pub fn fallback_card<R: Rng>(&self, view: &GameView, cards: &[Card], rng: &mut R) -> Card {
+        let legal = view.legal_cards(cards);
+
+        match self.card_fallback {
+            CardFallback::RandomCard => *legal.choose(rng).expect("the player holds a card"),
+            CardFallback::LowestCard => *legal
+                .iter()
+                .min_by_key(|e| e.rank().get_numerical_rank(true))
+                .expect("the player holds a card"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::PlayerId;
+    use crate::rank::Rank;
+    use crate::suit::Suit;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn setup_view() -> GameView {
+        GameView::new(PlayerId::new(1), 2, Card::new(Rank::Two, Suit::Hearts), 3)
+            .current_trick(vec![(PlayerId::new(0), Card::new(Rank::Nine, Suit::Clubs))])
+    }
+
+    fn setup_cards() -> Vec<Card> {
+        vec![
+            Card::new(Rank::Two, Suit::Spades),
+            Card::new(Rank::King, Suit::Clubs),
+            Card::new(Rank::Four, Suit::Clubs),
+        ]
+    }
+
+    #[test]
+    fn lowest_card_follows_suit() {
+        let limits = TimeLimits::default().card_fallback(CardFallback::LowestCard);
+        let mut rng = StdRng::seed_from_u64(1);
+
+        assert_eq!(
+            limits.fallback_card(&setup_view(), &setup_cards(), &mut rng),
+            Card::new(Rank::Four, Suit::Clubs)
+        );
+    }
+
+    #[test]
+    fn random_card_follows_suit() {
+        let limits = TimeLimits::default();
+        let mut rng = StdRng::seed_from_u64(1);
+
+        for _ in 0..20 {
+            let card = limits.fallback_card(&setup_view(), &setup_cards(), &mut rng);
+            assert_eq!(card.suit(), Suit::Clubs);
+        }
+    }
+
+    #[test]
+    fn default_bid_is_limited_to_the_number_of_tricks() {
+        let limits = TimeLimits::default().default_bid(5);
+
+        assert_eq!(limits.fallback_bid(&setup_view()), 3);
+        assert_eq!(TimeLimits::default().fallback_bid(&setup_view()), 0);
+    }
+}
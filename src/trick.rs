@@ -1,196 +1,273 @@
-//! A play of a Trick of Contract Whist, consisting of asking players for their plays.
+//! A play of a Trick of Contract Whist, consisting of collecting each player's play.
 //!
-//! The Trick progresses through four states. The first is the Start state, where player
-//! hands, the player order, and the trump card are passed into the struct. This is
-//! initiated by calling the `new()` function on the Trick struct. This state is used to
-//! create a Playing state. The Playing state is used to ask each player, in order, for
-//! their plays. Once all plays are collected, the trick enters the Scoring state to
-//! determine the winner. After scoring, the trick comes to rest in the Finished state,
-//! which is used to get the trick winner.
+//! The Trick progresses through three states. A Trick is started by calling the
+//! `new()` function on the Trick struct with the trump card and the order the
+//! players play in, which returns the Playing state. The Playing state collects
+//! the [Card] played by each player, in order. Once all plays are collected, the
+//! trick enters the Scoring state to determine the winner. After scoring, the
+//! trick comes to rest as a finished [Trick], which is used to get the trick
+//! winner and the cards played.
+//!
+//! Players are referenced by their [PlayerId], so every state owns its data and
+//! may be stored or sent between threads.
 //!
 //! # States
-//! [Playing]: Asks each player in order for their [Card]s. <br>
+//! [Playing]: Collects each player's [Card] in order. <br>
 //! [Scoring]: Determines the winner of the [Trick] based on the Trump and Led suit. <br>
 //!
 //! # Todo
 //! - [ ] Update documentation <br>
 
-use std::collections::HashMap;
+use std::cmp::Ordering;
 
 use crate::card::Card;
-use crate::player::Player;
+use crate::player::{Player, PlayerId};
 use crate::suit::Suit;
-use crate::PlayerHands;
 
-/// Trick struct for using Generic Type Parameters.
-pub struct Trick<'a> {
-    winner: &'a Box<dyn Player>,
+/// A finished Trick, holding the cards played and the winner.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trick {
+    trump_card: Card,
+    cards_played: Vec<(PlayerId, Card)>,
+    winner: PlayerId,
 }
 
 /// Struct to carry a Trick from start to finish.
 ///
 /// Only stores the data needed to take it through the playing states.
+#[derive(Debug, Clone)]
 pub struct InProgressTrick<T: TrickState> {
     extra: T,
 }
 
 /// State of the [Trick] while being played.
 ///
-/// Asks [Player]s for their [Card] and moves the [Trick] to the [Scoring] state.
-///
-/// The `'a`, `'b`, and `'c` lifetimes are used to help the compiler with lifetimes.
-/// Lifetime `'a` is the longest, and is used for the reference to a [Player], which
-/// are instantiated when the [crate::game::ContractWhistGame] is created. Lifetime `'b`
-/// is used for a reference to a trump card, which is owned by the [crate::hand::Hand]
-/// that is playing the [Trick]. Lifetime `'c'` is used to denote the lifetime of the
-/// `player_hands` borrow which is also owned by the [crate::hand::Hand] playing the
-/// [Trick]. `player_hands` is borrowed mutably to allow for the hand to change between
-/// plays of the [Trick].  
-pub struct Playing<'a, 'b, 'c>
-where
-    'a: 'b,
-    'b: 'c,
-{
-    players: Vec<&'a Box<dyn Player>>,
-    trump_card: &'b Card,
-    player_hands: &'c mut PlayerHands<'a>,
+/// Collects the [Card] of each [Player] in turn and moves the [Trick] to the
+/// [Scoring] state.
+#[derive(Debug, Clone)]
+pub struct Playing {
+    player_order: Vec<PlayerId>,
+    trump_card: Card,
+    cards_played: Vec<(PlayerId, Card)>,
 }
 
 /// State of the [Trick] while determing the winner.
 ///
 /// Determines the winner of the [Trick] based on the Trump and Led suit.
-///
-/// See [Playing] for a discussion on the lifetimes.
-pub struct Scoring<'a, 'b>
-where
-    'a: 'b,
-{
-    cards_played: HashMap<&'a Box<dyn Player>, Card>,
-    trump_card: &'b Card,
-    players: Vec<&'a Box<dyn Player>>,
+#[derive(Debug, Clone)]
+pub struct Scoring {
+    cards_played: Vec<(PlayerId, Card)>,
+    trump_card: Card,
 }
 
 pub trait TrickState {}
-impl<'a, 'b, 'c> TrickState for Playing<'a, 'b, 'c> {}
-impl<'a, 'b, 'c> TrickState for Scoring<'a, 'b> {}
+impl TrickState for Playing {}
+impl TrickState for Scoring {}
 
-impl<'a, 'b, 'c> Trick<'a> {
+impl Trick {
     /// Creates a new [Trick] and returns the [Playing] state.
+    ///
+    /// `player_order` is the order the players play in, starting with the lead player.
     #[allow(clippy::new_ret_no_self)]
-    pub fn new(
-        trump_card: &'b Card,
-        players: Vec<&'a Box<dyn Player>>,
-        player_hands: &'c mut PlayerHands<'a>,
-    ) -> InProgressTrick<Playing<'a, 'b, 'c>> {
+    pub fn new(trump_card: Card, player_order: Vec<PlayerId>) -> InProgressTrick<Playing> {
         InProgressTrick {
             extra: Playing {
+                cards_played: Vec::with_capacity(player_order.len()),
+                player_order,
                 trump_card,
-                player_hands,
-                players,
             },
         }
     }
 
     /// Returns the winner of the [Trick].
-    pub fn get_winner(&self) -> &'a Box<dyn Player> {
+    pub fn get_winner(&self) -> PlayerId {
         self.winner
     }
 
-    pub fn display_trick(&self) {
+    /// Returns the trump card the [Trick] was played with.
+    pub fn get_trump(&self) -> Card {
+        self.trump_card
+    }
+
+    /// Returns the cards played, in the order they were played.
+    pub fn get_cards_played(&self) -> &[(PlayerId, Card)] {
+        &self.cards_played
+    }
+
+    /// Returns the card played by the lead player.
+    pub fn get_led_card(&self) -> Card {
+        self.cards_played.first().unwrap().1
+    }
+
+    pub fn display_trick(&self, players: &[Box<dyn Player>]) {
         println!();
-        println!("{} is the winner!", self.winner);
+        println!("{} is the winner!", players[self.winner.seat()]);
     }
 }
 
-impl<'a, 'b, 'c> InProgressTrick<Playing<'a, 'b, 'c>> {
-    /// Asks [Player]s for their [Card]s and returns the [Scoring] state.
-    pub fn play_trick(self) -> InProgressTrick<Scoring<'a, 'b>> {
-        let player_hands = self.extra.player_hands;
-        let players = self.extra.players;
-        let trump_card: &'b Card = self.extra.trump_card;
-
-        let mut cards_played: HashMap<&Box<dyn Player>, Card> =
-            HashMap::with_capacity(players.len());
-
-        for player in &players {
-            let card: Card;
-            let new_hand: Vec<Card>;
-            if cards_played.is_empty() {
-                let player_hand = player_hands.get(*player).unwrap().to_owned();
-                (card, new_hand) = player.play_card(self.extra.trump_card, None, player_hand);
-            } else {
-                let first_player = players.first().unwrap();
-                let first_card = cards_played.get(first_player).unwrap();
-                let player_hand = player_hands.get(player).unwrap().to_owned();
-                (card, new_hand) =
-                    player.play_card(self.extra.trump_card, Some(first_card), player_hand);
+impl InProgressTrick<Playing> {
+    /// Returns the player whose turn it is to play, or None if all players have played.
+    pub fn next_player(&self) -> Option<PlayerId> {
+        self.extra
+            .player_order
+            .get(self.extra.cards_played.len())
+            .copied()
+    }
+
+    /// Returns the card played by the lead player, if they have played.
+    pub fn get_led_card(&self) -> Option<&Card> {
+        self.extra.cards_played.first().map(|e| &e.1)
+    }
+
+    /// Returns the cards played so far, in the order they were played.
+    pub fn get_cards_played(&self) -> &[(PlayerId, Card)] {
+        &self.extra.cards_played
+    }
+
+    /// Returns the order the players play in, starting with the lead player.
+    pub fn get_player_order(&self) -> &[PlayerId] {
+        &self.extra.player_order
+    }
+
+    /// Adds the player's [Card] to the [Trick].
+    ///
+    /// Returns false, without playing the card, if it is not the player's turn.
+    /// Checking that the card may be played is left to the [crate::hand::Hand],
+    /// which holds the player's cards.
+    pub fn play_card(&mut self, player: PlayerId, card: Card) -> bool {
+        match self.next_player() {
+            Some(next) if next == player => {
+                self.extra.cards_played.push((player, card));
+                true
             }
-            println!("{} played the {}", player, &card);
-            cards_played.insert(player, card);
-            player_hands.insert(player, new_hand);
+            _ => false,
         }
+    }
 
-        // for (player, card) in cards_played.iter() {
-        //     println!("{} played the {}", player, card);
-        // }
-
-        InProgressTrick {
-            extra: Scoring {
-                cards_played,
-                players,
-                trump_card,
-            },
+    /// Returns the [Scoring] state once every player has played.
+    ///
+    /// Returns the trick unchanged if a player has yet to play.
+    pub fn finish(self) -> Result<InProgressTrick<Scoring>, Self> {
+        match self.next_player() {
+            Some(_) => Err(self),
+            None => Ok(InProgressTrick {
+                extra: Scoring {
+                    cards_played: self.extra.cards_played,
+                    trump_card: self.extra.trump_card,
+                },
+            }),
         }
     }
 }
 
-impl<'a, 'b, 'c> InProgressTrick<Scoring<'a, 'b>> {
-    /// Determines the winner and returns the [Finished] state.
-    pub fn determine_winner(self) -> Trick<'a> {
-        let players = self.extra.players;
+impl InProgressTrick<Scoring> {
+    /// Determines the winner and returns the finished [Trick].
+    pub fn determine_winner(self) -> Trick {
         let cards_played = self.extra.cards_played;
+        let trump_card = self.extra.trump_card;
 
-        // Set up the trump and led suit
-        let (_, trump_suit) = self.extra.trump_card.get_value();
-        let lead_player = players.get(0).unwrap();
-        let (_, led_suit) = cards_played.get(lead_player).unwrap().get_value();
-
-        // Assign point values to the trump and led suit for ease of comparison
-        let tuples = [(trump_suit, 3), (led_suit, 2)];
-        let points: HashMap<&Suit, i32> = tuples.into_iter().collect();
-        let create_sortable_tuples =
-            |e: (&&'a Box<dyn Player>, &Card)| -> (i32, i32, &'a Box<dyn Player>) {
-                let (rank, suit) = e.1.get_value();
-                let suit_value = points.get(suit).unwrap_or(&1);
-
-                (*suit_value, rank.get_numerical_rank(true), e.0)
-            };
-        // Create the card mapping and sort them by suit then rank
-        // where trump suit > led suit > others
-        let mut cards: Vec<(i32, i32, &'a Box<dyn Player>)> =
-            cards_played.iter().map(create_sortable_tuples).collect();
-        let sort_tuples = |a: &(i32, i32, &'a Box<dyn Player>),
-                           b: &(i32, i32, &'a Box<dyn Player>)| {
-            let (a_suit, a_rank, _) = a;
-            let (b_suit, b_rank, _) = b;
-            let new_a = (a_suit, a_rank);
-            let new_b = (b_suit, b_rank);
-
-            new_b.cmp(&new_a)
-        };
-        cards.sort_by(sort_tuples);
-
-        // Set the winner and return the new state
-        let winner = cards.first().unwrap().2;
-
-        Trick { winner }
+        let led_card = cards_played.first().unwrap().1;
+        let winner = TrickComparator::new(trump_card.suit(), led_card.suit())
+            .winner(&cards_played)
+            .unwrap();
+
+        Trick {
+            trump_card,
+            cards_played,
+            winner,
+        }
+    }
+}
+
+/// Compares the cards played to a trick, given the trump and led suits.
+///
+/// A trump beats any card that is not a trump, a card in the led suit beats any
+/// card in another suit that is not a trump, and within a suit the higher rank
+/// wins. Cards in any other suit can not win the trick, so are all equal.
+///
+/// # Examples
+/// ```
+/// use contract_whist::{Card, Rank, Suit};
+/// use contract_whist::trick::TrickComparator;
+///
+/// let comparator = TrickComparator::new(Suit::Hearts, Suit::Clubs);
+/// let two_of_hearts = Card::new(Rank::Two, Suit::Hearts);
+/// let ace_of_clubs = Card::new(Rank::Ace, Suit::Clubs);
+///
+/// assert!(comparator.beats(&two_of_hearts, &ace_of_clubs));
+/// assert!(!comparator.beats(&Card::new(Rank::Ace, Suit::Spades), &ace_of_clubs));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrickComparator {
+    trump: Suit,
+    led: Suit,
+    aces_high: bool,
+}
+
+impl TrickComparator {
+    /// Creates a comparator for a trick with the given trump and led suits, with
+    /// aces high.
+    pub fn new(trump: Suit, led: Suit) -> TrickComparator {
+        TrickComparator {
+            trump,
+            led,
+            aces_high: true,
+        }
+    }
+
+    /// Sets whether aces rank above kings or below twos.
+    pub fn aces_high(self, aces_high: bool) -> TrickComparator {
+        TrickComparator { aces_high, ..self }
+    }
+
+    /// Returns the trump suit.
+    pub fn get_trump(&self) -> Suit {
+        self.trump
+    }
+
+    /// Returns the led suit.
+    pub fn get_led(&self) -> Suit {
+        self.led
+    }
+
+    /// Returns the value of a card in the trick, where trumps beat the led suit and
+    /// cards in other suits have no value.
+    fn value(&self, card: &Card) -> (i32, i32) {
+        let rank = card.rank().get_numerical_rank(self.aces_high);
+        match card.suit() {
+            suit if suit == self.trump => (2, rank),
+            suit if suit == self.led => (1, rank),
+            _ => (0, 0),
+        }
+    }
+
+    /// Compares two cards by their strength in the trick.
+    pub fn compare(&self, a: &Card, b: &Card) -> Ordering {
+        self.value(a).cmp(&self.value(b))
+    }
+
+    /// Returns true if `card` beats `other` in the trick.
+    pub fn beats(&self, card: &Card, other: &Card) -> bool {
+        self.compare(card, other) == Ordering::Greater
+    }
+
+    /// Returns the player whose card wins the trick, given the cards played in
+    /// order, or None if no card has been played.
+    pub fn winner(&self, cards_played: &[(PlayerId, Card)]) -> Option<PlayerId> {
+        let mut cards = cards_played.iter();
+        let mut winner = cards.next()?;
+        for played in cards {
+            if self.beats(&played.1, &winner.1) {
+                winner = played;
+            }
+        }
+        Some(winner.0)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::player::AIPlayer;
     use crate::rank::Rank;
     use crate::suit::Suit;
 
@@ -198,23 +275,100 @@ mod tests {
         Card::new(Rank::Ace, Suit::Hearts)
     }
 
-    // #[test]
-    // fn creating_new_trick_returns_playing_status() {
-    //     let trump_card = setup_trump();
-    //     let players: Vec<&Box<dyn Player>> = vec![
-    //         &Box::new(AIPlayer::new("Mickey".to_string())),
-    //         &Box::new(AIPlayer::new("Minnie".to_string())),
-    //         &Box::new(AIPlayer::new("Donald".to_string())),
-    //         &Box::new(AIPlayer::new("Daffy".to_string())),
-    //     ];
-    //     let player_hands: PlayerHands = HashMap::with_capacity(4);
-
-    //     for (index, player) in players.iter().enumerate() {
-    //         player_hands.insert(
-    //             &player,
-    //             vec![Card::new(Rank::VALUES[index % 3], Suit::VALUES[index % 3])],
-    //         );
-    //     }
-    //     let trick = Trick::new(&trump_card, players, &mut player_hands);
-    // }
+    fn play_all(trick: &mut InProgressTrick<Playing>, cards: &[Card]) {
+        for card in cards {
+            let player = trick.next_player().unwrap();
+            assert!(trick.play_card(player, *card));
+        }
+    }
+
+    #[test]
+    fn highest_card_in_led_suit_wins_without_trump() {
+        let player_order = vec![PlayerId::new(0), PlayerId::new(1), PlayerId::new(2)];
+        let mut trick = Trick::new(setup_trump(), player_order);
+        play_all(
+            &mut trick,
+            &[
+                Card::new(Rank::Ten, Suit::Clubs),
+                Card::new(Rank::King, Suit::Clubs),
+                Card::new(Rank::Ace, Suit::Spades),
+            ],
+        );
+
+        let trick = trick.finish().unwrap().determine_winner();
+
+        assert_eq!(trick.get_winner(), PlayerId::new(1));
+    }
+
+    #[test]
+    fn trump_beats_led_suit() {
+        let player_order = vec![PlayerId::new(2), PlayerId::new(0), PlayerId::new(1)];
+        let mut trick = Trick::new(setup_trump(), player_order);
+        play_all(
+            &mut trick,
+            &[
+                Card::new(Rank::Ace, Suit::Clubs),
+                Card::new(Rank::Two, Suit::Hearts),
+                Card::new(Rank::King, Suit::Clubs),
+            ],
+        );
+
+        let trick = trick.finish().unwrap().determine_winner();
+
+        assert_eq!(trick.get_winner(), PlayerId::new(0));
+        assert_eq!(trick.get_led_card(), Card::new(Rank::Ace, Suit::Clubs));
+    }
+
+    #[test]
+    fn comparator_ranks_trump_then_led_suit() {
+        let comparator = TrickComparator::new(Suit::Hearts, Suit::Clubs);
+        let two_of_hearts = Card::new(Rank::Two, Suit::Hearts);
+        let ace_of_clubs = Card::new(Rank::Ace, Suit::Clubs);
+        let king_of_clubs = Card::new(Rank::King, Suit::Clubs);
+        let ace_of_spades = Card::new(Rank::Ace, Suit::Spades);
+
+        assert!(comparator.beats(&two_of_hearts, &ace_of_clubs));
+        assert!(comparator.beats(&ace_of_clubs, &king_of_clubs));
+        assert!(comparator.beats(&king_of_clubs, &ace_of_spades));
+        assert!(!comparator.beats(&ace_of_spades, &king_of_clubs));
+        assert_eq!(
+            comparator.compare(&ace_of_spades, &Card::new(Rank::Two, Suit::Diamonds)),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn comparator_may_rank_aces_low() {
+        let comparator = TrickComparator::new(Suit::Hearts, Suit::Clubs).aces_high(false);
+        let ace_of_clubs = Card::new(Rank::Ace, Suit::Clubs);
+        let two_of_clubs = Card::new(Rank::Two, Suit::Clubs);
+
+        assert!(comparator.beats(&two_of_clubs, &ace_of_clubs));
+        assert_eq!(
+            comparator.winner(&[
+                (PlayerId::new(0), ace_of_clubs),
+                (PlayerId::new(1), two_of_clubs)
+            ]),
+            Some(PlayerId::new(1))
+        );
+        assert_eq!(comparator.winner(&[]), None);
+    }
+
+    #[test]
+    fn player_may_not_play_out_of_turn() {
+        let player_order = vec![PlayerId::new(0), PlayerId::new(1)];
+        let mut trick = Trick::new(setup_trump(), player_order);
+
+        assert!(!trick.play_card(PlayerId::new(1), Card::new(Rank::Two, Suit::Clubs)));
+        assert_eq!(trick.next_player(), Some(PlayerId::new(0)));
+    }
+
+    #[test]
+    fn unfinished_trick_can_not_be_scored() {
+        let player_order = vec![PlayerId::new(0), PlayerId::new(1)];
+        let mut trick = Trick::new(setup_trump(), player_order);
+        play_all(&mut trick, &[Card::new(Rank::Two, Suit::Clubs)]);
+
+        assert!(trick.finish().is_err());
+    }
 }
@@ -0,0 +1,260 @@
+//! A read-only view of the public information in a game, given to [crate::player::Player]s
+//! with every decision.
+//!
+//! The [GameView] holds everything a player at the table could see: the trump card,
+//! the bids placed so far, the cards played to the current trick, the tricks already
+//! played this hand, the results of previous hands, and the points so far. It never
+//! holds another player's cards.
+//!
+//! Views are created by the [crate::hand::Hand] and [crate::game::GameState], but may
+//! also be built directly, which is useful when testing players.
+//!
+//! # Examples
+//! ```
+//! use contract_whist::{Card, GameView, PlayerId, Rank, Suit};
+//!
+//! let view = GameView::new(PlayerId::new(1), 3, Card::new(Rank::Two, Suit::Hearts), 5)
+//!     .bids(vec![(PlayerId::new(0), 2), (PlayerId::new(1), 1)])
+//!     .current_trick(vec![(PlayerId::new(0), Card::new(Rank::King, Suit::Clubs))]);
+//!
+//! assert_eq!(view.get_bid(PlayerId::new(0)), Some(2));
+//! assert_eq!(view.get_led_card(), Some(Card::new(Rank::King, Suit::Clubs)));
+//! assert_eq!(view.get_tricks_won(PlayerId::new(0)), 0);
+//! ```
+
+use std::collections::HashMap;
+
+use crate::card::Card;
+use crate::card_set::CardSet;
+use crate::hand::Hand;
+use crate::player::PlayerId;
+use crate::rules::Rules;
+use crate::suit::Suit;
+use crate::trick::Trick;
+
+/// The public information available to a player when making a decision.
+#[derive(Debug, Clone)]
+pub struct GameView {
+    player: PlayerId,
+    num_players: usize,
+    trump: Card,
+    num_tricks: usize,
+    dealer: PlayerId,
+    bids: Vec<(PlayerId, isize)>,
+    current_trick: Vec<(PlayerId, Card)>,
+    tricks: Vec<Trick>,
+    played_cards: CardSet,
+    previous_hands: Vec<Hand>,
+    totals: HashMap<PlayerId, isize>,
+    names: Vec<String>,
+    rules: Rules,
+}
+
+impl GameView {
+    /// Creates a view for `player` of a hand of `num_tricks` tricks with the given trump.
+    ///
+    /// The view starts with no bids, no cards played, and no previous hands, with
+    /// the first seat dealing and the default [Rules].
+    pub fn new(player: PlayerId, num_players: usize, trump: Card, num_tricks: usize) -> GameView {
+        GameView {
+            player,
+            num_players,
+            trump,
+            num_tricks,
+            dealer: PlayerId::new(0),
+            bids: Vec::new(),
+            current_trick: Vec::new(),
+            tricks: Vec::new(),
+            played_cards: CardSet::new(),
+            previous_hands: Vec::new(),
+            totals: HashMap::new(),
+            names: Vec::new(),
+            rules: Rules::default(),
+        }
+    }
+
+    /// Sets the player who dealt the hand.
+    pub fn dealer(self, dealer: PlayerId) -> GameView {
+        GameView { dealer, ..self }
+    }
+
+    /// Sets the bids placed so far, in the order they were placed.
+    pub fn bids(self, bids: Vec<(PlayerId, isize)>) -> GameView {
+        GameView { bids, ..self }
+    }
+
+    /// Sets the cards played to the current trick, in the order they were played.
+    pub fn current_trick(self, current_trick: Vec<(PlayerId, Card)>) -> GameView {
+        GameView {
+            current_trick,
+            ..self
+        }
+    }
+
+    /// Sets the tricks finished so far this hand.
+    pub fn tricks(self, tricks: Vec<Trick>) -> GameView {
+        GameView { tricks, ..self }
+    }
+
+    /// Sets every card played so far this hand, including the current trick.
+    pub fn played_cards(self, played_cards: CardSet) -> GameView {
+        GameView {
+            played_cards,
+            ..self
+        }
+    }
+
+    /// Sets the finished hands of the game and the total points so far.
+    pub fn history(self, previous_hands: Vec<Hand>, totals: HashMap<PlayerId, isize>) -> GameView {
+        GameView {
+            previous_hands,
+            totals,
+            ..self
+        }
+    }
+
+    /// Sets the names of the players, indexed by seat.
+    pub fn names(self, names: Vec<String>) -> GameView {
+        GameView { names, ..self }
+    }
+
+    /// Sets the rules the game is played with.
+    pub fn rules(self, rules: Rules) -> GameView {
+        GameView { rules, ..self }
+    }
+
+    /// Returns the player the view belongs to.
+    pub fn get_player(&self) -> PlayerId {
+        self.player
+    }
+
+    /// Returns the number of players in the game.
+    pub fn get_num_players(&self) -> usize {
+        self.num_players
+    }
+
+    /// Returns the trump card turned over for the hand.
+    pub fn get_trump(&self) -> Card {
+        self.trump
+    }
+
+    /// Returns the trump suit for the hand.
+    pub fn get_trump_suit(&self) -> Suit {
+        self.trump.suit()
+    }
+
+    /// Returns the number of tricks in the hand.
+    pub fn get_num_tricks(&self) -> usize {
+        self.num_tricks
+    }
+
+    /// Returns the player who dealt the hand.
+    pub fn get_dealer(&self) -> PlayerId {
+        self.dealer
+    }
+
+    /// Returns the bids placed so far, in the order they were placed.
+    pub fn get_bids(&self) -> &[(PlayerId, isize)] {
+        &self.bids
+    }
+
+    /// Returns the bid of a player, if they have bid.
+    pub fn get_bid(&self, player: PlayerId) -> Option<isize> {
+        self.bids
+            .iter()
+            .find(|(id, _)| *id == player)
+            .map(|(_, bid)| *bid)
+    }
+
+    /// Returns the cards played to the current trick, in the order they were played.
+    pub fn get_current_trick(&self) -> &[(PlayerId, Card)] {
+        &self.current_trick
+    }
+
+    /// Returns the card led to the current trick, if any.
+    pub fn get_led_card(&self) -> Option<Card> {
+        self.current_trick.first().map(|(_, card)| *card)
+    }
+
+    /// Returns the cards in `cards` that may be played to the current trick.
+    ///
+    /// See [Rules::legal_cards].
+    pub fn legal_cards(&self, cards: &[Card]) -> Vec<Card> {
+        self.rules.legal_cards(cards, &self.current_trick)
+    }
+
+    /// Returns the rules the game is played with.
+    pub fn get_rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    /// Returns the tricks finished so far this hand.
+    pub fn get_tricks(&self) -> &[Trick] {
+        &self.tricks
+    }
+
+    /// Returns every card played so far this hand, including the current trick.
+    pub fn get_played_cards(&self) -> CardSet {
+        self.played_cards
+    }
+
+    /// Returns the number of tricks a player has won so far this hand.
+    pub fn get_tricks_won(&self, player: PlayerId) -> isize {
+        self.tricks
+            .iter()
+            .filter(|e| e.get_winner() == player)
+            .count() as isize
+    }
+
+    /// Returns the finished hands of the game, in the order they were played.
+    pub fn get_previous_hands(&self) -> &[Hand] {
+        &self.previous_hands
+    }
+
+    /// Returns the total points of a player from the finished hands.
+    pub fn get_total(&self, player: PlayerId) -> isize {
+        *self.totals.get(&player).unwrap_or(&0)
+    }
+
+    /// Returns the name of a player, or their seat if names are not known.
+    pub fn get_name(&self, player: PlayerId) -> String {
+        match self.names.get(player.seat()) {
+            Some(name) => name.to_string(),
+            None => player.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rank::Rank;
+
+    fn setup() -> GameView {
+        GameView::new(PlayerId::new(0), 2, Card::new(Rank::Two, Suit::Hearts), 3)
+    }
+
+    #[test]
+    fn tricks_won_are_counted_from_finished_tricks() {
+        let mut trick = Trick::new(
+            setup().get_trump(),
+            vec![PlayerId::new(0), PlayerId::new(1)],
+        );
+        trick.play_card(PlayerId::new(0), Card::new(Rank::Ace, Suit::Clubs));
+        trick.play_card(PlayerId::new(1), Card::new(Rank::Two, Suit::Clubs));
+        let trick = trick.finish().unwrap().determine_winner();
+
+        let view = setup().tricks(vec![trick]);
+
+        assert_eq!(view.get_tricks_won(PlayerId::new(0)), 1);
+        assert_eq!(view.get_tricks_won(PlayerId::new(1)), 0);
+    }
+
+    #[test]
+    fn names_fall_back_to_seat() {
+        let view = setup().names(vec!["Mickey".to_string()]);
+
+        assert_eq!(view.get_name(PlayerId::new(0)), "Mickey");
+        assert_eq!(view.get_name(PlayerId::new(1)), "Seat 1");
+    }
+}